@@ -1 +1 @@
-cargo build-bpf && solana program deploy -u https://api.devnet.solana.com --upgrade-authority ./divvy.json target/deploy/divvybetting.so
+cargo build-bpf && solana program deploy -u https://api.devnet.solana.com --upgrade-authority ./divvy.json ../target/deploy/divvybetting.so
@@ -1,18 +1,15 @@
-use std::mem::size_of;
-
-use solana_program::{account_info::{next_account_info, AccountInfo}, entrypoint::ProgramResult, instruction::{AccountMeta, Instruction}, msg, program::{invoke, invoke_signed}, program_error::ProgramError, program_pack::Pack, pubkey::Pubkey, rent::Rent, sysvar::Sysvar};
+use solana_program::{account_info::{next_account_info, AccountInfo}, clock::Clock, entrypoint::ProgramResult, msg, program::{invoke, invoke_signed}, program_error::ProgramError, program_pack::Pack, pubkey::Pubkey, rent::Rent, sysvar::Sysvar};
 
 use spl_token::{
     instruction::{transfer},
     state::Account as TokenAccount,
 };
 
-//Switchboard dependencies
-use switchboard_program::{get_aggregator, get_aggregator_result, AggregatorState, RoundResult};
+use divvyhouse::{instruction::{return_liquidity, transfer_locked_liquidity}, state::HpLiquidity};
 
-use crate::{calculate_available_liquidity, calculate_locked_liquidity, calculate_payout, error::ExchangeError, instruction::ExchangeInstruction, schema::{authority, divvy_house_program_id, token_program_id}, state::{Bet, BetType, BettingPoolState, Market, MarketSide, MoneylineMarketOutcome}};
+use divvycommon::{authority::{init_multisig, validate_authority, validate_role, Role, Roles}, calculate_locked_liquidity, calculate_min_payout, calculate_points_bettor_balance, fees::{check_fees, split_house_profit, BPS, DEFAULT_DIVVY_FOUNDATION_FEE_BPS, DEFAULT_INSURANCE_FUND_FEE_BPS}, odds::payout_from_american, pda::{create_pda_account, find_bet_address, find_market_address, find_position_address, BET_SEED, MARKET_SEED, POSITION_SEED}, points::{grade, handicap, Grade}};
 
-use fixed::types::U64F64;
+use crate::{calculate_available_liquidity, calculate_pool_size, error::ExchangeError, instruction::ExchangeInstruction, schema::{authority, divvy_house_program_id, token_program_id}, state::{Bet, BetType, BettingPoolState, ExposureLimits, ManualFeed, Market, MarketSide, MoneylineMarketOutcome, OracleSource, Position, LABEL_LEN, LEAGUE_LEN}};
 
 pub struct Processor;
 impl Processor {
@@ -28,17 +25,47 @@ impl Processor {
                 risk,
                 odds,
                 market_side,
+                odds_tolerance_bps,
+                nonce,
             } => {
                 msg!("Divvy - Init Bet");
-                Self::process_init_bet(accounts, risk, odds, market_side, program_id)
+                Self::process_init_bet(
+                    accounts,
+                    risk,
+                    odds,
+                    market_side,
+                    odds_tolerance_bps,
+                    nonce,
+                    program_id,
+                )
             }
             ExchangeInstruction::SettleBet { bump_seed } => {
                 msg!("Divvy - Settle");
                 Self::process_settle_bet(accounts, bump_seed, program_id)
             }
-            ExchangeInstruction::InitMoneylineMarket { bet_type } => {
+            ExchangeInstruction::InitMoneylineMarket {
+                event_id,
+                bet_type,
+                oracle_source,
+                max_staleness,
+                min_responses,
+                league,
+                start_time,
+                side_labels,
+            } => {
                 msg!("Divvy - Init Moneyline Market");
-                Self::process_init_moneyline_market(accounts, program_id, bet_type)
+                Self::process_init_moneyline_market(
+                    accounts,
+                    program_id,
+                    event_id,
+                    bet_type,
+                    oracle_source,
+                    max_staleness,
+                    min_responses,
+                    league,
+                    start_time,
+                    side_labels,
+                )
             }
             ExchangeInstruction::SettleMoneylineMarket { bump_seed } => {
                 msg!("Divvy - Settle Moneyline Market");
@@ -58,14 +85,71 @@ impl Processor {
                 msg!("Divvy - Freeze");
                 Self::process_freeze(accounts, program_id, freeze_betting)
             }
+            ExchangeInstruction::UpdateManualFeed { value, expo } => {
+                msg!("Divvy - Update Manual Feed");
+                Self::process_update_manual_feed(accounts, program_id, value, expo)
+            }
+            ExchangeInstruction::UpdateFees {
+                insurance_fund_fee_bps,
+                divvy_foundation_fee_bps,
+            } => {
+                msg!("Divvy - Update Fees");
+                Self::process_update_fees(accounts, program_id, insurance_fund_fee_bps, divvy_foundation_fee_bps)
+            }
+            ExchangeInstruction::ProposeAuthority { new_authority } => {
+                msg!("Divvy - Propose Authority");
+                Self::process_propose_authority(accounts, program_id, new_authority)
+            }
+            ExchangeInstruction::AcceptAuthority => {
+                msg!("Divvy - Accept Authority");
+                Self::process_accept_authority(accounts, program_id)
+            }
+            ExchangeInstruction::InitMultisig { m } => {
+                msg!("Divvy - Init Multisig");
+                Self::process_init_multisig(accounts, program_id, m)
+            }
+            ExchangeInstruction::GrantRole { role, member } => {
+                msg!("Divvy - Grant Role");
+                Self::process_grant_role(accounts, program_id, role, member)
+            }
+            ExchangeInstruction::RevokeRole { role, member } => {
+                msg!("Divvy - Revoke Role");
+                Self::process_revoke_role(accounts, program_id, role, member)
+            }
+            ExchangeInstruction::UpdateBetCutoff { bet_cutoff_secs } => {
+                msg!("Divvy - Update Bet Cutoff");
+                Self::process_update_bet_cutoff(accounts, program_id, bet_cutoff_secs)
+            }
+            ExchangeInstruction::SettleBets { bump_seed } => {
+                msg!("Divvy - Settle Bets");
+                Self::process_settle_bets(accounts, bump_seed, program_id)
+            }
+            ExchangeInstruction::UpdateCrankReward { crank_reward_lamports } => {
+                msg!("Divvy - Update Crank Reward");
+                Self::process_update_crank_reward(accounts, program_id, crank_reward_lamports)
+            }
+            ExchangeInstruction::VoidMarket { bump_seed } => {
+                msg!("Divvy - Void Market");
+                Self::process_void_market(accounts, program_id, bump_seed)
+            }
+            ExchangeInstruction::SuspendMarket { suspended } => {
+                msg!("Divvy - Suspend Market");
+                Self::process_suspend_market(accounts, program_id, suspended)
+            }
+            ExchangeInstruction::UpdateExposureLimits { exposure_limits } => {
+                msg!("Divvy - Update Exposure Limits");
+                Self::process_update_exposure_limits(accounts, program_id, exposure_limits)
+            }
         }
     }
 
     fn process_init_bet(
         accounts: &[AccountInfo],
         risk: u64,
-        _odds: u64,
+        odds: i64,
         market_side: u8,
+        odds_tolerance_bps: u16,
+        nonce: u64,
         program_id: &Pubkey,
     ) -> ProgramResult {
         msg!("- Risk");
@@ -78,12 +162,15 @@ impl Processor {
         }
         let feed_account = next_account_info(accounts_iter)?;
         let bet_account = next_account_info(accounts_iter)?;
+        let position_account = next_account_info(accounts_iter)?;
         let market_state_account = next_account_info(accounts_iter)?;
         let bet_pool_state_account = next_account_info(accounts_iter)?;
         let hp_usdt_account = next_account_info(accounts_iter)?;
+        let hp_state_account = next_account_info(accounts_iter)?;
         let bet_usdt_account = next_account_info(accounts_iter)?;
         let user_usdt_account = next_account_info(accounts_iter)?;
         let token_program = next_account_info(accounts_iter)?;
+        let system_program = next_account_info(accounts_iter)?;
 
         msg!("Validating accounts");
         //Checking if market is initialized
@@ -91,19 +178,39 @@ impl Processor {
             .map_err(|_| Into::<ProgramError>::into(ExchangeError::MarketNotInitialized))?;
         let mut pool_state = BettingPoolState::unpack(&bet_pool_state_account.data.borrow())
             .map_err(|_| Into::<ProgramError>::into(ExchangeError::BettingPoolStateNotInitialized))?;
-        let mut bet_state = Bet::unpack_unchecked(&bet_account.data.borrow())?;
-        if bet_state.is_initialized {
+        //Checking the bet account is the bettor's bet with this nonce
+        let (bet_address, bet_bump_seed) = find_bet_address(program_id, market_state_account.key, initializer.key, nonce);
+        if *bet_account.key != bet_address {
+            return Err(ExchangeError::InvalidBetAccount.into());
+        }
+        if bet_account.data_len() != 0 {
             return Err(ExchangeError::BetAlreadyInitialized.into());
         }
-        if !Rent::get()?.is_exempt(**bet_account.lamports.borrow(), bet_account.data_len()) {
-            return Err(ProgramError::AccountNotRentExempt);
+        //Checking the position account is the bettor's position on this market
+        let (position_address, position_bump_seed) = find_position_address(program_id, market_state_account.key, initializer.key);
+        if *position_account.key != position_address {
+            return Err(ExchangeError::InvalidPositionAccount.into());
         }
+        let new_position = position_account.data_len() == 0;
+        let mut position = if new_position {
+            Position {
+                is_initialized: true,
+                market: *market_state_account.key,
+                user_main_account: *initializer.key,
+                risk: [0; 3],
+                payout: [0; 3],
+            }
+        } else {
+            Position::unpack(&position_account.data.borrow())?
+        };
         let hp_usdt_state = TokenAccount::unpack(&hp_usdt_account.data.borrow())?;
 
         // Checking house pool usdt account
         if *hp_usdt_account.key != pool_state.house_pool_usdt {
-            return Err(ExchangeError::InvalidHousePoolUsdtAccount.into());
+            return Err(ExchangeError::InvalidPoolUsdtAccount.into());
         }
+        // Checking house pool state account
+        let hp_state = Self::unpack_house_pool_state(&pool_state, hp_state_account)?;
 
         // Checking bet pool usdt account
         if *bet_usdt_account.key != pool_state.betting_pool_usdt {
@@ -120,10 +227,19 @@ impl Processor {
         if pool_state.frozen_betting {
             return Err(ExchangeError::BettingFrozen.into());
         }
+        //Checking if the market is suspended
+        if market_state.suspended {
+            return Err(ExchangeError::MarketSuspended.into());
+        }
         //Checking if market is not commenced or settled yet
         if market_state.result != MoneylineMarketOutcome::NotYetCommenced {
             return Err(ExchangeError::MarketCommenced.into());
         }
+        //Checking if betting on the market has closed
+        let clock = Clock::get()?;
+        if clock.unix_timestamp >= market_state.bet_cutoff(pool_state.bet_cutoff_secs) {
+            return Err(ExchangeError::BettingClosed.into());
+        }
         //Checking if feed account is right
         if market_state.market_sides[market_side as usize]
             .odds_feed_account
@@ -137,16 +253,19 @@ impl Processor {
         if risk == 0 {
             return Err(ExchangeError::BetRiskZero.into());
         }
+        //Checking the odds tolerance is at most the whole quoted payout
+        if odds_tolerance_bps as u64 > BPS {
+            return Err(ExchangeError::InvalidOddsTolerance.into());
+        }
+        //Checking the risk against the bet limits
+        pool_state.exposure_limits.check_bet_risk(risk)?;
 
-        let available_liquidty = calculate_available_liquidity(&hp_usdt_state, &pool_state)?;
+        let available_liquidty = calculate_available_liquidity(&hp_usdt_state, &hp_state, &pool_state)?;
+        let pool_size = calculate_pool_size(&hp_usdt_state, &hp_state, &pool_state)?;
 
-        //Getting odds from the Switchboard
-        let aggregator: AggregatorState = get_aggregator(feed_account)?;
-        let round_result: RoundResult = get_aggregator_result(&aggregator)?;
-        let feed_odds = round_result
-            .result
-            .ok_or(ExchangeError::FeedNotInitialized)?;
-        if feed_odds >= 0f64 {
+        //Getting odds from the market oracle
+        let feed_odds = market_state.read_odds(program_id, feed_account, clock.unix_timestamp)?;
+        if feed_odds >= 0 {
             msg!("- Odds from feed: Positive:");
             msg!(0, 0, 0, 0, feed_odds as u64);
         } else {
@@ -154,13 +273,33 @@ impl Processor {
             msg!(0, 0, 0, 0, -feed_odds as u64);
         }
 
-        //TODO comparison of provided odds & feed odds.
+        //Snapshotting the line of spread and total bets from the side's points feed
+        let line = match market_state.bet_type {
+            BetType::MoneyLine => 0,
+            BetType::Spread | BetType::Total => {
+                let points_feed_account = next_account_info(accounts_iter)?;
+                if market_state.market_sides[market_side as usize]
+                    .points_feed_account
+                    .ok_or(ExchangeError::InvalidInstruction)?
+                    != *points_feed_account.key
+                {
+                    return Err(ExchangeError::InvalidFeedAccount.into());
+                }
+                market_state.read_points(program_id, points_feed_account, clock.unix_timestamp)?
+            }
+        };
 
         //Calculate payout
-        let payout = calculate_payout(feed_odds, risk).ok_or(ExchangeError::InvalidInstruction)?;
+        let payout = payout_from_american(feed_odds, risk).ok_or(ExchangeError::InvalidOdds)?;
         msg!("- Bet payout");
         msg!(0, 0, 0, 0, payout);
 
+        //Checking the feed odds against the odds quoted to the bettor
+        let quoted_payout = payout_from_american(odds, risk).ok_or(ExchangeError::InvalidOdds)?;
+        if payout < calculate_min_payout(quoted_payout, odds_tolerance_bps)? {
+            return Err(ExchangeError::OddsSlippageExceeded.into());
+        }
+
         // Payout coming out as zero, throw error
         if payout == 0u64  {
             return Err(ExchangeError::PayoutZero.into());
@@ -181,12 +320,32 @@ impl Processor {
         //Add risk & payout in market side
         let current_market_side_risk = market_state.market_sides[market_side as usize].risk;
         let current_market_side_payout = market_state.market_sides[market_side as usize].payout;
+        //Keep the side's most generous line, the locked liquidity assumes all its bets are at it
+        if market_state.bet_type != BetType::MoneyLine
+            && (current_market_side_risk == 0
+                || handicap(market_state.bet_type, market_side, line)
+                    > handicap(
+                        market_state.bet_type,
+                        market_side,
+                        market_state.market_sides[market_side as usize].line,
+                    ))
+        {
+            market_state.market_sides[market_side as usize].line = line;
+        }
         market_state.market_sides[market_side as usize].risk = current_market_side_risk
             .checked_add(risk)
             .ok_or(ExchangeError::AmountOverflow)?;
         market_state.market_sides[market_side as usize].payout = current_market_side_payout
             .checked_add(payout)
             .ok_or(ExchangeError::AmountOverflow)?;
+        pool_state
+            .exposure_limits
+            .check_side_payout(market_state.market_sides[market_side as usize].payout)?;
+        //Adding the bet to the bettor's position on the market
+        position.add_bet(market_side, risk, payout)?;
+        pool_state
+            .exposure_limits
+            .check_position(&position, market_side)?;
 
         //Calculating locked liquidity
         let new_locked_liquidity = calculate_locked_liquidity(&market_state)?;
@@ -202,6 +361,10 @@ impl Processor {
             {
                 return Err(ExchangeError::NotEnoughAvailableLiquidityForBet.into());
             }
+            //Checking the market stays within its share of the pool
+            pool_state
+                .exposure_limits
+                .check_market_exposure(new_locked_liquidity, pool_size)?;
         }
 
         market_state.locked_liquidity = new_locked_liquidity;
@@ -240,8 +403,15 @@ impl Processor {
             ],
         )?;
 
+        msg!("Creating the bet account");
+        create_pda_account(program_id, initializer, bet_account, system_program, Bet::LEN, &[BET_SEED, market_state_account.key.as_ref(), initializer.key.as_ref(), &nonce.to_le_bytes(), &[bet_bump_seed]])?;
+        if new_position {
+            msg!("Creating the position account");
+            create_pda_account(program_id, initializer, position_account, system_program, Position::LEN, &[POSITION_SEED, market_state_account.key.as_ref(), initializer.key.as_ref(), &[position_bump_seed]])?;
+        }
+
         // Initialize bet state
-        bet_state = Bet {
+        let bet_state = Bet {
             is_initialized: true,
             market: *market_state_account.key,
             user_usdt_account: *user_usdt_account.key,
@@ -250,6 +420,7 @@ impl Processor {
             user_payout: payout,
             user_market_side: market_side,
             outcome: 0, //Outcome 0 as market not settled.
+            line,
         };
 
         // Increment bettor balance
@@ -257,6 +428,7 @@ impl Processor {
 
         // Write the accounts
         Bet::pack(bet_state, &mut bet_account.data.borrow_mut())?;
+        Position::pack(position, &mut position_account.data.borrow_mut())?;
         BettingPoolState::pack(pool_state, &mut bet_pool_state_account.data.borrow_mut())?;
         Market::pack(market_state, &mut market_state_account.data.borrow_mut())?;
 
@@ -269,7 +441,7 @@ impl Processor {
         program_id: &Pubkey,
     ) -> ProgramResult {
         let accounts_iter = &mut accounts.iter();
-        let _initializer = next_account_info(accounts_iter)?;
+        let initializer = next_account_info(accounts_iter)?;
         let token_program = next_account_info(accounts_iter)?;
         let market_state_account = next_account_info(accounts_iter)?;
         let bet_state_account = next_account_info(accounts_iter)?;
@@ -278,15 +450,112 @@ impl Processor {
         let user_usdt_account = next_account_info(accounts_iter)?;
         let user_main_account = next_account_info(accounts_iter)?;
         let bet_pool_state_account = next_account_info(accounts_iter)?;
+        let hp_usdt_account = next_account_info(accounts_iter)?;
+        let hp_state_account = next_account_info(accounts_iter)?;
+        let divvy_hp_program = next_account_info(accounts_iter)?;
+        let ledger_account = next_account_info(accounts_iter)?;
+
+        Self::settle_bets(
+            program_id,
+            bump_seed,
+            initializer,
+            token_program,
+            market_state_account,
+            pda_account,
+            bet_usdt_account,
+            bet_pool_state_account,
+            hp_usdt_account,
+            hp_state_account,
+            divvy_hp_program,
+            ledger_account,
+            &[[bet_state_account.clone(), user_usdt_account.clone(), user_main_account.clone()]],
+            false,
+        )
+    }
+
+    fn process_settle_bets(
+        accounts: &[AccountInfo],
+        bump_seed: u8,
+        program_id: &Pubkey,
+    ) -> ProgramResult {
+        let accounts_iter = &mut accounts.iter();
+        let keeper = next_account_info(accounts_iter)?;
+        let token_program = next_account_info(accounts_iter)?;
+        let market_state_account = next_account_info(accounts_iter)?;
+        let pda_account = next_account_info(accounts_iter)?;
+        let bet_usdt_account = next_account_info(accounts_iter)?;
+        let bet_pool_state_account = next_account_info(accounts_iter)?;
+        let hp_usdt_account = next_account_info(accounts_iter)?;
+        let hp_state_account = next_account_info(accounts_iter)?;
+        let divvy_hp_program = next_account_info(accounts_iter)?;
+        let ledger_account = next_account_info(accounts_iter)?;
 
-        let mut pool_state = BettingPoolState::unpack(&bet_pool_state_account.data.borrow())?;
+        //Every bet comes with the user usdt and main accounts it pays out to
+        let bet_accounts = accounts_iter.as_slice().chunks_exact(3);
+        if !bet_accounts.remainder().is_empty() || bet_accounts.len() == 0 {
+            return Err(ExchangeError::InvalidInstruction.into());
+        }
+        let bets = bet_accounts
+            .map(|bet| [bet[0].clone(), bet[1].clone(), bet[2].clone()])
+            .collect::<Vec<_>>();
+        msg!("- Bets");
+        msg!(0, 0, 0, 0, bets.len() as u64);
+
+        Self::settle_bets(
+            program_id,
+            bump_seed,
+            keeper,
+            token_program,
+            market_state_account,
+            pda_account,
+            bet_usdt_account,
+            bet_pool_state_account,
+            hp_usdt_account,
+            hp_state_account,
+            divvy_hp_program,
+            ledger_account,
+            &bets,
+            true,
+        )
+    }
+
+    /// Settles `bets`, each the bet account followed by the user usdt and main
+    /// accounts it pays out to. With `crank_reward` the keeper gets the crank
+    /// reward out of the rent of each closed bet. What the market settlement
+    /// kept for bets graded below their side's line goes back to the house pool.
+    fn settle_bets<'a>(
+        program_id: &Pubkey,
+        bump_seed: u8,
+        keeper: &AccountInfo<'a>,
+        token_program: &AccountInfo<'a>,
+        market_state_account: &AccountInfo<'a>,
+        pda_account: &AccountInfo<'a>,
+        bet_usdt_account: &AccountInfo<'a>,
+        bet_pool_state_account: &AccountInfo<'a>,
+        hp_usdt_account: &AccountInfo<'a>,
+        hp_state_account: &AccountInfo<'a>,
+        divvy_hp_program: &AccountInfo<'a>,
+        ledger_account: &AccountInfo<'a>,
+        bets: &[[AccountInfo<'a>; 3]],
+        crank_reward: bool,
+    ) -> ProgramResult {
+        let mut pool_state = Self::unpack_pool_state(program_id, bet_pool_state_account)?;
         let mut market_state = Market::unpack(&market_state_account.data.borrow())?;
-        let mut bet_state = Bet::unpack(&bet_state_account.data.borrow())?;
 
         // Checking bet pool usdt account
         if *bet_usdt_account.key != pool_state.betting_pool_usdt {
             return Err(ExchangeError::InvalidBettingPoolUsdtAccount.into());
         }
+        // Checking house pool usdt account
+        if *hp_usdt_account.key != pool_state.house_pool_usdt {
+            return Err(ExchangeError::InvalidPoolUsdtAccount.into());
+        }
+        // Checking house pool state account
+        Self::unpack_house_pool_state(&pool_state, hp_state_account)?;
+        //Check house pool program ID
+        if *divvy_hp_program.key != divvy_house_program_id::ID {
+            return Err(ExchangeError::InvalidInstruction.into());
+        }
 
         if *market_state_account.owner != *program_id {
             return Err(ExchangeError::InvalidMarketAccount.into());
@@ -295,173 +564,258 @@ impl Processor {
             return Err(ExchangeError::InvalidInstruction.into());
         }
 
-        if bet_state.market != *market_state_account.key {
-            return Err(ExchangeError::ExpectedDataMismatch.into());
-        }
-
         //Checking if betting is frozen
         if pool_state.frozen_betting {
             return Err(ExchangeError::BettingFrozen.into());
         }
-        if market_state.result == MoneylineMarketOutcome::NotYetCommenced {
+        //Checking if the market is suspended
+        if market_state.suspended {
+            return Err(ExchangeError::MarketSuspended.into());
+        }
+        if market_state.result == MoneylineMarketOutcome::NotYetCommenced
+            || market_state.result == MoneylineMarketOutcome::Commenced
+        {
             return Err(ExchangeError::MarketNotSettled.into());
         }
 
-        if bet_state.user_usdt_account != *user_usdt_account.key {
-            return Err(ExchangeError::ExpectedDataMismatch.into());
-        }
+        //What the market settlement kept for bets that are paid less than it
+        let mut released_balance = 0u64;
+        for [bet_state_account, user_usdt_account, user_main_account] in bets.iter() {
+            if *bet_state_account.owner != *program_id {
+                return Err(ExchangeError::InvalidBetAccount.into());
+            }
+            let mut bet_state = Bet::unpack(&bet_state_account.data.borrow())?;
 
-        if bet_state.user_main_account != *user_main_account.key {
-            return Err(ExchangeError::ExpectedDataMismatch.into());
-        }
+            if bet_state.market != *market_state_account.key {
+                return Err(ExchangeError::ExpectedDataMismatch.into());
+            }
 
-        if bet_state.outcome != 0 {
-            return Err(ExchangeError::BetAlreadySettled.into());
-        }
+            if bet_state.user_usdt_account != *user_usdt_account.key {
+                return Err(ExchangeError::ExpectedDataMismatch.into());
+            }
 
-        // Decrement pending bets
-        msg!("Decrementing market pending bets.");
-        market_state.pending_bets = market_state
-            .pending_bets
-            .checked_sub(1)
-            .ok_or(ExchangeError::AmountOverflow)?;
+            if bet_state.user_main_account != *user_main_account.key {
+                return Err(ExchangeError::ExpectedDataMismatch.into());
+            }
 
-        msg!("Decrementing betting pool pending bets.");
-        msg!(" {} ",pool_state.pending_bets);
-        pool_state.pending_bets = pool_state
-            .pending_bets
-            .checked_sub(1)
-            .ok_or(ExchangeError::AmountOverflow)?;
+            if bet_state.outcome != 0 {
+                return Err(ExchangeError::BetAlreadySettled.into());
+            }
 
-        if bet_state.user_market_side != market_state.result.pack() {
-            bet_state.outcome = 2; //User have lost
-        } else {
-            bet_state.outcome = 1; //User have won
-            let bet_balance = bet_state
-                .user_risk
-                .checked_add(bet_state.user_payout)
+            // Decrement pending bets
+            msg!("Decrementing market pending bets.");
+            market_state.pending_bets = market_state
+                .pending_bets
+                .checked_sub(1)
+                .ok_or(ExchangeError::AmountOverflow)?;
+
+            msg!("Decrementing betting pool pending bets.");
+            pool_state.pending_bets = pool_state
+                .pending_bets
+                .checked_sub(1)
+                .ok_or(ExchangeError::AmountOverflow)?;
+
+            //Spread and total bets are graded at their own line, while the market settled
+            //as if every bet on the side was at the side's most generous line.
+            let (bet_grade, side_grade) = match market_state.bet_type {
+                //Every bet in a voided market gets its risk back
+                _ if market_state.result == MoneylineMarketOutcome::Void => (Grade::Push, Grade::Push),
+                BetType::MoneyLine => {
+                    let bet_grade = if bet_state.user_market_side == market_state.result.pack() {
+                        Grade::Win
+                    } else {
+                        Grade::Loss
+                    };
+                    (bet_grade, bet_grade)
+                }
+                BetType::Spread | BetType::Total => (
+                    grade(
+                        market_state.bet_type,
+                        bet_state.user_market_side,
+                        bet_state.line,
+                        market_state.home_score,
+                        market_state.away_score,
+                    ),
+                    grade(
+                        market_state.bet_type,
+                        bet_state.user_market_side,
+                        market_state.market_sides[bet_state.user_market_side as usize].line,
+                        market_state.home_score,
+                        market_state.away_score,
+                    ),
+                ),
+            };
+            bet_state.outcome = match bet_grade {
+                Grade::Win => 1, //User have won
+                Grade::Loss => 2, //User have lost
+                Grade::Push => 3, //Bet pushed, the risk is refunded
+            };
+            let bet_balance = bet_grade
+                .balance(bet_state.user_risk, bet_state.user_payout)
+                .ok_or(ExchangeError::AmountOverflow)?;
+            //What the market settlement kept in the bettor balance for this bet. Any of it
+            //the bet is not paid is released from the bettor balance.
+            let reserved_balance = side_grade
+                .balance(bet_state.user_risk, bet_state.user_payout)
                 .ok_or(ExchangeError::AmountOverflow)?;
 
-            // Subtract bettor balance in the market and house pool
-            // Only for winning bets, as when the market settles,
-            // the balance is changed to only include the winning sides risk and payout
-            market_state.bettor_balance = market_state
-                .bettor_balance
+            if reserved_balance > 0 {
+                // Subtract bettor balance in the market and house pool
+                // Only for winning and pushed sides, as when the market settles,
+                // the balance is changed to only include what those sides are owed
+                market_state.bettor_balance = market_state
+                    .bettor_balance
+                    .checked_sub(reserved_balance)
+                    .ok_or(ExchangeError::AmountOverflow)?;
+
+                //Remove risk & payout in market side. Only for winning and pushed sides, as locked
+                // liquidity was already calculated for losers.
+                let current_market_side_risk =
+                    market_state.market_sides[bet_state.user_market_side as usize].risk;
+                let current_market_side_payout =
+                    market_state.market_sides[bet_state.user_market_side as usize].payout;
+                market_state.market_sides[bet_state.user_market_side as usize].risk =
+                    current_market_side_risk
+                        .checked_sub(bet_state.user_risk)
+                        .ok_or(ExchangeError::MarketSideRiskUnderflow)?;
+                market_state.market_sides[bet_state.user_market_side as usize].payout =
+                    current_market_side_payout
+                        .checked_sub(bet_state.user_payout)
+                        .ok_or(ExchangeError::MarketSidePayoutUnderflow)?;
+            }
+            released_balance = reserved_balance
                 .checked_sub(bet_balance)
+                .and_then(|released| released_balance.checked_add(released))
                 .ok_or(ExchangeError::AmountOverflow)?;
 
-            //Remove risk & payout in market side. Only for winning bets, as locked
-            // liquidity was already calculated for losers.
-            let current_market_side_risk =
-                market_state.market_sides[bet_state.user_market_side as usize].risk;
-            let current_market_side_payout =
-                market_state.market_sides[bet_state.user_market_side as usize].payout;
-            market_state.market_sides[bet_state.user_market_side as usize].risk =
-                current_market_side_risk
-                    .checked_sub(bet_state.user_risk)
-                    .ok_or(ExchangeError::MarketSideRiskUnderflow)?;
-            market_state.market_sides[bet_state.user_market_side as usize].payout =
-                current_market_side_payout
-                    .checked_sub(bet_state.user_payout)
-                    .ok_or(ExchangeError::MarketSidePayoutUnderflow)?;
+            if bet_balance > 0 {
+                let transfer_instruction = transfer(
+                    &token_program.key,
+                    &bet_usdt_account.key,
+                    &user_usdt_account.key,
+                    &pda_account.key,
+                    &[&pda_account.key],
+                    bet_balance,
+                )?;
+                msg!("Calling the token program to transfer winnings to user.");
+                invoke_signed(
+                    &transfer_instruction,
+                    &[
+                        user_usdt_account.clone(),
+                        bet_usdt_account.clone(),
+                        pda_account.clone(),
+                        token_program.clone(),
+                    ],
+                    &[&[b"divvybetting", &[bump_seed]]],
+                )?;
+            }
 
-            let transfer_instruction = transfer(
-                &token_program.key,
-                &bet_usdt_account.key,
-                &user_usdt_account.key,
-                &pda_account.key,
-                &[&pda_account.key],
-                bet_balance,
-            )?;
-            msg!("Calling the token program to transfer winnings to user.");
-            invoke_signed(
-                &transfer_instruction,
-                &[
-                    user_usdt_account.clone(),
-                    bet_usdt_account.clone(),
-                    pda_account.clone(),
-                    token_program.clone(),
-                ],
-                //To Do Please test bump seed thing
-                &[&[b"divvybetting", &[255]]],
-            )?;
+            //Return rent to the user that placed the bet, less the keeper's crank reward
+            let balance = bet_state_account.lamports();
+            let keeper_reward = if crank_reward {
+                pool_state.crank_reward_lamports.min(balance)
+            } else {
+                0
+            };
+            **bet_state_account.try_borrow_mut_lamports()? -= balance;
+            **user_main_account.try_borrow_mut_lamports()? += balance - keeper_reward;
+            **keeper.try_borrow_mut_lamports()? += keeper_reward;
+            Bet::pack(bet_state, &mut bet_state_account.data.borrow_mut())?;
         }
 
-        //Return rent to the user that placed the bet
-        let balance = bet_state_account.lamports();
-        **bet_state_account.try_borrow_mut_lamports()? -= balance;
-        **user_main_account.try_borrow_mut_lamports()? += balance;
+        //The house lent the liquidity those bets were not paid, so it goes back to the house pool
+        if released_balance > 0 {
+            msg!("Returning released bettor balance to house pool");
+            msg!(0, 0, 0, 0, released_balance);
+            Self::return_house_liquidity(
+                hp_state_account,
+                ledger_account,
+                bet_usdt_account,
+                hp_usdt_account,
+                token_program,
+                pda_account,
+                divvy_hp_program,
+                market_state_account,
+                released_balance,
+                bump_seed,
+            )?;
+        }
 
         //Assert that when all of the markets winning bets are settled there is
         //no remaining risk, payout and bettor balance in the winning market side.
         if market_state.pending_bets == 0 {
-            // msg!("Market pending bets are settled. Asserting.");
-            // if market_state.market_sides[market_state.result as usize].risk != 0 {
-            //     return Err(ExchangeError::MarketSideRiskRemaining.into());
-            // }
-            // if market_state.market_sides[market_state.result as usize].payout != 0 {
-            //     return Err(ExchangeError::MarketSidePayoutRemaining.into());
-            // }
-            // if market_state.bettor_balance != 0 {
-            //     return Err(ExchangeError::MarketBettorBalanceRemaining.into());
-            // }
-        }
-
-        //Assert that when all of the house pool pending bets are settled there is
-        //no remaining bettor balance in the house pool.
+            msg!("Market pending bets are settled. Asserting.");
+            //The winning moneyline side has been paid, or every side of a voided market refunded
+            for (index, market_side) in market_state.market_sides.iter().enumerate() {
+                let settled = match (market_state.result, market_state.bet_type) {
+                    (MoneylineMarketOutcome::Void, _) => true,
+                    (result, BetType::MoneyLine) => index == result as usize,
+                    _ => false,
+                };
+                if settled && market_side.risk != 0 {
+                    return Err(ExchangeError::MarketSideRiskRemaining.into());
+                }
+                if settled && market_side.payout != 0 {
+                    return Err(ExchangeError::MarketSidePayoutRemaining.into());
+                }
+            }
+            if market_state.bettor_balance != 0 {
+                return Err(ExchangeError::MarketBettorBalanceRemaining.into());
+            }
+        }
+
+        //Assert that when all of the betting pool pending bets are settled there is
+        //no remaining locked or live liquidity in the betting pool.
         if pool_state.pending_bets == 0 {
-            // msg!("House pool pending bets are settled. Asserting.");
-            // if pool_state.locked_liquidity != 0 {
-            //     return Err(ExchangeError::HousePoolLockedLiquidityRemaining.into());
-            // }
-            // if pool_state.live_liquidity != 0 {
-            //     return Err(ExchangeError::HousePoolLockedLiquidityRemaining.into());
-            // }
+            msg!("Betting pool pending bets are settled. Asserting.");
+            if pool_state.locked_liquidity != 0 {
+                return Err(ExchangeError::HousePoolLockedLiquidityRemaining.into());
+            }
+            if pool_state.live_liquidity != 0 {
+                return Err(ExchangeError::HousePoolLockedLiquidityRemaining.into());
+            }
         }
 
         BettingPoolState::pack(pool_state, &mut bet_pool_state_account.data.borrow_mut())?;
         Market::pack(market_state, &mut market_state_account.data.borrow_mut())?;
-        Bet::pack(bet_state, &mut bet_state_account.data.borrow_mut())?;
         Ok(())
     }
 
     fn process_init_moneyline_market(
         accounts: &[AccountInfo],
-        _program_id: &Pubkey,
+        program_id: &Pubkey,
+        event_id: u64,
         bet_type: BetType,
+        oracle_source: OracleSource,
+        max_staleness: u64,
+        min_responses: u32,
+        league: [u8; LEAGUE_LEN],
+        start_time: i64,
+        side_labels: [[u8; LABEL_LEN]; 3],
     ) -> ProgramResult {
         let accounts_iter = &mut accounts.iter();
         let initializer = next_account_info(accounts_iter)?;
         let market_state_account = next_account_info(accounts_iter)?;
         let result_feed_account = next_account_info(accounts_iter)?;
         let bet_pool_state_account = next_account_info(accounts_iter)?;
+        let payer = next_account_info(accounts_iter)?;
+        let system_program = next_account_info(accounts_iter)?;
         let market_side_0_odds_feed_account = next_account_info(accounts_iter)?;
         let market_side_1_odds_feed_account = next_account_info(accounts_iter)?;
-        msg!("Checking if initializer is signer");
-        if !initializer.is_signer {
-            return Err(ProgramError::MissingRequiredSignature);
-        }
-        msg!("Checking if initializer is authorized");
-        if initializer.key != &authority::ID {
-            return Err(ExchangeError::NotValidAuthority.into());
-        }
         msg!("Unpack pool state");
-        let pool_state = BettingPoolState::unpack(&bet_pool_state_account.data.borrow())?;
-        msg!("Unpack market state");
-        let mut market_state = Market::unpack_unchecked(&market_state_account.data.borrow())?;
+        let pool_state = Self::unpack_pool_state(program_id, bet_pool_state_account)?;
 
         //Checking if betting is frozen
         if pool_state.frozen_betting {
             return Err(ExchangeError::BettingFrozen.into());
         }
-        if market_state.is_initialized {
-            return Err(ExchangeError::MarketAlreadyInitialized.into());
+        //Checking the market account is the only one for this event and bet type
+        let (market_address, market_bump_seed) = find_market_address(program_id, event_id, bet_type);
+        if *market_state_account.key != market_address {
+            return Err(ExchangeError::InvalidMarketAccount.into());
         }
-        if !Rent::get()?.is_exempt(
-            **market_state_account.lamports.borrow(),
-            market_state_account.data_len(),
-        ) {
-            return Err(ProgramError::AccountNotRentExempt);
+        if market_state_account.data_len() != 0 {
+            return Err(ExchangeError::MarketAlreadyInitialized.into());
         }
 
         let market_sides: [MarketSide; 3] = match bet_type {
@@ -473,18 +827,24 @@ impl Processor {
                         points_feed_account: None,
                         payout: 0,
                         risk: 0,
+                        line: 0,
+                        label: side_labels[0],
                     },
                     MarketSide {
                         odds_feed_account: Some(*market_side_1_odds_feed_account.key),
                         points_feed_account: None,
                         payout: 0,
                         risk: 0,
+                        line: 0,
+                        label: side_labels[1],
                     },
                     MarketSide {
                         odds_feed_account: Some(*market_side_2_odds_feed_account.key),
                         points_feed_account: None,
                         payout: 0,
                         risk: 0,
+                        line: 0,
+                        label: side_labels[2],
                     },
                 ]
             }
@@ -497,24 +857,36 @@ impl Processor {
                         points_feed_account: Some(*market_side_0_points_feed_account.key),
                         payout: 0,
                         risk: 0,
+                        line: 0,
+                        label: side_labels[0],
                     },
                     MarketSide {
                         odds_feed_account: Some(*market_side_1_odds_feed_account.key),
                         points_feed_account: Some(*market_side_1_points_feed_account.key),
                         payout: 0,
                         risk: 0,
+                        line: 0,
+                        label: side_labels[1],
                     },
                     MarketSide {
                         odds_feed_account: None,
                         points_feed_account: None,
                         payout: 0,
                         risk: 0,
+                        line: 0,
+                        label: side_labels[2],
                     },
                 ]
             }
         };
 
-        market_state = Market {
+        msg!("Checking if initializer is authorized");
+        validate_role(program_id, &pool_state.authority, &pool_state.roles, Role::MarketCreator, initializer, accounts_iter.as_slice())?;
+
+        msg!("Creating the market account");
+        create_pda_account(program_id, payer, market_state_account, system_program, Market::LEN, &[MARKET_SEED, &event_id.to_le_bytes(), &[bet_type.pack()], &[market_bump_seed]])?;
+
+        let market_state = Market {
             is_initialized: true,
             market_sides: market_sides,
             locked_liquidity: 0,
@@ -523,6 +895,15 @@ impl Processor {
             bettor_balance: 0,
             pending_bets: 0,
             bet_type,
+            oracle_source,
+            max_staleness,
+            min_responses,
+            home_score: 0,
+            away_score: 0,
+            event_id,
+            league,
+            start_time,
+            suspended: false,
         };
         Market::pack(market_state, &mut market_state_account.data.borrow_mut())?;
 
@@ -545,18 +926,27 @@ impl Processor {
         let insurance_fund_usdt_account = next_account_info(accounts_iter)?;
         let divvy_foundation_proceeds_usdt = next_account_info(accounts_iter)?;
         let token_program = next_account_info(accounts_iter)?;
+        let hp_state_account = next_account_info(accounts_iter)?;
+        let divvy_hp_program = next_account_info(accounts_iter)?;
+        let ledger_account = next_account_info(accounts_iter)?;
 
         let mut market_state = Market::unpack(&market_state_account.data.borrow())?;
-        let mut pool_state = BettingPoolState::unpack(&bet_pool_state_account.data.borrow())?;
+        let mut pool_state = Self::unpack_pool_state(program_id, bet_pool_state_account)?;
 
         // Checking house pool usdt account
         if *hp_usdt_account.key != pool_state.house_pool_usdt {
-            return Err(ExchangeError::InvalidHousePoolUsdtAccount.into());
+            return Err(ExchangeError::InvalidPoolUsdtAccount.into());
+        }
+        // Checking house pool state account
+        Self::unpack_house_pool_state(&pool_state, hp_state_account)?;
+        //Check house pool program ID
+        if *divvy_hp_program.key != divvy_house_program_id::ID {
+            return Err(ExchangeError::InvalidInstruction.into());
         }
 
         // Checking bet pool usdt account
         if *bet_usdt_account.key != pool_state.betting_pool_usdt {
-            return Err(ExchangeError::InvalidHousePoolUsdtAccount.into());
+            return Err(ExchangeError::InvalidPoolUsdtAccount.into());
         }
         if *insurance_fund_usdt_account.key != pool_state.insurance_fund_usdt {
             return Err(ExchangeError::InvalidInsuranceFundUsdtAccount.into());
@@ -574,6 +964,10 @@ impl Processor {
         if pool_state.frozen_betting {
             return Err(ExchangeError::BettingFrozen.into());
         }
+        //Checking if the market is suspended
+        if market_state.suspended {
+            return Err(ExchangeError::MarketSuspended.into());
+        }
         //Verifying result account
         if result_account.key != &market_state.result_feed {
             return Err(ExchangeError::NotValidAuthority.into());
@@ -584,65 +978,81 @@ impl Processor {
         {
             return Err(ExchangeError::MarketAlreadySettled.into());
         }
-        //Getting results from Switchboard
-        msg!("Unpacking switchboard aggregator.");
-        let aggregator: AggregatorState = get_aggregator(result_account)?;
-        msg!("Unpacking switchboard result.");
-        let round_result: RoundResult = get_aggregator_result(&aggregator)?;
-        msg!("Unpacking switchboard result option.");
-        let result_u8 = round_result
-            .result
-            .ok_or(ExchangeError::FeedNotInitialized)? as u8;
-        msg!("- Result feed");
-        msg!(0, 0, 0, 0, result_u8);
-        if result_u8 > 2 {
-            return Err(ExchangeError::NotValidMarketResult.into());
-        }
+        //Getting results from the market oracle
+        msg!("Reading result from oracle:");
+        msg!(market_state.oracle_source.into());
+        let clock = Clock::get()?;
 
         msg!("- Market state");
         msg!(market_state.result.into());
 
-        let new_market_result = MoneylineMarketOutcome::unpack(&result_u8)?;
-
         //When the market settles the bettor balance changes from the amount of risk the bettors
-        //have entered into the market to the winning sides unsettled risk and payout.
+        //have entered into the market to what the winning and pushed bets are owed.
         let current_bettor_balance = market_state.bettor_balance;
-        let new_bettor_balance = market_state.market_sides[new_market_result as usize]
-            .risk
-            .checked_add(market_state.market_sides[new_market_result as usize].payout)
-            .ok_or(ExchangeError::AmountOverflow)?;
+        let (new_market_result, new_bettor_balance) = match market_state.bet_type {
+            BetType::MoneyLine => {
+                let new_market_result = market_state.read_result(program_id, result_account, clock.unix_timestamp)?;
+                msg!("- Result feed");
+                msg!(new_market_result.into());
+                let new_bettor_balance = match new_market_result {
+                    MoneylineMarketOutcome::Void => current_bettor_balance,
+                    _ => market_state.market_sides[new_market_result as usize]
+                        .risk
+                        .checked_add(market_state.market_sides[new_market_result as usize].payout)
+                        .ok_or(ExchangeError::AmountOverflow)?,
+                };
+                (new_market_result, new_bettor_balance)
+            }
+            BetType::Spread | BetType::Total => {
+                match market_state.read_score(program_id, result_account, clock.unix_timestamp)? {
+                    Some((home_score, away_score)) => {
+                        msg!("- Final score, home and away");
+                        msg!(0, 0, 0, home_score as u64, away_score as u64);
+                        market_state.home_score = home_score;
+                        market_state.away_score = away_score;
+                        (
+                            MoneylineMarketOutcome::FinalScore,
+                            calculate_points_bettor_balance(&market_state, home_score, away_score)?,
+                        )
+                    }
+                    None => {
+                        msg!("- Result feed");
+                        msg!(MoneylineMarketOutcome::Void.into());
+                        (MoneylineMarketOutcome::Void, current_bettor_balance)
+                    }
+                }
+            }
+        };
         
         msg!("- New bettor balance");
         msg!(0, 0, 0, 0, new_bettor_balance);
         msg!("- Current bettor balance");
         msg!(0, 0, 0, 0, new_bettor_balance);
-        if new_bettor_balance < current_bettor_balance {
+        let locked_liquidity = market_state.locked_liquidity;
+        let returned_liquidity = if new_bettor_balance < current_bettor_balance {
             // The house has made money
             msg!("House made money");
             let house_profit = current_bettor_balance
                 .checked_sub(new_bettor_balance)
                 .ok_or(ExchangeError::AmountOverflow)?;
 
-            let locked_liquidity = market_state.locked_liquidity;
-            let house_profit_frac: U64F64 = U64F64::from_num(house_profit);
-
-            let insurance_fund_fee: u64 = (house_profit_frac * U64F64::from_num(0.01))
-                .checked_to_num()
-                .ok_or(ExchangeError::AmountOverflow)?;
-            let divvy_foundation_fee: u64 = (house_profit_frac * U64F64::from_num(0.05))
-                .checked_to_num()
-                .ok_or(ExchangeError::AmountOverflow)?;
-
-            let total_house_profit: u64 = (house_profit_frac * U64F64::from_num(0.94))
-                .checked_to_num()
-                .ok_or(ExchangeError::AmountOverflow)?;
+            let fee_split = split_house_profit(
+                house_profit,
+                pool_state.insurance_fund_fee_bps,
+                pool_state.divvy_foundation_fee_bps,
+            )?;
+            let insurance_fund_fee = fee_split.insurance_fund_fee;
+            let divvy_foundation_fee = fee_split.divvy_foundation_fee;
+            let total_house_profit = fee_split.house_share;
 
             msg!("- House profit before fees");
             msg!(0, 0, 0, 0, house_profit);
-            msg!("- Insurance fund fee");
-            msg!(0, 0, 0, 0, insurance_fund_fee);
-            msg!("- Divvy Foundation fee");
-            msg!(0, 0, 0, 0, divvy_foundation_fee);
+            msg!("- Insurance fund fee, bps and amount");
+            msg!(0, 0, 0, pool_state.insurance_fund_fee_bps as u64, insurance_fund_fee);
+            msg!("- Divvy Foundation fee, bps and amount");
+            msg!(0, 0, 0, pool_state.divvy_foundation_fee_bps as u64, divvy_foundation_fee);
+            msg!("- House profit after fees");
+            msg!(0, 0, 0, 0, total_house_profit);
 
             msg!("Transfering USDT to the insurance fund");
             let transfer_instruction = transfer(
@@ -661,7 +1071,7 @@ impl Processor {
                     pda_account.clone(),
                     token_program.clone(),
                 ],
-                &[&[b"divvybetting", &[255]]],
+                &[&[b"divvybetting", &[bump_seed]]],
             )?;
             msg!("Transfering USDT to the Divvy foundation");
             let transfer_instruction = transfer(
@@ -680,29 +1090,43 @@ impl Processor {
                     pda_account.clone(),
                     token_program.clone(),
                 ],
-                &[&[b"divvybetting", &[255]]],
+                &[&[b"divvybetting", &[bump_seed]]],
             )?;
 
-            msg!("Transfering house profit and locked liquidity to house pool");
-            let transfer_instruction = transfer(
-                &token_program.key,
-                &bet_usdt_account.key,
-                &hp_usdt_account.key,
-                &pda_account.key,
-                &[&pda_account.key],
-                (total_house_profit+locked_liquidity).clone(),
-            )?;
-            invoke_signed(
-                &transfer_instruction,
-                &[
-                    bet_usdt_account.clone(),
-                    hp_usdt_account.clone(),
-                    pda_account.clone(),
-                    token_program.clone(),
-                ],
-                &[&[b"divvybetting", &[255]]],
-            )?;
-        }
+            total_house_profit
+                .checked_add(locked_liquidity)
+                .ok_or(ExchangeError::AmountOverflow)?
+        } else if new_bettor_balance == current_bettor_balance {
+            // The house broke even, as it does when the market is voided. No fees are
+            // taken and the locked liquidity is released back to the house.
+            msg!("House broke even");
+            locked_liquidity
+        } else {
+            // The house lost money, paid out of the locked liquidity
+            msg!("House lost money");
+            let house_loss = new_bettor_balance
+                .checked_sub(current_bettor_balance)
+                .ok_or(ExchangeError::AmountOverflow)?;
+            msg!("- House loss");
+            msg!(0, 0, 0, 0, house_loss);
+            locked_liquidity
+                .checked_sub(house_loss)
+                .ok_or(ExchangeError::AmountOverflow)?
+        };
+        msg!("Returning liquidity to house pool");
+        msg!(0, 0, 0, 0, returned_liquidity);
+        Self::return_house_liquidity(
+            hp_state_account,
+            ledger_account,
+            bet_usdt_account,
+            hp_usdt_account,
+            token_program,
+            pda_account,
+            divvy_hp_program,
+            market_state_account,
+            returned_liquidity,
+            bump_seed,
+        )?;
 
         market_state.bettor_balance = new_bettor_balance;
 
@@ -712,21 +1136,11 @@ impl Processor {
         let current_pool_locked_liquidity = pool_state.locked_liquidity;
         let current_pool_live_liquidity = pool_state.live_liquidity;
         market_state.locked_liquidity = new_locked_liquidity;
-        match market_state.result {
-            MoneylineMarketOutcome::NotYetCommenced => {
-                pool_state.locked_liquidity = pool_state
-                    .locked_liquidity
-                    .checked_sub(current_locked_liquidity)
-                    .ok_or(ExchangeError::AmountOverflow)?;
-            }
-            MoneylineMarketOutcome::Commenced => {
-                pool_state.live_liquidity = pool_state
-                    .live_liquidity
-                    .checked_sub(current_locked_liquidity)
-                    .ok_or(ExchangeError::AmountOverflow)?;
-            }
-            _ => return Err(ExchangeError::InvalidInstruction.into()),
-        }
+        //Only a commenced market settles, so all of its liquidity is live
+        pool_state.live_liquidity = pool_state
+            .live_liquidity
+            .checked_sub(current_locked_liquidity)
+            .ok_or(ExchangeError::AmountOverflow)?;
 
         market_state.result = new_market_result;
 
@@ -798,6 +1212,14 @@ impl Processor {
             insurance_fund_usdt: *insurance_fund_usdt_account.key,
             divvy_foundation_proceeds_usdt: *divvy_foundation_proceeds_usdt.key,
             frozen_betting: false,
+            insurance_fund_fee_bps: DEFAULT_INSURANCE_FUND_FEE_BPS,
+            divvy_foundation_fee_bps: DEFAULT_DIVVY_FOUNDATION_FEE_BPS,
+            authority: *initializer.key,
+            pending_authority: None,
+            roles: Roles::default(),
+            bet_cutoff_secs: 0,
+            crank_reward_lamports: 0,
+            exposure_limits: ExposureLimits::default(),
         };
         BettingPoolState::pack(pool_state, &mut bet_pool_state_account.data.borrow_mut())?;
         Ok(())
@@ -807,18 +1229,10 @@ impl Processor {
         accounts: &[AccountInfo],
         hp_bump_seed: u8,
         bump_seed: u8,
-        _program_id: &Pubkey,
+        program_id: &Pubkey,
     ) -> ProgramResult {
         let accounts_iter = &mut accounts.iter();
         let initializer = next_account_info(accounts_iter)?;
-        msg!("Check if initializer is a signer");
-        if !initializer.is_signer {
-            return Err(ProgramError::MissingRequiredSignature);
-        }
-        msg!("Check authority");
-        if initializer.key != &authority::ID {
-            return Err(ExchangeError::NotValidAuthority.into());
-        }
         let market_state_account = next_account_info(accounts_iter)?;
         let token_program = next_account_info(accounts_iter)?;
         let pda_account = next_account_info(accounts_iter)?;
@@ -828,12 +1242,32 @@ impl Processor {
         let pool_state_account = next_account_info(accounts_iter)?;
         let bet_pool_state_account = next_account_info(accounts_iter)?;
         let divvy_hp_program = next_account_info(accounts_iter)?;
+        let payer_account = next_account_info(accounts_iter)?;
+        let ledger_account = next_account_info(accounts_iter)?;
+        let system_program = next_account_info(accounts_iter)?;
 
+        if *market_state_account.owner != *program_id {
+            return Err(ExchangeError::InvalidMarketAccount.into());
+        }
         let mut market_state = Market::unpack(&market_state_account.data.borrow())?;
-        let mut pool_state = BettingPoolState::unpack(&bet_pool_state_account.data.borrow())?;
+        let mut pool_state = Self::unpack_pool_state(program_id, bet_pool_state_account)?;
+        msg!("Check authority");
+        //Once betting has closed any keeper may commence the market
+        if Clock::get()?.unix_timestamp < market_state.bet_cutoff(pool_state.bet_cutoff_secs) {
+            validate_role(program_id, &pool_state.authority, &pool_state.roles, Role::Settler, initializer, accounts_iter.as_slice())?;
+        }
 
-        //Check house pool porgram ID
-        // divvy_house_program_id::ID
+        //Check house pool program ID
+        if *divvy_hp_program.key != divvy_house_program_id::ID {
+            return Err(ExchangeError::InvalidInstruction.into());
+        }
+        //Checking the liquidity moves from the house pool to the betting pool
+        if *pool_usdt_account.key != pool_state.house_pool_usdt {
+            return Err(ExchangeError::InvalidPoolUsdtAccount.into());
+        }
+        if *betting_usdt_account.key != pool_state.betting_pool_usdt {
+            return Err(ExchangeError::InvalidBettingPoolUsdtAccount.into());
+        }
 
         //Checking if betting is frozen: Should we?
         if pool_state.frozen_betting {
@@ -844,29 +1278,18 @@ impl Processor {
             return Err(ExchangeError::MarketCommenced.into());
         }
 
-        let usdt_amount = market_state.locked_liquidity;
-        let signer_pubkeys = &[bet_pda_account.key];
-
-        let mut data = Vec::with_capacity(size_of::<Self>());
-        data.push(4);
-        data.extend_from_slice(&usdt_amount.to_le_bytes());
-        data.extend_from_slice(&bump_seed.to_le_bytes());
-
-        let mut accounts = Vec::with_capacity(5 + signer_pubkeys.len());
-        accounts.push(AccountMeta::new(*token_program.key, false));
-        accounts.push(AccountMeta::new(*pda_account.key, false));
-        for signer_pubkey in signer_pubkeys.iter() {
-            accounts.push(AccountMeta::new_readonly(**signer_pubkey, true));
-        }
-        accounts.push(AccountMeta::new(*betting_usdt_account.key, false));
-        accounts.push(AccountMeta::new(*pool_usdt_account.key, false));
-        accounts.push(AccountMeta::new(*pool_state_account.key, false));
-
-        let instruction = Instruction {
-            program_id: *divvy_hp_program.key,
-            accounts,
-            data,
-        };
+        let instruction = transfer_locked_liquidity(
+            divvy_hp_program.key,
+            pda_account.key,
+            bet_pda_account.key,
+            betting_usdt_account.key,
+            pool_usdt_account.key,
+            pool_state_account.key,
+            payer_account.key,
+            market_state_account.key,
+            market_state.locked_liquidity,
+            hp_bump_seed,
+        )?;
         msg!("Transfer locked liquidity");
         invoke_signed(
                 &instruction,
@@ -877,6 +1300,11 @@ impl Processor {
                     betting_usdt_account.clone(),
                     pool_usdt_account.clone(),
                     pool_state_account.clone(),
+                    payer_account.clone(),
+                    market_state_account.clone(),
+                    ledger_account.clone(),
+                    system_program.clone(),
+                    divvy_hp_program.clone(),
                 ],
                 &[&[b"divvybetting", &[bump_seed]]],
             )?;
@@ -896,24 +1324,134 @@ impl Processor {
         Ok(())
     }
 
-    pub fn process_freeze(
+    pub fn process_void_market(
         accounts: &[AccountInfo],
-        _program_id: &Pubkey,
-        freeze_betting: bool,
+        program_id: &Pubkey,
+        bump_seed: u8,
     ) -> ProgramResult {
         let accounts_iter = &mut accounts.iter();
         let initializer = next_account_info(accounts_iter)?;
+        let market_state_account = next_account_info(accounts_iter)?;
         let bet_pool_state_account = next_account_info(accounts_iter)?;
+        let pda_account = next_account_info(accounts_iter)?;
+        let hp_usdt_account = next_account_info(accounts_iter)?;
+        let bet_usdt_account = next_account_info(accounts_iter)?;
+        let token_program = next_account_info(accounts_iter)?;
+        let hp_state_account = next_account_info(accounts_iter)?;
+        let divvy_hp_program = next_account_info(accounts_iter)?;
+        let ledger_account = next_account_info(accounts_iter)?;
 
-        if !initializer.is_signer {
-            return Err(ProgramError::MissingRequiredSignature);
+        if *market_state_account.owner != *program_id {
+            return Err(ExchangeError::InvalidMarketAccount.into());
         }
-        if initializer.key != &authority::ID {
-            return Err(ExchangeError::NotValidAuthority.into());
+        let mut market_state = Market::unpack(&market_state_account.data.borrow())?;
+        let mut pool_state = Self::unpack_pool_state(program_id, bet_pool_state_account)?;
+        validate_role(program_id, &pool_state.authority, &pool_state.roles, Role::Settler, initializer, accounts_iter.as_slice())?;
+
+        // Checking house pool usdt account
+        if *hp_usdt_account.key != pool_state.house_pool_usdt {
+            return Err(ExchangeError::InvalidPoolUsdtAccount.into());
+        }
+        // Checking house pool state account
+        Self::unpack_house_pool_state(&pool_state, hp_state_account)?;
+        //Check house pool program ID
+        if *divvy_hp_program.key != divvy_house_program_id::ID {
+            return Err(ExchangeError::InvalidInstruction.into());
+        }
+        // Checking bet pool usdt account
+        if *bet_usdt_account.key != pool_state.betting_pool_usdt {
+            return Err(ExchangeError::InvalidBettingPoolUsdtAccount.into());
+        }
+        if *token_program.key != token_program_id::ID {
+            return Err(ExchangeError::InvalidInstruction.into());
         }
 
-        let mut pool_state = BettingPoolState::unpack(&bet_pool_state_account.data.borrow())?;
-  
+        //Releasing the locked liquidity of the market back to the house. Every bet
+        //keeps its risk in the bettor balance and is refunded when it settles.
+        let locked_liquidity = market_state.locked_liquidity;
+        match market_state.result {
+            MoneylineMarketOutcome::NotYetCommenced => {
+                //The liquidity never left the house pool
+                pool_state.locked_liquidity = pool_state
+                    .locked_liquidity
+                    .checked_sub(locked_liquidity)
+                    .ok_or(ExchangeError::AmountOverflow)?;
+            }
+            MoneylineMarketOutcome::Commenced => {
+                msg!("Returning locked liquidity to house pool");
+                Self::return_house_liquidity(
+                    hp_state_account,
+                    ledger_account,
+                    bet_usdt_account,
+                    hp_usdt_account,
+                    token_program,
+                    pda_account,
+                    divvy_hp_program,
+                    market_state_account,
+                    locked_liquidity,
+                    bump_seed,
+                )?;
+                pool_state.live_liquidity = pool_state
+                    .live_liquidity
+                    .checked_sub(locked_liquidity)
+                    .ok_or(ExchangeError::AmountOverflow)?;
+            }
+            _ => return Err(ExchangeError::MarketAlreadySettled.into()),
+        }
+        msg!("- Released liquidity");
+        msg!(0, 0, 0, 0, locked_liquidity);
+
+        market_state.locked_liquidity = 0;
+        market_state.result = MoneylineMarketOutcome::Void;
+
+        Market::pack(market_state, &mut market_state_account.data.borrow_mut())?;
+        BettingPoolState::pack(pool_state, &mut bet_pool_state_account.data.borrow_mut())?;
+
+        Ok(())
+    }
+
+    pub fn process_suspend_market(
+        accounts: &[AccountInfo],
+        program_id: &Pubkey,
+        suspended: bool,
+    ) -> ProgramResult {
+        let accounts_iter = &mut accounts.iter();
+        let initializer = next_account_info(accounts_iter)?;
+        let market_state_account = next_account_info(accounts_iter)?;
+        let bet_pool_state_account = next_account_info(accounts_iter)?;
+
+        if *market_state_account.owner != *program_id {
+            return Err(ExchangeError::InvalidMarketAccount.into());
+        }
+        let mut market_state = Market::unpack(&market_state_account.data.borrow())?;
+        let pool_state = Self::unpack_pool_state(program_id, bet_pool_state_account)?;
+        validate_role(program_id, &pool_state.authority, &pool_state.roles, Role::Pauser, initializer, accounts_iter.as_slice())?;
+
+        if suspended && !market_state.suspended {
+            msg!("Suspending market");
+        } else if !suspended && market_state.suspended {
+            msg!("Resuming market");
+        }
+
+        market_state.suspended = suspended;
+
+        Market::pack(market_state, &mut market_state_account.data.borrow_mut())?;
+
+        Ok(())
+    }
+
+    pub fn process_freeze(
+        accounts: &[AccountInfo],
+        program_id: &Pubkey,
+        freeze_betting: bool,
+    ) -> ProgramResult {
+        let accounts_iter = &mut accounts.iter();
+        let initializer = next_account_info(accounts_iter)?;
+        let bet_pool_state_account = next_account_info(accounts_iter)?;
+
+        let mut pool_state = Self::unpack_pool_state(program_id, bet_pool_state_account)?;
+        validate_role(program_id, &pool_state.authority, &pool_state.roles, Role::Pauser, initializer, accounts_iter.as_slice())?;
+
         if freeze_betting && !pool_state.frozen_betting {
             msg!("Freezing betting");
         } else if !freeze_betting && pool_state.frozen_betting {
@@ -926,4 +1464,304 @@ impl Processor {
 
         Ok(())
     }
+
+    pub fn process_update_fees(
+        accounts: &[AccountInfo],
+        program_id: &Pubkey,
+        insurance_fund_fee_bps: u16,
+        divvy_foundation_fee_bps: u16,
+    ) -> ProgramResult {
+        let accounts_iter = &mut accounts.iter();
+        let initializer = next_account_info(accounts_iter)?;
+        let bet_pool_state_account = next_account_info(accounts_iter)?;
+
+        let mut pool_state = Self::unpack_pool_state(program_id, bet_pool_state_account)?;
+        validate_role(program_id, &pool_state.authority, &pool_state.roles, Role::Treasurer, initializer, accounts_iter.as_slice())?;
+        check_fees(insurance_fund_fee_bps, divvy_foundation_fee_bps)?;
+
+        msg!("- Insurance fund fee bps from");
+        msg!(0, 0, 0, 0, pool_state.insurance_fund_fee_bps as u64);
+        msg!("- Insurance fund fee bps to");
+        msg!(0, 0, 0, 0, insurance_fund_fee_bps as u64);
+        msg!("- Divvy Foundation fee bps from");
+        msg!(0, 0, 0, 0, pool_state.divvy_foundation_fee_bps as u64);
+        msg!("- Divvy Foundation fee bps to");
+        msg!(0, 0, 0, 0, divvy_foundation_fee_bps as u64);
+
+        pool_state.insurance_fund_fee_bps = insurance_fund_fee_bps;
+        pool_state.divvy_foundation_fee_bps = divvy_foundation_fee_bps;
+
+        BettingPoolState::pack(pool_state, &mut bet_pool_state_account.data.borrow_mut())?;
+
+        Ok(())
+    }
+
+    pub fn process_update_bet_cutoff(
+        accounts: &[AccountInfo],
+        program_id: &Pubkey,
+        bet_cutoff_secs: u64,
+    ) -> ProgramResult {
+        let accounts_iter = &mut accounts.iter();
+        let initializer = next_account_info(accounts_iter)?;
+        let bet_pool_state_account = next_account_info(accounts_iter)?;
+
+        let mut pool_state = Self::unpack_pool_state(program_id, bet_pool_state_account)?;
+        validate_role(program_id, &pool_state.authority, &pool_state.roles, Role::Settler, initializer, accounts_iter.as_slice())?;
+
+        msg!("- Bet cutoff secs from");
+        msg!(0, 0, 0, 0, pool_state.bet_cutoff_secs);
+        msg!("- Bet cutoff secs to");
+        msg!(0, 0, 0, 0, bet_cutoff_secs);
+
+        pool_state.bet_cutoff_secs = bet_cutoff_secs;
+
+        BettingPoolState::pack(pool_state, &mut bet_pool_state_account.data.borrow_mut())?;
+
+        Ok(())
+    }
+
+    pub fn process_update_crank_reward(
+        accounts: &[AccountInfo],
+        program_id: &Pubkey,
+        crank_reward_lamports: u64,
+    ) -> ProgramResult {
+        let accounts_iter = &mut accounts.iter();
+        let initializer = next_account_info(accounts_iter)?;
+        let bet_pool_state_account = next_account_info(accounts_iter)?;
+
+        let mut pool_state = Self::unpack_pool_state(program_id, bet_pool_state_account)?;
+        validate_role(program_id, &pool_state.authority, &pool_state.roles, Role::Treasurer, initializer, accounts_iter.as_slice())?;
+        //Checking the reward is paid out of the bet rent alone
+        if crank_reward_lamports > Rent::get()?.minimum_balance(Bet::LEN) {
+            return Err(ExchangeError::CrankRewardTooHigh.into());
+        }
+
+        msg!("- Crank reward lamports from");
+        msg!(0, 0, 0, 0, pool_state.crank_reward_lamports);
+        msg!("- Crank reward lamports to");
+        msg!(0, 0, 0, 0, crank_reward_lamports);
+
+        pool_state.crank_reward_lamports = crank_reward_lamports;
+
+        BettingPoolState::pack(pool_state, &mut bet_pool_state_account.data.borrow_mut())?;
+
+        Ok(())
+    }
+
+    pub fn process_update_exposure_limits(
+        accounts: &[AccountInfo],
+        program_id: &Pubkey,
+        exposure_limits: ExposureLimits,
+    ) -> ProgramResult {
+        let accounts_iter = &mut accounts.iter();
+        let initializer = next_account_info(accounts_iter)?;
+        let bet_pool_state_account = next_account_info(accounts_iter)?;
+
+        let mut pool_state = Self::unpack_pool_state(program_id, bet_pool_state_account)?;
+        validate_role(program_id, &pool_state.authority, &pool_state.roles, Role::Treasurer, initializer, accounts_iter.as_slice())?;
+        exposure_limits.check()?;
+
+        msg!("- Max market exposure bps");
+        msg!(0, 0, 0, 0, exposure_limits.max_market_exposure_bps as u64);
+        msg!("- Max side payout");
+        msg!(0, 0, 0, 0, exposure_limits.max_side_payout);
+        msg!("- Min bet risk");
+        msg!(0, 0, 0, 0, exposure_limits.min_bet_risk);
+        msg!("- Max bet risk");
+        msg!(0, 0, 0, 0, exposure_limits.max_bet_risk);
+
+        pool_state.exposure_limits = exposure_limits;
+
+        BettingPoolState::pack(pool_state, &mut bet_pool_state_account.data.borrow_mut())?;
+
+        Ok(())
+    }
+
+    pub fn process_update_manual_feed(
+        accounts: &[AccountInfo],
+        program_id: &Pubkey,
+        value: i64,
+        expo: i32,
+    ) -> ProgramResult {
+        let accounts_iter = &mut accounts.iter();
+        let initializer = next_account_info(accounts_iter)?;
+        let feed_account = next_account_info(accounts_iter)?;
+        let bet_pool_state_account = next_account_info(accounts_iter)?;
+
+        let pool_state = Self::unpack_pool_state(program_id, bet_pool_state_account)?;
+        validate_role(program_id, &pool_state.authority, &pool_state.roles, Role::Settler, initializer, accounts_iter.as_slice())?;
+        if feed_account.owner != program_id {
+            return Err(ExchangeError::InvalidFeedAccount.into());
+        }
+        if !Rent::get()?.is_exempt(**feed_account.lamports.borrow(), feed_account.data_len()) {
+            return Err(ProgramError::AccountNotRentExempt);
+        }
+
+        let mut feed = ManualFeed::unpack_unchecked(&feed_account.data.borrow())?;
+        if !feed.is_initialized {
+            msg!("Initializing manual feed");
+        }
+        feed.is_initialized = true;
+        feed.value = value;
+        feed.expo = expo;
+        feed.timestamp = Clock::get()?.unix_timestamp;
+
+        ManualFeed::pack(feed, &mut feed_account.data.borrow_mut())?;
+
+        Ok(())
+    }
+
+    pub fn process_propose_authority(
+        accounts: &[AccountInfo],
+        program_id: &Pubkey,
+        new_authority: Pubkey,
+    ) -> ProgramResult {
+        let accounts_iter = &mut accounts.iter();
+        let authority_account = next_account_info(accounts_iter)?;
+        let bet_pool_state_account = next_account_info(accounts_iter)?;
+
+        let mut pool_state = Self::unpack_pool_state(program_id, bet_pool_state_account)?;
+        validate_authority(program_id, &pool_state.authority, authority_account, accounts_iter.as_slice())?;
+
+        msg!("Proposing new authority");
+        pool_state.pending_authority = Some(new_authority);
+
+        BettingPoolState::pack(pool_state, &mut bet_pool_state_account.data.borrow_mut())?;
+
+        Ok(())
+    }
+
+    pub fn process_accept_authority(
+        accounts: &[AccountInfo],
+        program_id: &Pubkey,
+    ) -> ProgramResult {
+        let accounts_iter = &mut accounts.iter();
+        let pending_authority_account = next_account_info(accounts_iter)?;
+        let bet_pool_state_account = next_account_info(accounts_iter)?;
+
+        let mut pool_state = Self::unpack_pool_state(program_id, bet_pool_state_account)?;
+        let pending_authority = pool_state.pending_authority.ok_or(ExchangeError::NoPendingAuthority)?;
+        validate_authority(program_id, &pending_authority, pending_authority_account, accounts_iter.as_slice())?;
+
+        msg!("Accepting authority");
+        pool_state.authority = pending_authority;
+        pool_state.pending_authority = None;
+
+        BettingPoolState::pack(pool_state, &mut bet_pool_state_account.data.borrow_mut())?;
+
+        Ok(())
+    }
+
+    pub fn process_init_multisig(
+        accounts: &[AccountInfo],
+        program_id: &Pubkey,
+        m: u8,
+    ) -> ProgramResult {
+        let accounts_iter = &mut accounts.iter();
+        let multisig_account = next_account_info(accounts_iter)?;
+
+        init_multisig(program_id, multisig_account, accounts_iter.as_slice(), m)
+    }
+
+    pub fn process_grant_role(
+        accounts: &[AccountInfo],
+        program_id: &Pubkey,
+        role: Role,
+        member: Pubkey,
+    ) -> ProgramResult {
+        let accounts_iter = &mut accounts.iter();
+        let authority_account = next_account_info(accounts_iter)?;
+        let bet_pool_state_account = next_account_info(accounts_iter)?;
+
+        let mut pool_state = Self::unpack_pool_state(program_id, bet_pool_state_account)?;
+        validate_authority(program_id, &pool_state.authority, authority_account, accounts_iter.as_slice())?;
+
+        msg!("Granting role");
+        msg!(0, 0, 0, 0, role.pack() as u64);
+        pool_state.roles.grant(role, &member)?;
+
+        BettingPoolState::pack(pool_state, &mut bet_pool_state_account.data.borrow_mut())?;
+
+        Ok(())
+    }
+
+    pub fn process_revoke_role(
+        accounts: &[AccountInfo],
+        program_id: &Pubkey,
+        role: Role,
+        member: Pubkey,
+    ) -> ProgramResult {
+        let accounts_iter = &mut accounts.iter();
+        let authority_account = next_account_info(accounts_iter)?;
+        let bet_pool_state_account = next_account_info(accounts_iter)?;
+
+        let mut pool_state = Self::unpack_pool_state(program_id, bet_pool_state_account)?;
+        validate_authority(program_id, &pool_state.authority, authority_account, accounts_iter.as_slice())?;
+
+        msg!("Revoking role");
+        msg!(0, 0, 0, 0, role.pack() as u64);
+        pool_state.roles.revoke(role, &member)?;
+
+        BettingPoolState::pack(pool_state, &mut bet_pool_state_account.data.borrow_mut())?;
+
+        Ok(())
+    }
+
+    //Only a pool state owned by this program holds its authority and roles
+    fn unpack_pool_state(
+        program_id: &Pubkey,
+        bet_pool_state_account: &AccountInfo,
+    ) -> Result<BettingPoolState, ProgramError> {
+        if bet_pool_state_account.owner != program_id {
+            return Err(ExchangeError::InvalidBettingPoolStateAccount.into());
+        }
+        BettingPoolState::unpack(&bet_pool_state_account.data.borrow())
+    }
+
+    fn unpack_house_pool_state(
+        pool_state: &BettingPoolState,
+        hp_state_account: &AccountInfo,
+    ) -> Result<HpLiquidity, ProgramError> {
+        let hp_state = HpLiquidity::unpack(&hp_state_account.data.borrow())?;
+        if *hp_state_account.owner != divvy_house_program_id::ID || hp_state.pool_usdt != pool_state.house_pool_usdt {
+            return Err(ExchangeError::InvalidHousePoolStateAccount.into());
+        }
+        Ok(hp_state)
+    }
+
+    fn return_house_liquidity<'a>(
+        hp_state_account: &AccountInfo<'a>,
+        ledger_account: &AccountInfo<'a>,
+        bet_usdt_account: &AccountInfo<'a>,
+        hp_usdt_account: &AccountInfo<'a>,
+        token_program: &AccountInfo<'a>,
+        pda_account: &AccountInfo<'a>,
+        divvy_hp_program: &AccountInfo<'a>,
+        market_state_account: &AccountInfo<'a>,
+        usdt_amount: u64,
+        bump_seed: u8,
+    ) -> ProgramResult {
+        let instruction = return_liquidity(
+            divvy_hp_program.key,
+            pda_account.key,
+            hp_state_account.key,
+            market_state_account.key,
+            bet_usdt_account.key,
+            hp_usdt_account.key,
+            usdt_amount,
+        )?;
+        invoke_signed(
+            &instruction,
+            &[
+                pda_account.clone(),
+                hp_state_account.clone(),
+                ledger_account.clone(),
+                bet_usdt_account.clone(),
+                hp_usdt_account.clone(),
+                token_program.clone(),
+                divvy_hp_program.clone(),
+            ],
+            &[&[b"divvybetting", &[bump_seed]]],
+        )
+    }
 }
@@ -0,0 +1,420 @@
+mod common;
+
+use common::{exchange_error, funded_divvy, now, program_test};
+use divvybetting::{
+    error::ExchangeError,
+    instruction,
+    schema::divvy_house_program_id,
+    state::{
+        BetType, BettingPoolState, ExposureLimits, Market, Multisig, OracleSource, Role, Roles,
+    },
+};
+use divvyhouse::state::HpLiquidity;
+use solana_program::{instruction::InstructionError, program_pack::Pack, pubkey::Pubkey};
+use solana_sdk::{
+    account::Account,
+    signature::{Keypair, Signer},
+    transaction::TransactionError,
+};
+
+#[tokio::test]
+async fn test_propose_and_accept_authority() {
+    let program_id = Pubkey::new_unique();
+    let mut divvy = funded_divvy(program_test(&program_id), program_id).await;
+    let bet_pool_state = divvy.bet_pool_state.pubkey();
+    let old_authority = Keypair::from_bytes(&divvy.authority.to_bytes()).unwrap();
+    let new_authority = Keypair::new();
+
+    let outsider = Keypair::new();
+    let instruction =
+        instruction::accept_authority(&program_id, &outsider.pubkey(), &bet_pool_state, &[])
+            .unwrap();
+    assert_eq!(
+        divvy
+            .process(&[instruction], &[&outsider])
+            .await
+            .unwrap_err(),
+        exchange_error(ExchangeError::NoPendingAuthority)
+    );
+    let instruction = instruction::propose_authority(
+        &program_id,
+        &outsider.pubkey(),
+        &bet_pool_state,
+        &outsider.pubkey(),
+        &[],
+    )
+    .unwrap();
+    assert_eq!(
+        divvy
+            .process(&[instruction], &[&outsider])
+            .await
+            .unwrap_err(),
+        exchange_error(ExchangeError::NotValidAuthority)
+    );
+
+    let instruction = instruction::propose_authority(
+        &program_id,
+        &old_authority.pubkey(),
+        &bet_pool_state,
+        &new_authority.pubkey(),
+        &[],
+    )
+    .unwrap();
+    divvy
+        .process(&[instruction], &[&old_authority])
+        .await
+        .unwrap();
+    let pool_state: BettingPoolState = divvy.unpack(&bet_pool_state).await;
+    assert_eq!(pool_state.authority, old_authority.pubkey());
+    assert_eq!(pool_state.pending_authority, Some(new_authority.pubkey()));
+
+    // Only the proposed authority can accept
+    let instruction =
+        instruction::accept_authority(&program_id, &old_authority.pubkey(), &bet_pool_state, &[])
+            .unwrap();
+    assert_eq!(
+        divvy
+            .process(&[instruction], &[&old_authority])
+            .await
+            .unwrap_err(),
+        exchange_error(ExchangeError::NotValidAuthority)
+    );
+    let instruction =
+        instruction::accept_authority(&program_id, &new_authority.pubkey(), &bet_pool_state, &[])
+            .unwrap();
+    divvy
+        .process(&[instruction], &[&new_authority])
+        .await
+        .unwrap();
+    let pool_state: BettingPoolState = divvy.unpack(&bet_pool_state).await;
+    assert_eq!(pool_state.authority, new_authority.pubkey());
+    assert_eq!(pool_state.pending_authority, None);
+
+    // The old authority is locked out
+    assert_eq!(
+        divvy.update_fees(200, 300).await.unwrap_err(),
+        exchange_error(ExchangeError::NotValidAuthority)
+    );
+    let instruction = instruction::update_fees(
+        &program_id,
+        &new_authority.pubkey(),
+        &bet_pool_state,
+        200,
+        300,
+        &[],
+    )
+    .unwrap();
+    divvy
+        .process(&[instruction], &[&new_authority])
+        .await
+        .unwrap();
+    let pool_state: BettingPoolState = divvy.unpack(&bet_pool_state).await;
+    assert_eq!(pool_state.insurance_fund_fee_bps, 200);
+}
+
+#[tokio::test]
+async fn test_multisig_authority() {
+    let program_id = Pubkey::new_unique();
+    let mut divvy = funded_divvy(program_test(&program_id), program_id).await;
+    let bet_pool_state = divvy.bet_pool_state.pubkey();
+    let authority = Keypair::from_bytes(&divvy.authority.to_bytes()).unwrap();
+    let signers = [Keypair::new(), Keypair::new(), Keypair::new()];
+    let signer_keys = signers
+        .iter()
+        .map(|signer| signer.pubkey())
+        .collect::<Vec<_>>();
+    let signer_keys = signer_keys.iter().collect::<Vec<_>>();
+
+    let multisig = Keypair::new();
+    divvy
+        .create_account(&multisig, Multisig::LEN, &program_id)
+        .await;
+    let instruction =
+        instruction::init_multisig(&program_id, &multisig.pubkey(), &signer_keys, 4).unwrap();
+    assert_eq!(
+        divvy.process(&[instruction], &[]).await.unwrap_err(),
+        exchange_error(ExchangeError::InvalidMultisig)
+    );
+    // A key listed twice would let it sign for two
+    let instruction = instruction::init_multisig(
+        &program_id,
+        &multisig.pubkey(),
+        &[signer_keys[0], signer_keys[1], signer_keys[0]],
+        2,
+    )
+    .unwrap();
+    assert_eq!(
+        divvy.process(&[instruction], &[]).await.unwrap_err(),
+        exchange_error(ExchangeError::InvalidMultisig)
+    );
+    let instruction =
+        instruction::init_multisig(&program_id, &multisig.pubkey(), &signer_keys, 2).unwrap();
+    divvy.process(&[instruction], &[]).await.unwrap();
+    let instruction =
+        instruction::init_multisig(&program_id, &multisig.pubkey(), &signer_keys[..2], 1).unwrap();
+    assert_eq!(
+        divvy.process(&[instruction], &[]).await.unwrap_err(),
+        exchange_error(ExchangeError::MultisigAlreadyInitialized)
+    );
+    let multisig_state: Multisig = divvy.unpack(&multisig.pubkey()).await;
+    assert_eq!((multisig_state.m, multisig_state.n), (2, 3));
+
+    let instruction = instruction::propose_authority(
+        &program_id,
+        &authority.pubkey(),
+        &bet_pool_state,
+        &multisig.pubkey(),
+        &[],
+    )
+    .unwrap();
+    divvy.process(&[instruction], &[&authority]).await.unwrap();
+
+    // One of the three signers is not enough
+    let instruction = instruction::accept_authority(
+        &program_id,
+        &multisig.pubkey(),
+        &bet_pool_state,
+        &signer_keys[..1],
+    )
+    .unwrap();
+    assert_eq!(
+        divvy
+            .process(&[instruction], &[&signers[0]])
+            .await
+            .unwrap_err(),
+        TransactionError::InstructionError(0, InstructionError::MissingRequiredSignature)
+    );
+    let instruction = instruction::accept_authority(
+        &program_id,
+        &multisig.pubkey(),
+        &bet_pool_state,
+        &signer_keys[1..],
+    )
+    .unwrap();
+    divvy
+        .process(&[instruction], &[&signers[1], &signers[2]])
+        .await
+        .unwrap();
+    let pool_state: BettingPoolState = divvy.unpack(&bet_pool_state).await;
+    assert_eq!(pool_state.authority, multisig.pubkey());
+
+    // The multisig signers follow the accounts of any admin instruction
+    let instruction = instruction::freeze(
+        &program_id,
+        &multisig.pubkey(),
+        &bet_pool_state,
+        true,
+        &[signer_keys[0], signer_keys[2]],
+    )
+    .unwrap();
+    divvy
+        .process(&[instruction], &[&signers[0], &signers[2]])
+        .await
+        .unwrap();
+    let pool_state: BettingPoolState = divvy.unpack(&bet_pool_state).await;
+    assert!(pool_state.frozen_betting);
+}
+
+#[tokio::test]
+async fn test_roles() {
+    let program_id = Pubkey::new_unique();
+    let mut divvy = funded_divvy(program_test(&program_id), program_id).await;
+    let bet_pool_state = divvy.bet_pool_state.pubkey();
+    let authority = Keypair::from_bytes(&divvy.authority.to_bytes()).unwrap();
+    let pauser = Keypair::new();
+
+    let instruction = instruction::grant_role(
+        &program_id,
+        &pauser.pubkey(),
+        &bet_pool_state,
+        Role::Pauser,
+        &pauser.pubkey(),
+        &[],
+    )
+    .unwrap();
+    assert_eq!(
+        divvy.process(&[instruction], &[&pauser]).await.unwrap_err(),
+        exchange_error(ExchangeError::NotValidAuthority)
+    );
+    let instruction = instruction::grant_role(
+        &program_id,
+        &authority.pubkey(),
+        &bet_pool_state,
+        Role::Pauser,
+        &pauser.pubkey(),
+        &[],
+    )
+    .unwrap();
+    divvy.process(&[instruction], &[&authority]).await.unwrap();
+    let pool_state: BettingPoolState = divvy.unpack(&bet_pool_state).await;
+    assert!(pool_state.roles.has_role(Role::Pauser, &pauser.pubkey()));
+
+    // A pauser can freeze but not change fees
+    let instruction =
+        instruction::freeze(&program_id, &pauser.pubkey(), &bet_pool_state, true, &[]).unwrap();
+    divvy.process(&[instruction], &[&pauser]).await.unwrap();
+    let pool_state: BettingPoolState = divvy.unpack(&bet_pool_state).await;
+    assert!(pool_state.frozen_betting);
+    let instruction =
+        instruction::update_fees(&program_id, &pauser.pubkey(), &bet_pool_state, 0, 0, &[])
+            .unwrap();
+    assert_eq!(
+        divvy.process(&[instruction], &[&pauser]).await.unwrap_err(),
+        exchange_error(ExchangeError::NotValidAuthority)
+    );
+
+    // The house pool keeps its own roles
+    let instruction = divvyhouse::instruction::freeze(
+        &divvy_house_program_id::ID,
+        &pauser.pubkey(),
+        &divvy.hp_state.pubkey(),
+        true,
+        &[],
+    )
+    .unwrap();
+    assert_eq!(
+        divvy
+            .process(&[instruction.clone()], &[&pauser])
+            .await
+            .unwrap_err(),
+        exchange_error(ExchangeError::NotValidAuthority)
+    );
+    let grant = divvyhouse::instruction::grant_role(
+        &divvy_house_program_id::ID,
+        &authority.pubkey(),
+        &divvy.hp_state.pubkey(),
+        Role::Pauser,
+        &pauser.pubkey(),
+        &[],
+    )
+    .unwrap();
+    divvy
+        .process(&[grant, instruction], &[&authority, &pauser])
+        .await
+        .unwrap();
+    let hp_state: HpLiquidity = divvy.unpack(&divvy.hp_state.pubkey()).await;
+    assert!(hp_state.frozen_pool);
+
+    let instruction = instruction::revoke_role(
+        &program_id,
+        &authority.pubkey(),
+        &bet_pool_state,
+        Role::Pauser,
+        &pauser.pubkey(),
+        &[],
+    )
+    .unwrap();
+    divvy.process(&[instruction], &[&authority]).await.unwrap();
+    let instruction =
+        instruction::freeze(&program_id, &pauser.pubkey(), &bet_pool_state, false, &[]).unwrap();
+    assert_eq!(
+        divvy.process(&[instruction], &[&pauser]).await.unwrap_err(),
+        exchange_error(ExchangeError::NotValidAuthority)
+    );
+}
+
+#[tokio::test]
+async fn test_forged_pool_state_rejected() {
+    let program_id = Pubkey::new_unique();
+    let attacker = Keypair::new();
+
+    // A pool state naming the attacker as authority, in an account the attacker owns
+    let forged_pool_state = Pubkey::new_unique();
+    let mut data = vec![0; BettingPoolState::LEN];
+    BettingPoolState::pack(
+        BettingPoolState {
+            is_initialized: true,
+            locked_liquidity: 0,
+            live_liquidity: 0,
+            pending_bets: 0,
+            house_pool_usdt: Pubkey::new_unique(),
+            betting_pool_usdt: Pubkey::new_unique(),
+            insurance_fund_usdt: Pubkey::new_unique(),
+            divvy_foundation_proceeds_usdt: Pubkey::new_unique(),
+            frozen_betting: false,
+            insurance_fund_fee_bps: 0,
+            divvy_foundation_fee_bps: 0,
+            authority: attacker.pubkey(),
+            pending_authority: None,
+            roles: Roles::default(),
+            bet_cutoff_secs: 0,
+            crank_reward_lamports: 0,
+            exposure_limits: ExposureLimits::default(),
+        },
+        &mut data,
+    )
+    .unwrap();
+    let mut program_test = program_test(&program_id);
+    program_test.add_account(
+        forged_pool_state,
+        Account {
+            lamports: 1_000_000_000,
+            data,
+            owner: attacker.pubkey(),
+            executable: false,
+            rent_epoch: 0,
+        },
+    );
+    let mut divvy = funded_divvy(program_test, program_id).await;
+    let feed = divvy.create_manual_feed(-110, 0).await.unwrap().pubkey();
+    let market = divvy
+        .init_market(
+            &feed,
+            &[&feed, &feed, &feed],
+            &[],
+            BetType::MoneyLine,
+            OracleSource::Manual,
+        )
+        .await
+        .unwrap();
+
+    let update_feed = instruction::update_manual_feed(
+        &program_id,
+        &attacker.pubkey(),
+        &feed,
+        &forged_pool_state,
+        10_000,
+        0,
+        &[],
+    )
+    .unwrap();
+    let suspend = instruction::suspend_market(
+        &program_id,
+        &attacker.pubkey(),
+        &market,
+        &forged_pool_state,
+        true,
+        &[],
+    )
+    .unwrap();
+    let init_market = instruction::init_moneyline_market(
+        &program_id,
+        &attacker.pubkey(),
+        &divvy.payer.pubkey(),
+        &feed,
+        &forged_pool_state,
+        &[&feed, &feed, &feed],
+        &[],
+        u64::MAX,
+        BetType::MoneyLine,
+        OracleSource::Manual,
+        0,
+        0,
+        "TEST",
+        now() + 86_400,
+        &["Home", "Away", "Draw"],
+        &[],
+    )
+    .unwrap();
+    for instruction in [update_feed, suspend, init_market].iter().cloned() {
+        assert_eq!(
+            divvy
+                .process(&[instruction], &[&attacker])
+                .await
+                .unwrap_err(),
+            exchange_error(ExchangeError::InvalidBettingPoolStateAccount)
+        );
+    }
+    let market_state: Market = divvy.unpack(&market).await;
+    assert!(!market_state.suspended);
+}
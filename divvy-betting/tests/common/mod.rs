@@ -0,0 +1,731 @@
+#![allow(dead_code)]
+
+use divvybetting::{
+    error::ExchangeError,
+    instruction,
+    processor::Processor,
+    schema::{divvy_house_program_id, switchboard_devnet_id},
+    state::{BetType, BettingPoolState, ManualFeed, OracleSource},
+};
+use divvycommon::pda::{find_bet_address, find_market_address};
+use divvyhouse::state::HpLiquidity;
+use prost::Message;
+use solana_program::{
+    instruction::{Instruction, InstructionError},
+    native_token::LAMPORTS_PER_SOL,
+    program_pack::{IsInitialized, Pack},
+    pubkey::Pubkey,
+    system_instruction,
+};
+use solana_program_test::{processor, BanksClient, ProgramTest};
+use solana_sdk::{
+    account::Account,
+    hash::Hash,
+    signature::{read_keypair_file, Keypair, Signer},
+    transaction::{Transaction, TransactionError},
+};
+use spl_token::state::{Account as TokenAccount, Mint};
+use std::time::{SystemTime, UNIX_EPOCH};
+use switchboard_program::{AggregatorState, RoundResult, SwitchboardAccountType};
+
+pub const USDT: u64 = 1_000_000;
+
+/// Boots the betting program next to the house program it calls into.
+pub fn program_test(program_id: &Pubkey) -> ProgramTest {
+    let mut program_test =
+        ProgramTest::new("divvybetting", *program_id, processor!(Processor::process));
+    program_test.add_program(
+        "divvyhouse",
+        divvy_house_program_id::ID,
+        processor!(divvyhouse::processor::Processor::process),
+    );
+    program_test
+}
+
+/// The wall clock unix timestamp, which the test bank clock starts from.
+pub fn now() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_secs() as i64
+}
+
+/// A Switchboard aggregator account whose latest round resolved to `result`.
+pub fn aggregator_account(result: f64, round_open_timestamp: i64, num_success: i32) -> Account {
+    let round_result = RoundResult {
+        result: Some(result),
+        round_open_timestamp: Some(round_open_timestamp),
+        num_success: Some(num_success),
+        ..RoundResult::default()
+    };
+    let aggregator = AggregatorState {
+        current_round_result: Some(round_result.clone()),
+        last_round_result: Some(round_result),
+        ..AggregatorState::default()
+    };
+    let mut data = vec![SwitchboardAccountType::TYPE_AGGREGATOR as u8];
+    aggregator.encode_length_delimited(&mut data).unwrap();
+    Account {
+        lamports: 1_000_000_000,
+        data,
+        owner: switchboard_devnet_id::ID,
+        executable: false,
+        rent_epoch: 0,
+    }
+}
+
+/// Adds a fresh mock aggregator resolved to `result` and returns its address.
+pub fn add_aggregator(program_test: &mut ProgramTest, result: f64) -> Pubkey {
+    add_aggregator_round(program_test, result, now(), 1)
+}
+
+/// Adds a mock aggregator with a given round and returns its address.
+pub fn add_aggregator_round(
+    program_test: &mut ProgramTest,
+    result: f64,
+    round_open_timestamp: i64,
+    num_success: i32,
+) -> Pubkey {
+    let feed = Pubkey::new_unique();
+    program_test.add_account(
+        feed,
+        aggregator_account(result, round_open_timestamp, num_success),
+    );
+    feed
+}
+
+/// A Pyth price account trading at `price * 10^expo`.
+pub fn pyth_price_account(price: i64, expo: i32) -> Account {
+    let mut data = vec![0; 3312];
+    data[0..4].copy_from_slice(&0xa1b2c3d4u32.to_le_bytes());
+    data[4..8].copy_from_slice(&2u32.to_le_bytes());
+    data[8..12].copy_from_slice(&3u32.to_le_bytes());
+    data[20..24].copy_from_slice(&expo.to_le_bytes());
+    data[28..32].copy_from_slice(&1u32.to_le_bytes());
+    data[96..104].copy_from_slice(&now().to_le_bytes());
+    data[208..216].copy_from_slice(&price.to_le_bytes());
+    data[224..228].copy_from_slice(&1u32.to_le_bytes());
+    Account {
+        lamports: 1_000_000_000,
+        data,
+        owner: Pubkey::new_unique(),
+        executable: false,
+        rent_epoch: 0,
+    }
+}
+
+/// Adds a mock Pyth price account and returns its address.
+pub fn add_pyth_price(program_test: &mut ProgramTest, price: i64, expo: i32) -> Pubkey {
+    let feed = Pubkey::new_unique();
+    program_test.add_account(feed, pyth_price_account(price, expo));
+    feed
+}
+
+/// The program authority, read from the keypair the deploy scripts use.
+pub fn authority() -> Keypair {
+    read_keypair_file(concat!(env!("CARGO_MANIFEST_DIR"), "/divvy.json")).unwrap()
+}
+
+/// The error a single instruction transaction fails with.
+pub fn exchange_error(error: ExchangeError) -> TransactionError {
+    TransactionError::InstructionError(0, InstructionError::Custom(error as u32))
+}
+
+/// A user with a funded USDT account and an empty house token account.
+pub struct User {
+    pub keypair: Keypair,
+    pub usdt_account: Keypair,
+    pub ht_account: Keypair,
+}
+
+/// Both programs booted and initialized through their `Ownership` instructions.
+pub struct Divvy {
+    pub banks_client: BanksClient,
+    pub payer: Keypair,
+    pub recent_blockhash: Hash,
+    pub program_id: Pubkey,
+    pub authority: Keypair,
+    pub usdt_mint: Keypair,
+    pub hp_pda: Pubkey,
+    pub hp_bump_seed: u8,
+    pub hp_state: Keypair,
+    pub hp_usdt_account: Keypair,
+    pub hp_queue_ht_account: Keypair,
+    pub ht_mint: Keypair,
+    pub pda: Pubkey,
+    pub bump_seed: u8,
+    pub bet_pool_state: Keypair,
+    pub bet_usdt_account: Keypair,
+    pub insurance_fund_usdt_account: Keypair,
+    pub divvy_foundation_proceeds_usdt_account: Keypair,
+    /// Event id of the next market, so each test market gets its own address.
+    pub next_event_id: u64,
+    /// Nonce of the next bet.
+    pub next_nonce: u64,
+}
+
+impl Divvy {
+    pub async fn start(program_test: ProgramTest, program_id: Pubkey) -> Self {
+        let (banks_client, payer, recent_blockhash) = program_test.start().await;
+        let (hp_pda, hp_bump_seed) =
+            Pubkey::find_program_address(&[b"divvyhouse"], &divvy_house_program_id::ID);
+        let (pda, bump_seed) = Pubkey::find_program_address(&[b"divvybetting"], &program_id);
+        let mut divvy = Divvy {
+            banks_client,
+            payer,
+            recent_blockhash,
+            program_id,
+            authority: authority(),
+            usdt_mint: Keypair::new(),
+            hp_pda,
+            hp_bump_seed,
+            hp_state: Keypair::new(),
+            hp_usdt_account: Keypair::new(),
+            hp_queue_ht_account: Keypair::new(),
+            ht_mint: Keypair::new(),
+            pda,
+            bump_seed,
+            bet_pool_state: Keypair::new(),
+            bet_usdt_account: Keypair::new(),
+            insurance_fund_usdt_account: Keypair::new(),
+            divvy_foundation_proceeds_usdt_account: Keypair::new(),
+            next_event_id: 0,
+            next_nonce: 0,
+        };
+
+        let payer = divvy.payer.pubkey();
+        let usdt_mint = divvy.usdt_mint.pubkey();
+        divvy
+            .create_mint(&clone_keypair(&divvy.usdt_mint), &payer)
+            .await;
+        divvy
+            .create_mint(&clone_keypair(&divvy.ht_mint), &hp_pda)
+            .await;
+        for (account, owner) in [
+            (clone_keypair(&divvy.hp_usdt_account), hp_pda),
+            (clone_keypair(&divvy.bet_usdt_account), pda),
+            (clone_keypair(&divvy.insurance_fund_usdt_account), payer),
+            (
+                clone_keypair(&divvy.divvy_foundation_proceeds_usdt_account),
+                payer,
+            ),
+        ]
+        .iter()
+        {
+            divvy.create_token_account(account, &usdt_mint, owner).await;
+        }
+        let ht_mint = divvy.ht_mint.pubkey();
+        divvy
+            .create_token_account(
+                &clone_keypair(&divvy.hp_queue_ht_account),
+                &ht_mint,
+                &hp_pda,
+            )
+            .await;
+        divvy
+            .create_account(
+                &clone_keypair(&divvy.hp_state),
+                HpLiquidity::LEN,
+                &divvy_house_program_id::ID,
+            )
+            .await;
+        divvy
+            .create_account(
+                &clone_keypair(&divvy.bet_pool_state),
+                BettingPoolState::LEN,
+                &program_id,
+            )
+            .await;
+
+        let authority = clone_keypair(&divvy.authority);
+        divvy
+            .process(
+                &[
+                    divvyhouse::instruction::ownership(
+                        &divvy_house_program_id::ID,
+                        &authority.pubkey(),
+                        &divvy.hp_state.pubkey(),
+                        &divvy.ht_mint.pubkey(),
+                        &divvy.bet_usdt_account.pubkey(),
+                        &divvy.hp_usdt_account.pubkey(),
+                        &divvy.hp_queue_ht_account.pubkey(),
+                        &program_id,
+                        hp_bump_seed,
+                    )
+                    .unwrap(),
+                    instruction::ownership(
+                        &program_id,
+                        &authority.pubkey(),
+                        &divvy.bet_pool_state.pubkey(),
+                        &divvy.hp_usdt_account.pubkey(),
+                        &divvy.bet_usdt_account.pubkey(),
+                        &divvy.insurance_fund_usdt_account.pubkey(),
+                        &divvy.divvy_foundation_proceeds_usdt_account.pubkey(),
+                        bump_seed,
+                    )
+                    .unwrap(),
+                ],
+                &[&authority],
+            )
+            .await
+            .unwrap();
+        divvy
+    }
+
+    pub async fn process(
+        &mut self,
+        instructions: &[Instruction],
+        signers: &[&Keypair],
+    ) -> Result<(), TransactionError> {
+        let mut all_signers = vec![&self.payer];
+        all_signers.extend_from_slice(signers);
+        let transaction = Transaction::new_signed_with_payer(
+            instructions,
+            Some(&self.payer.pubkey()),
+            &all_signers,
+            self.recent_blockhash,
+        );
+        self.banks_client
+            .process_transaction(transaction)
+            .await
+            .map_err(|e| e.unwrap())
+    }
+
+    pub async fn create_account(&mut self, account: &Keypair, space: usize, owner: &Pubkey) {
+        let rent = self.banks_client.get_rent().await.unwrap();
+        let instruction = system_instruction::create_account(
+            &self.payer.pubkey(),
+            &account.pubkey(),
+            rent.minimum_balance(space),
+            space as u64,
+            owner,
+        );
+        self.process(&[instruction], &[account]).await.unwrap();
+    }
+
+    pub async fn create_mint(&mut self, mint: &Keypair, authority: &Pubkey) {
+        self.create_account(mint, Mint::LEN, &spl_token::id()).await;
+        let instruction = spl_token::instruction::initialize_mint(
+            &spl_token::id(),
+            &mint.pubkey(),
+            authority,
+            None,
+            6,
+        )
+        .unwrap();
+        self.process(&[instruction], &[]).await.unwrap();
+    }
+
+    pub async fn create_token_account(&mut self, account: &Keypair, mint: &Pubkey, owner: &Pubkey) {
+        self.create_account(account, TokenAccount::LEN, &spl_token::id())
+            .await;
+        let instruction = spl_token::instruction::initialize_account(
+            &spl_token::id(),
+            &account.pubkey(),
+            mint,
+            owner,
+        )
+        .unwrap();
+        self.process(&[instruction], &[]).await.unwrap();
+    }
+
+    /// Creates a user holding `usdt_amount` USDT and enough SOL for bet rent.
+    pub async fn create_user(&mut self, usdt_amount: u64) -> User {
+        let user = User {
+            keypair: Keypair::new(),
+            usdt_account: Keypair::new(),
+            ht_account: Keypair::new(),
+        };
+        let usdt_mint = self.usdt_mint.pubkey();
+        let ht_mint = self.ht_mint.pubkey();
+        self.create_token_account(&user.usdt_account, &usdt_mint, &user.keypair.pubkey())
+            .await;
+        self.create_token_account(&user.ht_account, &ht_mint, &user.keypair.pubkey())
+            .await;
+        let instruction = spl_token::instruction::mint_to(
+            &spl_token::id(),
+            &usdt_mint,
+            &user.usdt_account.pubkey(),
+            &self.payer.pubkey(),
+            &[],
+            usdt_amount,
+        )
+        .unwrap();
+        let fund = system_instruction::transfer(
+            &self.payer.pubkey(),
+            &user.keypair.pubkey(),
+            LAMPORTS_PER_SOL,
+        );
+        self.process(&[instruction, fund], &[]).await.unwrap();
+        user
+    }
+
+    pub async fn token_balance(&mut self, account: &Pubkey) -> u64 {
+        self.unpack::<TokenAccount>(account).await.amount
+    }
+
+    pub async fn unpack<T: Pack + IsInitialized>(&mut self, account: &Pubkey) -> T {
+        let account = self
+            .banks_client
+            .get_account(*account)
+            .await
+            .unwrap()
+            .unwrap();
+        T::unpack(&account.data).unwrap()
+    }
+
+    pub async fn account_exists(&mut self, account: &Pubkey) -> bool {
+        self.banks_client
+            .get_account(*account)
+            .await
+            .unwrap()
+            .map_or(false, |account| account.lamports > 0)
+    }
+
+    pub async fn deposit(&mut self, user: &User, usdt_amount: u64) -> Result<(), TransactionError> {
+        let instruction = divvyhouse::instruction::deposit(
+            &divvy_house_program_id::ID,
+            &user.keypair.pubkey(),
+            &self.ht_mint.pubkey(),
+            &user.ht_account.pubkey(),
+            &self.hp_pda,
+            &user.usdt_account.pubkey(),
+            &self.hp_usdt_account.pubkey(),
+            &self.hp_state.pubkey(),
+            usdt_amount,
+            self.hp_bump_seed,
+        )
+        .unwrap();
+        self.process(&[instruction], &[&user.keypair]).await
+    }
+
+    pub async fn withdraw(&mut self, user: &User, ht_amount: u64) -> Result<(), TransactionError> {
+        let instruction = divvyhouse::instruction::withdraw(
+            &divvy_house_program_id::ID,
+            &user.keypair.pubkey(),
+            &self.ht_mint.pubkey(),
+            &user.ht_account.pubkey(),
+            &self.hp_pda,
+            &user.usdt_account.pubkey(),
+            &self.hp_usdt_account.pubkey(),
+            &self.hp_state.pubkey(),
+            ht_amount,
+            self.hp_bump_seed,
+        )
+        .unwrap();
+        self.process(&[instruction], &[&user.keypair]).await
+    }
+
+    /// Queues `ht_amount` of the user's house tokens at the tail of the
+    /// withdrawal queue and returns the request sequence.
+    pub async fn request_withdrawal(
+        &mut self,
+        user: &User,
+        ht_amount: u64,
+    ) -> Result<u64, TransactionError> {
+        let hp_state: HpLiquidity = self.unpack(&self.hp_state.pubkey()).await;
+        let instruction = divvyhouse::instruction::request_withdrawal(
+            &divvy_house_program_id::ID,
+            &user.keypair.pubkey(),
+            &user.ht_account.pubkey(),
+            &self.hp_queue_ht_account.pubkey(),
+            &self.ht_mint.pubkey(),
+            &self.hp_usdt_account.pubkey(),
+            &self.hp_state.pubkey(),
+            hp_state.queue_tail,
+            ht_amount,
+        )
+        .unwrap();
+        self.process(&[instruction], &[&user.keypair]).await?;
+        Ok(hp_state.queue_tail)
+    }
+
+    /// Fills the head of the withdrawal queue, the user's request, into
+    /// their USDT account.
+    pub async fn fill_withdrawal(&mut self, user: &User) -> Result<(), TransactionError> {
+        let hp_state: HpLiquidity = self.unpack(&self.hp_state.pubkey()).await;
+        let instruction = divvyhouse::instruction::fill_withdrawal(
+            &divvy_house_program_id::ID,
+            &user.keypair.pubkey(),
+            &user.usdt_account.pubkey(),
+            &self.hp_queue_ht_account.pubkey(),
+            &self.ht_mint.pubkey(),
+            &self.hp_usdt_account.pubkey(),
+            &self.hp_state.pubkey(),
+            &self.hp_pda,
+            hp_state.queue_head,
+            self.hp_bump_seed,
+        )
+        .unwrap();
+        self.process(&[instruction], &[]).await
+    }
+
+    pub async fn init_market(
+        &mut self,
+        result_feed: &Pubkey,
+        odds_feeds: &[&Pubkey],
+        points_feeds: &[&Pubkey],
+        bet_type: BetType,
+        oracle_source: OracleSource,
+    ) -> Result<Pubkey, TransactionError> {
+        self.init_market_with_limits(
+            result_feed,
+            odds_feeds,
+            points_feeds,
+            bet_type,
+            oracle_source,
+            0,
+            0,
+        )
+        .await
+    }
+
+    pub async fn init_market_with_limits(
+        &mut self,
+        result_feed: &Pubkey,
+        odds_feeds: &[&Pubkey],
+        points_feeds: &[&Pubkey],
+        bet_type: BetType,
+        oracle_source: OracleSource,
+        max_staleness: u64,
+        min_responses: u32,
+    ) -> Result<Pubkey, TransactionError> {
+        let event_id = self.next_event_id;
+        self.next_event_id += 1;
+        let (market, _) = find_market_address(&self.program_id, event_id, bet_type);
+        let authority = clone_keypair(&self.authority);
+        let instruction = instruction::init_moneyline_market(
+            &self.program_id,
+            &authority.pubkey(),
+            &self.payer.pubkey(),
+            result_feed,
+            &self.bet_pool_state.pubkey(),
+            odds_feeds,
+            points_feeds,
+            event_id,
+            bet_type,
+            oracle_source,
+            max_staleness,
+            min_responses,
+            "TEST",
+            now() + 86_400,
+            &["Home", "Away", "Draw"][..odds_feeds.len()],
+            &[],
+        )
+        .unwrap();
+        self.process(&[instruction], &[&authority]).await?;
+        Ok(market)
+    }
+
+    pub async fn create_manual_feed(
+        &mut self,
+        value: i64,
+        expo: i32,
+    ) -> Result<Keypair, TransactionError> {
+        let feed = Keypair::new();
+        self.create_account(&feed, ManualFeed::LEN, &self.program_id.clone())
+            .await;
+        self.update_manual_feed(&feed.pubkey(), value, expo).await?;
+        Ok(feed)
+    }
+
+    pub async fn update_manual_feed(
+        &mut self,
+        feed: &Pubkey,
+        value: i64,
+        expo: i32,
+    ) -> Result<(), TransactionError> {
+        let authority = clone_keypair(&self.authority);
+        let instruction = instruction::update_manual_feed(
+            &self.program_id,
+            &authority.pubkey(),
+            feed,
+            &self.bet_pool_state.pubkey(),
+            value,
+            expo,
+            &[],
+        )
+        .unwrap();
+        self.process(&[instruction], &[&authority]).await
+    }
+
+    pub async fn init_bet(
+        &mut self,
+        user: &User,
+        market: &Pubkey,
+        feed: &Pubkey,
+        risk: u64,
+        odds: i64,
+        market_side: u8,
+        odds_tolerance_bps: u16,
+    ) -> Result<Pubkey, TransactionError> {
+        self.init_bet_with_points_feed(
+            user,
+            market,
+            feed,
+            None,
+            risk,
+            odds,
+            market_side,
+            odds_tolerance_bps,
+        )
+        .await
+    }
+
+    pub async fn init_bet_with_points_feed(
+        &mut self,
+        user: &User,
+        market: &Pubkey,
+        feed: &Pubkey,
+        points_feed: Option<&Pubkey>,
+        risk: u64,
+        odds: i64,
+        market_side: u8,
+        odds_tolerance_bps: u16,
+    ) -> Result<Pubkey, TransactionError> {
+        let nonce = self.next_nonce;
+        self.next_nonce += 1;
+        let (bet, _) = find_bet_address(&self.program_id, market, &user.keypair.pubkey(), nonce);
+        let instruction = instruction::init_bet(
+            &self.program_id,
+            &user.keypair.pubkey(),
+            feed,
+            points_feed,
+            market,
+            &self.bet_pool_state.pubkey(),
+            &self.hp_usdt_account.pubkey(),
+            &self.hp_state.pubkey(),
+            &self.bet_usdt_account.pubkey(),
+            &user.usdt_account.pubkey(),
+            risk,
+            odds,
+            market_side,
+            odds_tolerance_bps,
+            nonce,
+        )
+        .unwrap();
+        self.process(&[instruction], &[&user.keypair]).await?;
+        Ok(bet)
+    }
+
+    pub async fn commence_market(&mut self, market: &Pubkey) -> Result<(), TransactionError> {
+        let authority = clone_keypair(&self.authority);
+        let instruction = instruction::commence_market(
+            &self.program_id,
+            &authority.pubkey(),
+            market,
+            &self.hp_pda,
+            &self.pda,
+            &self.bet_usdt_account.pubkey(),
+            &self.hp_usdt_account.pubkey(),
+            &self.hp_state.pubkey(),
+            &self.bet_pool_state.pubkey(),
+            &divvy_house_program_id::ID,
+            &self.payer.pubkey(),
+            self.hp_bump_seed,
+            self.bump_seed,
+            &[],
+        )
+        .unwrap();
+        self.process(&[instruction], &[&authority]).await
+    }
+
+    pub async fn settle_market(
+        &mut self,
+        market: &Pubkey,
+        result_feed: &Pubkey,
+    ) -> Result<(), TransactionError> {
+        let authority = clone_keypair(&self.authority);
+        let instruction = instruction::settle_moneyline_market(
+            &self.program_id,
+            &authority.pubkey(),
+            market,
+            &self.bet_pool_state.pubkey(),
+            result_feed,
+            &self.pda,
+            &self.hp_usdt_account.pubkey(),
+            &self.bet_usdt_account.pubkey(),
+            &self.insurance_fund_usdt_account.pubkey(),
+            &self.divvy_foundation_proceeds_usdt_account.pubkey(),
+            &self.hp_state.pubkey(),
+            &divvy_house_program_id::ID,
+            self.bump_seed,
+        )
+        .unwrap();
+        self.process(&[instruction], &[&authority]).await
+    }
+
+    pub async fn void_market(&mut self, market: &Pubkey) -> Result<(), TransactionError> {
+        let authority = clone_keypair(&self.authority);
+        let instruction = instruction::void_market(
+            &self.program_id,
+            &authority.pubkey(),
+            market,
+            &self.bet_pool_state.pubkey(),
+            &self.pda,
+            &self.hp_usdt_account.pubkey(),
+            &self.bet_usdt_account.pubkey(),
+            &self.hp_state.pubkey(),
+            &divvy_house_program_id::ID,
+            self.bump_seed,
+            &[],
+        )
+        .unwrap();
+        self.process(&[instruction], &[&authority]).await
+    }
+
+    pub async fn settle_bet(
+        &mut self,
+        user: &User,
+        market: &Pubkey,
+        bet: &Pubkey,
+    ) -> Result<(), TransactionError> {
+        let instruction = instruction::settle_bet(
+            &self.program_id,
+            &self.payer.pubkey(),
+            market,
+            bet,
+            &self.pda,
+            &self.bet_usdt_account.pubkey(),
+            &user.usdt_account.pubkey(),
+            &user.keypair.pubkey(),
+            &self.bet_pool_state.pubkey(),
+            &self.hp_usdt_account.pubkey(),
+            &self.hp_state.pubkey(),
+            &divvy_house_program_id::ID,
+            self.bump_seed,
+        )
+        .unwrap();
+        self.process(&[instruction], &[]).await
+    }
+
+    pub async fn update_fees(
+        &mut self,
+        insurance_fund_fee_bps: u16,
+        divvy_foundation_fee_bps: u16,
+    ) -> Result<(), TransactionError> {
+        let authority = clone_keypair(&self.authority);
+        let instruction = instruction::update_fees(
+            &self.program_id,
+            &authority.pubkey(),
+            &self.bet_pool_state.pubkey(),
+            insurance_fund_fee_bps,
+            divvy_foundation_fee_bps,
+            &[],
+        )
+        .unwrap();
+        self.process(&[instruction], &[&authority]).await
+    }
+}
+
+/// Boots the programs with 1,000 USDT deposited in the house pool.
+pub async fn funded_divvy(program_test: ProgramTest, program_id: Pubkey) -> Divvy {
+    let mut divvy = Divvy::start(program_test, program_id).await;
+    let lp = divvy.create_user(1_000 * USDT).await;
+    divvy.deposit(&lp, 1_000 * USDT).await.unwrap();
+    divvy
+}
+
+fn clone_keypair(keypair: &Keypair) -> Keypair {
+    Keypair::from_bytes(&keypair.to_bytes()).unwrap()
+}
@@ -0,0 +1,172 @@
+mod common;
+
+use common::{exchange_error, funded_divvy, program_test, USDT};
+use divvybetting::{
+    error::ExchangeError,
+    instruction,
+    schema::divvy_house_program_id,
+    state::{Bet, BetType, BettingPoolState, OracleSource},
+};
+use solana_program::{program_pack::Pack, pubkey::Pubkey};
+use solana_sdk::signature::{Keypair, Signer};
+
+#[tokio::test]
+async fn test_keeper_settles_bets_in_batches() {
+    let program_id = Pubkey::new_unique();
+    let mut divvy = funded_divvy(program_test(&program_id), program_id).await;
+    let bet_pool_state = divvy.bet_pool_state.pubkey();
+    let authority = Keypair::from_bytes(&divvy.authority.to_bytes()).unwrap();
+    let side_0_feed = divvy.create_manual_feed(-110, 0).await.unwrap().pubkey();
+    let side_1_feed = divvy.create_manual_feed(150, 0).await.unwrap().pubkey();
+    let side_2_feed = divvy.create_manual_feed(300, 0).await.unwrap().pubkey();
+    let result_feed = divvy.create_manual_feed(0, 0).await.unwrap().pubkey();
+    let market = divvy
+        .init_market(
+            &result_feed,
+            &[&side_0_feed, &side_1_feed, &side_2_feed],
+            &[],
+            BetType::MoneyLine,
+            OracleSource::Manual,
+        )
+        .await
+        .unwrap();
+    let winner = divvy.create_user(300 * USDT).await;
+    let loser = divvy.create_user(300 * USDT).await;
+    let keeper = divvy.create_user(0).await;
+    let winning_bet = divvy
+        .init_bet(&winner, &market, &side_0_feed, 110 * USDT, -110, 0, 0)
+        .await
+        .unwrap();
+    let losing_bet = divvy
+        .init_bet(&loser, &market, &side_1_feed, 100 * USDT, 150, 1, 0)
+        .await
+        .unwrap();
+    let other_losing_bet = divvy
+        .init_bet(&loser, &market, &side_2_feed, 50 * USDT, 300, 2, 0)
+        .await
+        .unwrap();
+
+    // The crank reward comes out of the bet rent, so it is capped by it
+    let rent = divvy.banks_client.get_rent().await.unwrap();
+    let bet_rent = rent.minimum_balance(Bet::LEN);
+    let instruction = instruction::update_crank_reward(
+        &program_id,
+        &authority.pubkey(),
+        &bet_pool_state,
+        bet_rent + 1,
+        &[],
+    )
+    .unwrap();
+    assert_eq!(
+        divvy
+            .process(&[instruction], &[&authority])
+            .await
+            .unwrap_err(),
+        exchange_error(ExchangeError::CrankRewardTooHigh)
+    );
+    let crank_reward = 5_000;
+    let instruction = instruction::update_crank_reward(
+        &program_id,
+        &authority.pubkey(),
+        &bet_pool_state,
+        crank_reward,
+        &[],
+    )
+    .unwrap();
+    divvy.process(&[instruction], &[&authority]).await.unwrap();
+    let pool_state: BettingPoolState = divvy.unpack(&bet_pool_state).await;
+    assert_eq!(pool_state.crank_reward_lamports, crank_reward);
+
+    let winner_usdt = winner.usdt_account.pubkey();
+    let winner_main = winner.keypair.pubkey();
+    let loser_usdt = loser.usdt_account.pubkey();
+    let loser_main = loser.keypair.pubkey();
+    let pda = divvy.pda;
+    let bet_usdt = divvy.bet_usdt_account.pubkey();
+    let hp_usdt = divvy.hp_usdt_account.pubkey();
+    let hp_state = divvy.hp_state.pubkey();
+    let bump_seed = divvy.bump_seed;
+    let settle_bets = |bets: &[(&Pubkey, &Pubkey, &Pubkey)]| {
+        instruction::settle_bets(
+            &program_id,
+            &keeper.keypair.pubkey(),
+            &market,
+            &pda,
+            &bet_usdt,
+            &bet_pool_state,
+            &hp_usdt,
+            &hp_state,
+            &divvy_house_program_id::ID,
+            bets,
+            bump_seed,
+        )
+        .unwrap()
+    };
+    let all_bets = settle_bets(&[
+        (&winning_bet, &winner_usdt, &winner_main),
+        (&losing_bet, &loser_usdt, &loser_main),
+        (&other_losing_bet, &loser_usdt, &loser_main),
+    ]);
+
+    let winning_bet_only = settle_bets(&[(&winning_bet, &winner_usdt, &winner_main)]);
+    assert_eq!(
+        divvy.process(&[winning_bet_only], &[]).await.unwrap_err(),
+        exchange_error(ExchangeError::MarketNotSettled)
+    );
+    divvy.commence_market(&market).await.unwrap();
+    divvy.settle_market(&market, &result_feed).await.unwrap();
+
+    // Every bet needs its user accounts
+    let mut missing_account = settle_bets(&[(&winning_bet, &winner_usdt, &winner_main)]);
+    missing_account.accounts.pop();
+    assert_eq!(
+        divvy.process(&[missing_account], &[]).await.unwrap_err(),
+        exchange_error(ExchangeError::InvalidInstruction)
+    );
+    // Winnings only go to the USDT account recorded in the bet
+    let wrong_user = settle_bets(&[(&winning_bet, &loser_usdt, &winner_main)]);
+    assert_eq!(
+        divvy.process(&[wrong_user], &[]).await.unwrap_err(),
+        exchange_error(ExchangeError::ExpectedDataMismatch)
+    );
+
+    let winner_lamports = divvy.banks_client.get_balance(winner_main).await.unwrap();
+    let loser_lamports = divvy.banks_client.get_balance(loser_main).await.unwrap();
+    let keeper_lamports = divvy
+        .banks_client
+        .get_balance(keeper.keypair.pubkey())
+        .await
+        .unwrap();
+    divvy.process(&[all_bets], &[]).await.unwrap();
+
+    for bet in [winning_bet, losing_bet, other_losing_bet].iter() {
+        assert!(!divvy.account_exists(bet).await);
+    }
+    assert_eq!(divvy.token_balance(&winner_usdt).await, 400 * USDT);
+    assert_eq!(divvy.token_balance(&loser_usdt).await, 150 * USDT);
+    assert_eq!(
+        divvy.banks_client.get_balance(winner_main).await.unwrap(),
+        winner_lamports + bet_rent - crank_reward
+    );
+    assert_eq!(
+        divvy.banks_client.get_balance(loser_main).await.unwrap(),
+        loser_lamports + 2 * (bet_rent - crank_reward)
+    );
+    assert_eq!(
+        divvy
+            .banks_client
+            .get_balance(keeper.keypair.pubkey())
+            .await
+            .unwrap(),
+        keeper_lamports + 3 * crank_reward
+    );
+    let pool_state: BettingPoolState = divvy.unpack(&bet_pool_state).await;
+    assert_eq!(pool_state.pending_bets, 0);
+
+    // Closed bets can not be settled again
+    let settled_again = settle_bets(&[(&losing_bet, &loser_usdt, &loser_main)]);
+    assert_eq!(
+        divvy.process(&[settled_again], &[]).await.unwrap_err(),
+        exchange_error(ExchangeError::InvalidBetAccount)
+    );
+}
@@ -0,0 +1,118 @@
+mod common;
+
+use common::{exchange_error, funded_divvy, program_test, USDT};
+use divvybetting::{
+    error::ExchangeError,
+    instruction,
+    schema::divvy_house_program_id,
+    state::{BetType, BettingPoolState, Market, MoneylineMarketOutcome, OracleSource, Role},
+};
+use solana_program::pubkey::Pubkey;
+use solana_sdk::signature::{Keypair, Signer};
+
+#[tokio::test]
+async fn test_betting_closes_before_start_time() {
+    let program_id = Pubkey::new_unique();
+    let mut divvy = funded_divvy(program_test(&program_id), program_id).await;
+    let bet_pool_state = divvy.bet_pool_state.pubkey();
+    let authority = Keypair::from_bytes(&divvy.authority.to_bytes()).unwrap();
+    let settler = Keypair::new();
+    let keeper = Keypair::new();
+    let side_0_feed = divvy.create_manual_feed(-110, 0).await.unwrap().pubkey();
+    let side_1_feed = divvy.create_manual_feed(150, 0).await.unwrap().pubkey();
+    let side_2_feed = divvy.create_manual_feed(300, 0).await.unwrap().pubkey();
+    let result_feed = divvy.create_manual_feed(0, 0).await.unwrap().pubkey();
+    // The test markets start a day from now
+    let market = divvy
+        .init_market(
+            &result_feed,
+            &[&side_0_feed, &side_1_feed, &side_2_feed],
+            &[],
+            BetType::MoneyLine,
+            OracleSource::Manual,
+        )
+        .await
+        .unwrap();
+    let user = divvy.create_user(300 * USDT).await;
+    divvy
+        .init_bet(&user, &market, &side_1_feed, 100 * USDT, 150, 1, 0)
+        .await
+        .unwrap();
+
+    let commence = |initializer: &Pubkey| {
+        instruction::commence_market(
+            &program_id,
+            initializer,
+            &market,
+            &divvy.hp_pda,
+            &divvy.pda,
+            &divvy.bet_usdt_account.pubkey(),
+            &divvy.hp_usdt_account.pubkey(),
+            &divvy.hp_state.pubkey(),
+            &bet_pool_state,
+            &divvy_house_program_id::ID,
+            &divvy.payer.pubkey(),
+            divvy.hp_bump_seed,
+            divvy.bump_seed,
+            &[],
+        )
+        .unwrap()
+    };
+    let settler_commence = commence(&settler.pubkey());
+    let keeper_commence = commence(&keeper.pubkey());
+    // Before betting closes only a settler can commence the market
+    assert_eq!(
+        divvy
+            .process(&[settler_commence], &[&settler])
+            .await
+            .unwrap_err(),
+        exchange_error(ExchangeError::NotValidAuthority)
+    );
+
+    // Only the authority or a settler sets the cutoff
+    let instruction = instruction::update_bet_cutoff(
+        &program_id,
+        &settler.pubkey(),
+        &bet_pool_state,
+        2 * 86_400,
+        &[],
+    )
+    .unwrap();
+    assert_eq!(
+        divvy
+            .process(&[instruction.clone()], &[&settler])
+            .await
+            .unwrap_err(),
+        exchange_error(ExchangeError::NotValidAuthority)
+    );
+    let grant = instruction::grant_role(
+        &program_id,
+        &authority.pubkey(),
+        &bet_pool_state,
+        Role::Settler,
+        &settler.pubkey(),
+        &[],
+    )
+    .unwrap();
+    divvy
+        .process(&[grant, instruction], &[&authority, &settler])
+        .await
+        .unwrap();
+    let pool_state: BettingPoolState = divvy.unpack(&bet_pool_state).await;
+    assert_eq!(pool_state.bet_cutoff_secs, 2 * 86_400);
+
+    // Closing betting two days before the start closes it now
+    assert_eq!(
+        divvy
+            .init_bet(&user, &market, &side_0_feed, 10 * USDT, -110, 0, 0)
+            .await
+            .unwrap_err(),
+        exchange_error(ExchangeError::BettingClosed)
+    );
+    divvy.process(&[keeper_commence], &[&keeper]).await.unwrap();
+    let market_state: Market = divvy.unpack(&market).await;
+    assert!(market_state.result == MoneylineMarketOutcome::Commenced);
+    let pool_state: BettingPoolState = divvy.unpack(&bet_pool_state).await;
+    assert_eq!(pool_state.locked_liquidity, 0);
+    assert_eq!(pool_state.live_liquidity, market_state.locked_liquidity);
+}
@@ -0,0 +1,109 @@
+mod common;
+
+use common::{exchange_error, funded_divvy, program_test, USDT};
+use divvybetting::{
+    error::ExchangeError,
+    instruction,
+    state::{BetType, BettingPoolState, OracleSource},
+};
+use divvycommon::fees::{
+    DEFAULT_DIVVY_FOUNDATION_FEE_BPS, DEFAULT_INSURANCE_FUND_FEE_BPS, MAX_INSURANCE_FUND_FEE_BPS,
+};
+use solana_program::pubkey::Pubkey;
+use solana_sdk::signature::Signer;
+
+#[tokio::test]
+async fn test_update_fees() {
+    let program_id = Pubkey::new_unique();
+    let mut divvy = funded_divvy(program_test(&program_id), program_id).await;
+
+    let user = divvy.create_user(0).await;
+    let instruction = instruction::update_fees(
+        &program_id,
+        &user.keypair.pubkey(),
+        &divvy.bet_pool_state.pubkey(),
+        0,
+        0,
+        &[],
+    )
+    .unwrap();
+    assert_eq!(
+        divvy
+            .process(&[instruction], &[&user.keypair])
+            .await
+            .unwrap_err(),
+        exchange_error(ExchangeError::NotValidAuthority)
+    );
+    assert_eq!(
+        divvy
+            .update_fees(MAX_INSURANCE_FUND_FEE_BPS + 1, 0)
+            .await
+            .unwrap_err(),
+        exchange_error(ExchangeError::FeeTooHigh)
+    );
+    let pool_state: BettingPoolState = divvy.unpack(&divvy.bet_pool_state.pubkey()).await;
+    assert_eq!(
+        pool_state.insurance_fund_fee_bps,
+        DEFAULT_INSURANCE_FUND_FEE_BPS
+    );
+    assert_eq!(
+        pool_state.divvy_foundation_fee_bps,
+        DEFAULT_DIVVY_FOUNDATION_FEE_BPS
+    );
+
+    divvy.update_fees(200, 300).await.unwrap();
+    let pool_state: BettingPoolState = divvy.unpack(&divvy.bet_pool_state.pubkey()).await;
+    assert_eq!(pool_state.insurance_fund_fee_bps, 200);
+    assert_eq!(pool_state.divvy_foundation_fee_bps, 300);
+}
+
+#[tokio::test]
+async fn test_settlement_takes_updated_fees() {
+    let program_id = Pubkey::new_unique();
+    let mut divvy = funded_divvy(program_test(&program_id), program_id).await;
+    divvy.update_fees(200, 300).await.unwrap();
+
+    let side_0_feed = divvy.create_manual_feed(-110, 0).await.unwrap().pubkey();
+    let side_1_feed = divvy.create_manual_feed(150, 0).await.unwrap().pubkey();
+    let side_2_feed = divvy.create_manual_feed(300, 0).await.unwrap().pubkey();
+    let result_feed = divvy.create_manual_feed(0, 0).await.unwrap().pubkey();
+    let market = divvy
+        .init_market(
+            &result_feed,
+            &[&side_0_feed, &side_1_feed, &side_2_feed],
+            &[],
+            BetType::MoneyLine,
+            OracleSource::Manual,
+        )
+        .await
+        .unwrap();
+    let user = divvy.create_user(100 * USDT).await;
+    divvy
+        .init_bet(&user, &market, &side_1_feed, 100 * USDT, 150, 1, 0)
+        .await
+        .unwrap();
+    divvy.commence_market(&market).await.unwrap();
+    divvy.settle_market(&market, &result_feed).await.unwrap();
+
+    // The house made 100 USDT
+    assert_eq!(
+        divvy
+            .token_balance(&divvy.insurance_fund_usdt_account.pubkey())
+            .await,
+        2 * USDT
+    );
+    assert_eq!(
+        divvy
+            .token_balance(&divvy.divvy_foundation_proceeds_usdt_account.pubkey())
+            .await,
+        3 * USDT
+    );
+    assert_eq!(
+        divvy.token_balance(&divvy.hp_usdt_account.pubkey()).await,
+        1_095 * USDT
+    );
+    assert_eq!(
+        divvy.token_balance(&divvy.bet_usdt_account.pubkey()).await,
+        0
+    );
+}
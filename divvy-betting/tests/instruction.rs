@@ -0,0 +1,669 @@
+use divvybetting::{
+    error::ExchangeError,
+    instruction::{self, ExchangeInstruction},
+    state::{BetType, ExposureLimits, OracleSource, Role, LABEL_LEN, LEAGUE_LEN},
+};
+use divvycommon::{
+    pack_text,
+    pda::{find_bet_address, find_ledger_address, find_market_address, find_position_address},
+};
+use solana_program::{pubkey::Pubkey, system_program};
+
+fn assert_round_trip(instruction: ExchangeInstruction) {
+    let packed = instruction.pack();
+    let unpacked = ExchangeInstruction::unpack(&packed).unwrap();
+    assert_eq!(unpacked, instruction);
+}
+
+#[test]
+fn test_pack_unpack_round_trip() {
+    assert_round_trip(ExchangeInstruction::Initbet {
+        risk: 25_000_000,
+        odds: -110,
+        market_side: 2,
+        odds_tolerance_bps: 50,
+        nonce: u64::MAX,
+    });
+    assert_round_trip(ExchangeInstruction::SettleBet { bump_seed: 255 });
+    assert_round_trip(ExchangeInstruction::InitMoneylineMarket {
+        event_id: 0,
+        bet_type: BetType::MoneyLine,
+        oracle_source: OracleSource::SwitchboardV1,
+        max_staleness: 0,
+        min_responses: 0,
+        league: [0; LEAGUE_LEN],
+        start_time: 0,
+        side_labels: [[0; LABEL_LEN]; 3],
+    });
+    assert_round_trip(ExchangeInstruction::InitMoneylineMarket {
+        event_id: 4_815_162_342,
+        bet_type: BetType::Total,
+        oracle_source: OracleSource::Manual,
+        max_staleness: 600,
+        min_responses: 3,
+        league: pack_text("NFL").unwrap(),
+        start_time: i64::MAX,
+        side_labels: [
+            pack_text("Over").unwrap(),
+            pack_text("Under").unwrap(),
+            [0; LABEL_LEN],
+        ],
+    });
+    assert_round_trip(ExchangeInstruction::SettleMoneylineMarket { bump_seed: 253 });
+    assert_round_trip(ExchangeInstruction::Ownership { bump_seed: 252 });
+    assert_round_trip(ExchangeInstruction::CommenceMarket {
+        hp_bump_seed: 251,
+        bump_seed: 250,
+    });
+    assert_round_trip(ExchangeInstruction::Freeze {
+        freeze_betting: true,
+    });
+    assert_round_trip(ExchangeInstruction::Freeze {
+        freeze_betting: false,
+    });
+    assert_round_trip(ExchangeInstruction::UpdateManualFeed {
+        value: -1_105,
+        expo: -1,
+    });
+    assert_round_trip(ExchangeInstruction::UpdateManualFeed {
+        value: i64::MIN,
+        expo: i32::MAX,
+    });
+    assert_round_trip(ExchangeInstruction::UpdateFees {
+        insurance_fund_fee_bps: 100,
+        divvy_foundation_fee_bps: u16::MAX,
+    });
+    assert_round_trip(ExchangeInstruction::ProposeAuthority {
+        new_authority: Pubkey::new_unique(),
+    });
+    assert_round_trip(ExchangeInstruction::AcceptAuthority);
+    assert_round_trip(ExchangeInstruction::InitMultisig { m: 2 });
+    assert_round_trip(ExchangeInstruction::GrantRole {
+        role: Role::MarketCreator,
+        member: Pubkey::new_unique(),
+    });
+    assert_round_trip(ExchangeInstruction::RevokeRole {
+        role: Role::Treasurer,
+        member: Pubkey::new_unique(),
+    });
+    assert_round_trip(ExchangeInstruction::UpdateBetCutoff {
+        bet_cutoff_secs: u64::MAX,
+    });
+    assert_round_trip(ExchangeInstruction::SettleBets { bump_seed: 254 });
+    assert_round_trip(ExchangeInstruction::UpdateCrankReward {
+        crank_reward_lamports: 5_000,
+    });
+    assert_round_trip(ExchangeInstruction::VoidMarket { bump_seed: 255 });
+    assert_round_trip(ExchangeInstruction::SuspendMarket { suspended: true });
+    assert_round_trip(ExchangeInstruction::SuspendMarket { suspended: false });
+    assert_round_trip(ExchangeInstruction::UpdateExposureLimits {
+        exposure_limits: ExposureLimits::default(),
+    });
+    assert_round_trip(ExchangeInstruction::UpdateExposureLimits {
+        exposure_limits: ExposureLimits {
+            max_market_exposure_bps: 2_500,
+            max_side_payout: u64::MAX,
+            min_bet_risk: 1_000_000,
+            max_bet_risk: 500_000_000,
+            max_wallet_risk: 1_000_000_000,
+            max_wallet_payout: 0,
+        },
+    });
+}
+
+#[test]
+fn test_unpack_invalid() {
+    assert!(ExchangeInstruction::unpack(&[]).is_err());
+    assert!(ExchangeInstruction::unpack(&[7]).is_err());
+    assert!(ExchangeInstruction::unpack(&[0, 1, 0, 0]).is_err());
+    assert!(ExchangeInstruction::unpack(&[5, 255]).is_err());
+    assert!(ExchangeInstruction::unpack(&[8, 100, 0, 1]).is_err());
+    assert!(ExchangeInstruction::unpack(&[9; 32]).is_err());
+    assert!(ExchangeInstruction::unpack(&[11]).is_err());
+    let mut grant_unknown_role = vec![12, 4];
+    grant_unknown_role.extend_from_slice(Pubkey::new_unique().as_ref());
+    assert!(ExchangeInstruction::unpack(&grant_unknown_role).is_err());
+    assert!(ExchangeInstruction::unpack(&[14, 0, 0, 0, 0]).is_err());
+    assert!(ExchangeInstruction::unpack(&[18]).is_err());
+    assert!(ExchangeInstruction::unpack(&[19, 0, 0]).is_err());
+    let bet_on_fourth_side = ExchangeInstruction::Initbet {
+        risk: 25_000_000,
+        odds: -110,
+        market_side: 3,
+        odds_tolerance_bps: 50,
+        nonce: 0,
+    };
+    assert_eq!(
+        ExchangeInstruction::unpack(&bet_on_fourth_side.pack()).unwrap_err(),
+        ExchangeError::InvalidInstruction.into()
+    );
+}
+
+#[test]
+fn test_init_bet_builder() {
+    let program_id = Pubkey::new_unique();
+    let initializer = Pubkey::new_unique();
+    let feed = Pubkey::new_unique();
+    let market = Pubkey::new_unique();
+    let (bet, _) = find_bet_address(&program_id, &market, &initializer, 7);
+    let (position, _) = find_position_address(&program_id, &market, &initializer);
+    let bet_pool_state = Pubkey::new_unique();
+    let hp_usdt = Pubkey::new_unique();
+    let hp_state = Pubkey::new_unique();
+    let bet_usdt = Pubkey::new_unique();
+    let user_usdt = Pubkey::new_unique();
+    let ix = instruction::init_bet(
+        &program_id,
+        &initializer,
+        &feed,
+        None,
+        &market,
+        &bet_pool_state,
+        &hp_usdt,
+        &hp_state,
+        &bet_usdt,
+        &user_usdt,
+        10,
+        -120,
+        1,
+        100,
+        7,
+    )
+    .unwrap();
+
+    assert_eq!(ix.program_id, program_id);
+    assert_eq!(
+        ExchangeInstruction::unpack(&ix.data).unwrap(),
+        ExchangeInstruction::Initbet {
+            risk: 10,
+            odds: -120,
+            market_side: 1,
+            odds_tolerance_bps: 100,
+            nonce: 7,
+        }
+    );
+    let keys: Vec<Pubkey> = ix.accounts.iter().map(|meta| meta.pubkey).collect();
+    assert_eq!(
+        keys,
+        vec![
+            initializer,
+            feed,
+            bet,
+            position,
+            market,
+            bet_pool_state,
+            hp_usdt,
+            hp_state,
+            bet_usdt,
+            user_usdt,
+            spl_token::id(),
+            system_program::id()
+        ]
+    );
+    assert!(ix.accounts[0].is_signer);
+    assert!(ix.accounts[0].is_writable);
+    assert!(ix.accounts[2].is_writable);
+    assert!(ix.accounts[3].is_writable);
+
+    let points_feed = Pubkey::new_unique();
+    let ix = instruction::init_bet(
+        &program_id,
+        &initializer,
+        &feed,
+        Some(&points_feed),
+        &market,
+        &bet_pool_state,
+        &hp_usdt,
+        &hp_state,
+        &bet_usdt,
+        &user_usdt,
+        10,
+        -120,
+        1,
+        100,
+        7,
+    )
+    .unwrap();
+    assert_eq!(ix.accounts.len(), 13);
+    assert_eq!(ix.accounts[12].pubkey, points_feed);
+    assert!(!ix.accounts[12].is_writable);
+}
+
+#[test]
+fn test_commence_market_builder() {
+    let program_id = Pubkey::new_unique();
+    let house_program_id = Pubkey::new_unique();
+    let keys: Vec<Pubkey> = (0..9).map(|_| Pubkey::new_unique()).collect();
+    let ix = instruction::commence_market(
+        &program_id,
+        &keys[0],
+        &keys[1],
+        &keys[2],
+        &keys[3],
+        &keys[4],
+        &keys[5],
+        &keys[6],
+        &keys[7],
+        &house_program_id,
+        &keys[8],
+        254,
+        253,
+        &[],
+    )
+    .unwrap();
+
+    assert_eq!(
+        ExchangeInstruction::unpack(&ix.data).unwrap(),
+        ExchangeInstruction::CommenceMarket {
+            hp_bump_seed: 254,
+            bump_seed: 253,
+        }
+    );
+    assert_eq!(ix.accounts.len(), 13);
+    assert_eq!(ix.accounts[2].pubkey, spl_token::id());
+    assert_eq!(ix.accounts[9].pubkey, house_program_id);
+    // The payer funds the house pool's ledger of the market
+    assert!(ix.accounts[10].is_signer);
+    assert!(ix.accounts[10].is_writable);
+    let (ledger, _) = find_ledger_address(&house_program_id, &keys[6], &keys[1]);
+    assert_eq!(ix.accounts[11].pubkey, ledger);
+    assert_eq!(ix.accounts[12].pubkey, system_program::id());
+}
+
+#[test]
+fn test_builders_pack_their_variant() {
+    let program_id = Pubkey::new_unique();
+    let key = Pubkey::new_unique();
+
+    let ix = instruction::settle_bet(
+        &program_id,
+        &key,
+        &key,
+        &key,
+        &key,
+        &key,
+        &key,
+        &key,
+        &key,
+        &key,
+        &key,
+        &key,
+        3,
+    )
+    .unwrap();
+    assert_eq!(ix.accounts.len(), 13);
+    assert_eq!(
+        ExchangeInstruction::unpack(&ix.data).unwrap(),
+        ExchangeInstruction::SettleBet { bump_seed: 3 }
+    );
+    let bets = [Pubkey::new_unique(), Pubkey::new_unique()];
+    let ix = instruction::settle_bets(
+        &program_id,
+        &key,
+        &key,
+        &key,
+        &key,
+        &key,
+        &key,
+        &key,
+        &key,
+        &[(&bets[0], &key, &key), (&bets[1], &key, &key)],
+        3,
+    )
+    .unwrap();
+    assert_eq!(ix.accounts.len(), 16);
+    // Anyone can crank, so the keeper does not have to sign
+    assert!(!ix.accounts[0].is_signer);
+    assert!(ix.accounts[0].is_writable);
+    assert_eq!(ix.accounts[10].pubkey, bets[0]);
+    assert_eq!(ix.accounts[13].pubkey, bets[1]);
+    assert!(ix.accounts[10..].iter().all(|account| account.is_writable));
+    assert_eq!(
+        ExchangeInstruction::unpack(&ix.data).unwrap(),
+        ExchangeInstruction::SettleBets { bump_seed: 3 }
+    );
+
+    let ix = instruction::init_moneyline_market(
+        &program_id,
+        &key,
+        &key,
+        &key,
+        &key,
+        &[&key, &key],
+        &[&key, &key],
+        9,
+        BetType::Total,
+        OracleSource::Manual,
+        600,
+        3,
+        "NBA",
+        1_700_000_000,
+        &["Over", "Under"],
+        &[],
+    )
+    .unwrap();
+    assert_eq!(ix.accounts.len(), 10);
+    assert_eq!(
+        ix.accounts[1].pubkey,
+        find_market_address(&program_id, 9, BetType::Total).0
+    );
+    assert_eq!(ix.accounts[5].pubkey, system_program::id());
+    assert_eq!(
+        ExchangeInstruction::unpack(&ix.data).unwrap(),
+        ExchangeInstruction::InitMoneylineMarket {
+            event_id: 9,
+            bet_type: BetType::Total,
+            oracle_source: OracleSource::Manual,
+            max_staleness: 600,
+            min_responses: 3,
+            league: pack_text("NBA").unwrap(),
+            start_time: 1_700_000_000,
+            side_labels: [
+                pack_text("Over").unwrap(),
+                pack_text("Under").unwrap(),
+                [0; LABEL_LEN]
+            ],
+        }
+    );
+    // Labels must fit and come one per market side
+    assert!(instruction::init_moneyline_market(
+        &program_id,
+        &key,
+        &key,
+        &key,
+        &key,
+        &[&key, &key],
+        &[&key, &key],
+        9,
+        BetType::Total,
+        OracleSource::Manual,
+        600,
+        3,
+        "NBA",
+        1_700_000_000,
+        &["Over", "Under", "Push"],
+        &[],
+    )
+    .is_err());
+    assert!(instruction::init_moneyline_market(
+        &program_id,
+        &key,
+        &key,
+        &key,
+        &key,
+        &[&key, &key],
+        &[&key, &key],
+        9,
+        BetType::Total,
+        OracleSource::Manual,
+        600,
+        3,
+        "NATIONAL BASKETBALL",
+        1_700_000_000,
+        &["Over", "Under"],
+        &[],
+    )
+    .is_err());
+    // Labels that are not UTF-8 do not unpack
+    let mut data = ix.data.clone();
+    let label_start = data.len() - 3 * LABEL_LEN;
+    data[label_start] = 0xff;
+    assert!(ExchangeInstruction::unpack(&data).is_err());
+    assert!(instruction::init_moneyline_market(
+        &program_id,
+        &key,
+        &key,
+        &key,
+        &key,
+        &[&key, &key, &key],
+        &[&key],
+        9,
+        BetType::Total,
+        OracleSource::Manual,
+        600,
+        3,
+        "NBA",
+        1_700_000_000,
+        &["Over", "Under"],
+        &[],
+    )
+    .is_err());
+
+    let ix = instruction::settle_moneyline_market(
+        &program_id,
+        &key,
+        &key,
+        &key,
+        &key,
+        &key,
+        &key,
+        &key,
+        &key,
+        &key,
+        &key,
+        &key,
+        3,
+    )
+    .unwrap();
+    assert_eq!(ix.accounts.len(), 13);
+    assert_eq!(
+        ExchangeInstruction::unpack(&ix.data).unwrap(),
+        ExchangeInstruction::SettleMoneylineMarket { bump_seed: 3 }
+    );
+
+    let ix = instruction::ownership(&program_id, &key, &key, &key, &key, &key, &key, 3).unwrap();
+    assert_eq!(ix.accounts.len(), 6);
+    assert_eq!(
+        ExchangeInstruction::unpack(&ix.data).unwrap(),
+        ExchangeInstruction::Ownership { bump_seed: 3 }
+    );
+
+    let ix = instruction::freeze(&program_id, &key, &key, true, &[]).unwrap();
+    assert_eq!(ix.accounts.len(), 2);
+    assert_eq!(
+        ExchangeInstruction::unpack(&ix.data).unwrap(),
+        ExchangeInstruction::Freeze {
+            freeze_betting: true,
+        }
+    );
+    let ix = instruction::update_fees(&program_id, &key, &key, 150, 450, &[]).unwrap();
+    assert_eq!(ix.accounts.len(), 2);
+    assert!(ix.accounts[0].is_signer);
+    assert!(ix.accounts[1].is_writable);
+    assert_eq!(
+        ExchangeInstruction::unpack(&ix.data).unwrap(),
+        ExchangeInstruction::UpdateFees {
+            insurance_fund_fee_bps: 150,
+            divvy_foundation_fee_bps: 450,
+        }
+    );
+    let ix = instruction::update_bet_cutoff(&program_id, &key, &key, 600, &[]).unwrap();
+    assert_eq!(ix.accounts.len(), 2);
+    assert!(ix.accounts[0].is_signer);
+    assert!(ix.accounts[1].is_writable);
+    assert_eq!(
+        ExchangeInstruction::unpack(&ix.data).unwrap(),
+        ExchangeInstruction::UpdateBetCutoff {
+            bet_cutoff_secs: 600
+        }
+    );
+    let ix = instruction::update_crank_reward(&program_id, &key, &key, 5_000, &[]).unwrap();
+    assert_eq!(ix.accounts.len(), 2);
+    assert!(ix.accounts[0].is_signer);
+    assert!(ix.accounts[1].is_writable);
+    assert_eq!(
+        ExchangeInstruction::unpack(&ix.data).unwrap(),
+        ExchangeInstruction::UpdateCrankReward {
+            crank_reward_lamports: 5_000
+        }
+    );
+    let ix = instruction::void_market(
+        &program_id,
+        &key,
+        &key,
+        &key,
+        &key,
+        &key,
+        &key,
+        &key,
+        &key,
+        3,
+        &[],
+    )
+    .unwrap();
+    assert_eq!(ix.accounts.len(), 10);
+    assert!(ix.accounts[0].is_signer);
+    assert!(ix.accounts[1].is_writable);
+    assert!(ix.accounts[4].is_writable);
+    assert!(ix.accounts[5].is_writable);
+    assert_eq!(ix.accounts[6].pubkey, spl_token::id());
+    assert_eq!(
+        ExchangeInstruction::unpack(&ix.data).unwrap(),
+        ExchangeInstruction::VoidMarket { bump_seed: 3 }
+    );
+    let ix = instruction::suspend_market(&program_id, &key, &key, &key, true, &[]).unwrap();
+    assert_eq!(ix.accounts.len(), 3);
+    assert!(ix.accounts[0].is_signer);
+    assert!(ix.accounts[1].is_writable);
+    assert!(!ix.accounts[2].is_writable);
+    assert_eq!(
+        ExchangeInstruction::unpack(&ix.data).unwrap(),
+        ExchangeInstruction::SuspendMarket { suspended: true }
+    );
+    let exposure_limits = ExposureLimits {
+        max_market_exposure_bps: 1_000,
+        ..ExposureLimits::default()
+    };
+    let ix =
+        instruction::update_exposure_limits(&program_id, &key, &key, exposure_limits, &[]).unwrap();
+    assert_eq!(ix.accounts.len(), 2);
+    assert!(ix.accounts[0].is_signer);
+    assert!(ix.accounts[1].is_writable);
+    assert_eq!(
+        ExchangeInstruction::unpack(&ix.data).unwrap(),
+        ExchangeInstruction::UpdateExposureLimits { exposure_limits }
+    );
+}
+
+#[test]
+fn test_authority_builders() {
+    let program_id = Pubkey::new_unique();
+    let authority = Pubkey::new_unique();
+    let pool_state = Pubkey::new_unique();
+    let new_authority = Pubkey::new_unique();
+    let signer_0 = Pubkey::new_unique();
+    let signer_1 = Pubkey::new_unique();
+
+    let ix = instruction::update_manual_feed(
+        &program_id,
+        &authority,
+        &new_authority,
+        &pool_state,
+        1,
+        0,
+        &[],
+    )
+    .unwrap();
+    assert_eq!(ix.accounts.len(), 3);
+    assert_eq!(ix.accounts[2].pubkey, pool_state);
+    assert!(!ix.accounts[2].is_writable);
+
+    let ix =
+        instruction::propose_authority(&program_id, &authority, &pool_state, &new_authority, &[])
+            .unwrap();
+    assert_eq!(ix.accounts.len(), 2);
+    assert!(ix.accounts[0].is_signer);
+    assert!(ix.accounts[1].is_writable);
+    assert_eq!(
+        ExchangeInstruction::unpack(&ix.data).unwrap(),
+        ExchangeInstruction::ProposeAuthority { new_authority }
+    );
+
+    // A multisig authority does not sign, its signers do
+    let ix = instruction::accept_authority(
+        &program_id,
+        &new_authority,
+        &pool_state,
+        &[&signer_0, &signer_1],
+    )
+    .unwrap();
+    assert_eq!(ix.accounts.len(), 4);
+    assert!(!ix.accounts[0].is_signer);
+    assert!(ix.accounts[2].is_signer && ix.accounts[3].is_signer);
+    assert_eq!(
+        ExchangeInstruction::unpack(&ix.data).unwrap(),
+        ExchangeInstruction::AcceptAuthority
+    );
+
+    // So do the signers of a multisig role holder
+    let ix = instruction::update_fees(
+        &program_id,
+        &new_authority,
+        &pool_state,
+        150,
+        450,
+        &[&signer_0, &signer_1],
+    )
+    .unwrap();
+    assert_eq!(ix.accounts.len(), 4);
+    assert!(!ix.accounts[0].is_signer);
+    assert_eq!(ix.accounts[3].pubkey, signer_1);
+    assert!(ix.accounts[2].is_signer && ix.accounts[3].is_signer);
+
+    let ix = instruction::init_multisig(&program_id, &new_authority, &[&signer_0, &signer_1], 2)
+        .unwrap();
+    assert_eq!(ix.accounts.len(), 3);
+    assert!(ix.accounts[0].is_writable);
+    assert!(ix.accounts.iter().all(|account| !account.is_signer));
+    assert_eq!(
+        ExchangeInstruction::unpack(&ix.data).unwrap(),
+        ExchangeInstruction::InitMultisig { m: 2 }
+    );
+}
+
+#[test]
+fn test_role_builders() {
+    let program_id = Pubkey::new_unique();
+    let authority = Pubkey::new_unique();
+    let pool_state = Pubkey::new_unique();
+    let member = Pubkey::new_unique();
+
+    let ix = instruction::grant_role(
+        &program_id,
+        &authority,
+        &pool_state,
+        Role::Pauser,
+        &member,
+        &[],
+    )
+    .unwrap();
+    assert_eq!(ix.accounts.len(), 2);
+    assert!(ix.accounts[0].is_signer);
+    assert!(ix.accounts[1].is_writable);
+    assert_eq!(
+        ExchangeInstruction::unpack(&ix.data).unwrap(),
+        ExchangeInstruction::GrantRole {
+            role: Role::Pauser,
+            member
+        }
+    );
+
+    let ix = instruction::revoke_role(
+        &program_id,
+        &authority,
+        &pool_state,
+        Role::Settler,
+        &member,
+        &[&member],
+    )
+    .unwrap();
+    assert_eq!(ix.accounts.len(), 3);
+    assert!(!ix.accounts[0].is_signer);
+    assert_eq!(
+        ExchangeInstruction::unpack(&ix.data).unwrap(),
+        ExchangeInstruction::RevokeRole {
+            role: Role::Settler,
+            member
+        }
+    );
+}
@@ -0,0 +1,332 @@
+mod common;
+
+use common::{exchange_error, program_test, Divvy, USDT};
+use divvybetting::{
+    error::ExchangeError,
+    schema::divvy_house_program_id,
+    state::{BetType, Market, OracleSource},
+};
+use divvycommon::pda::find_ledger_address;
+use divvyhouse::{
+    instruction,
+    state::{HpLiquidity, MarketLedger},
+};
+use solana_program::{program_pack::Pack, pubkey::Pubkey};
+use solana_sdk::signature::{Keypair, Signer};
+
+#[tokio::test]
+async fn test_committed_liquidity() {
+    let program_id = Pubkey::new_unique();
+    let mut divvy = Divvy::start(program_test(&program_id), program_id).await;
+    let hp_state = divvy.hp_state.pubkey();
+    let hp_usdt = divvy.hp_usdt_account.pubkey();
+    let side_0_feed = divvy.create_manual_feed(-110, 0).await.unwrap().pubkey();
+    let side_1_feed = divvy.create_manual_feed(150, 0).await.unwrap().pubkey();
+    let side_2_feed = divvy.create_manual_feed(300, 0).await.unwrap().pubkey();
+    let result_feed = divvy.create_manual_feed(0, 0).await.unwrap().pubkey();
+    let market = divvy
+        .init_market(
+            &result_feed,
+            &[&side_0_feed, &side_1_feed, &side_2_feed],
+            &[],
+            BetType::MoneyLine,
+            OracleSource::Manual,
+        )
+        .await
+        .unwrap();
+    let first_lp = divvy.create_user(1_000 * USDT).await;
+    let second_lp = divvy.create_user(100 * USDT).await;
+    let bettor = divvy.create_user(300 * USDT).await;
+    divvy.deposit(&first_lp, 1_000 * USDT).await.unwrap();
+    divvy
+        .init_bet(&bettor, &market, &side_1_feed, 300 * USDT, 150, 1, 0)
+        .await
+        .unwrap();
+
+    // The 450 USDT lent to the market is committed, not lost
+    divvy.commence_market(&market).await.unwrap();
+    let hp_state_data: HpLiquidity = divvy.unpack(&hp_state).await;
+    assert_eq!(hp_state_data.committed_liquidity, 450 * USDT);
+    let (ledger, _) = find_ledger_address(&divvy_house_program_id::ID, &hp_state, &market);
+    let ledger_data: MarketLedger = divvy.unpack(&ledger).await;
+    assert_eq!(ledger_data.market, market);
+    assert_eq!(ledger_data.lent, 450 * USDT);
+    assert!(!ledger_data.settled);
+    assert_eq!(divvy.token_balance(&hp_usdt).await, 550 * USDT);
+    divvy.deposit(&second_lp, 100 * USDT).await.unwrap();
+    assert_eq!(
+        divvy.token_balance(&second_lp.ht_account.pubkey()).await,
+        100 * USDT
+    );
+
+    // Only the liquidity in the pool can be withdrawn
+    assert_eq!(
+        divvy.withdraw(&first_lp, 651 * USDT).await.unwrap_err(),
+        exchange_error(ExchangeError::NotEnoughAvailableLiquidityForWithdrawal)
+    );
+    divvy.withdraw(&first_lp, 650 * USDT).await.unwrap();
+    assert_eq!(
+        divvy.token_balance(&first_lp.usdt_account.pubkey()).await,
+        650 * USDT
+    );
+    assert_eq!(divvy.token_balance(&hp_usdt).await, 0);
+
+    // Voiding the market releases its liquidity back to the pool
+    divvy.void_market(&market).await.unwrap();
+    let hp_state_data: HpLiquidity = divvy.unpack(&hp_state).await;
+    assert_eq!(hp_state_data.committed_liquidity, 0);
+    assert_eq!(hp_state_data.realized_pnl, 0);
+    assert_eq!(divvy.token_balance(&hp_usdt).await, 450 * USDT);
+    let ledger_data: MarketLedger = divvy.unpack(&ledger).await;
+    assert_eq!(ledger_data.returned, 450 * USDT);
+    assert_eq!(ledger_data.realized_pnl, 0);
+    assert!(ledger_data.settled);
+    divvy.withdraw(&second_lp, 100 * USDT).await.unwrap();
+    assert_eq!(
+        divvy.token_balance(&second_lp.usdt_account.pubkey()).await,
+        100 * USDT
+    );
+}
+
+#[tokio::test]
+async fn test_only_the_betting_program_moves_liquidity() {
+    let program_id = Pubkey::new_unique();
+    let mut divvy = Divvy::start(program_test(&program_id), program_id).await;
+    let hp_state = divvy.hp_state.pubkey();
+    let hp_usdt = divvy.hp_usdt_account.pubkey();
+    let lp = divvy.create_user(100 * USDT).await;
+    divvy.deposit(&lp, 100 * USDT).await.unwrap();
+    let hp_state_data: HpLiquidity = divvy.unpack(&hp_state).await;
+    assert_eq!(hp_state_data.betting_program, program_id);
+
+    // A signer other than the betting program PDA can not borrow or release liquidity
+    let impostor = Keypair::new();
+    let transfer = instruction::transfer_locked_liquidity(
+        &divvy_house_program_id::ID,
+        &divvy.hp_pda,
+        &impostor.pubkey(),
+        &divvy.bet_usdt_account.pubkey(),
+        &hp_usdt,
+        &hp_state,
+        &impostor.pubkey(),
+        &Pubkey::new_unique(),
+        100 * USDT,
+        divvy.hp_bump_seed,
+    )
+    .unwrap();
+    assert_eq!(
+        divvy.process(&[transfer], &[&impostor]).await.unwrap_err(),
+        exchange_error(ExchangeError::InvalidBettingPda)
+    );
+    let give_back = instruction::return_liquidity(
+        &divvy_house_program_id::ID,
+        &impostor.pubkey(),
+        &hp_state,
+        &Pubkey::new_unique(),
+        &divvy.bet_usdt_account.pubkey(),
+        &hp_usdt,
+        0,
+    )
+    .unwrap();
+    assert_eq!(
+        divvy.process(&[give_back], &[&impostor]).await.unwrap_err(),
+        exchange_error(ExchangeError::InvalidBettingPda)
+    );
+    assert_eq!(divvy.token_balance(&hp_usdt).await, 100 * USDT);
+
+    // The betting program must be a program owning the betting pool USDT account
+    let other_hp_state = Keypair::new();
+    divvy
+        .create_account(
+            &other_hp_state,
+            HpLiquidity::LEN,
+            &divvy_house_program_id::ID,
+        )
+        .await;
+    let authority = Keypair::from_bytes(&divvy.authority.to_bytes()).unwrap();
+    let ownership = |betting_program: &Pubkey, betting_usdt_account: &Pubkey| {
+        instruction::ownership(
+            &divvy_house_program_id::ID,
+            &authority.pubkey(),
+            &other_hp_state.pubkey(),
+            &divvy.ht_mint.pubkey(),
+            betting_usdt_account,
+            &hp_usdt,
+            &divvy.hp_queue_ht_account.pubkey(),
+            betting_program,
+            divvy.hp_bump_seed,
+        )
+        .unwrap()
+    };
+    let not_a_program = ownership(&impostor.pubkey(), &divvy.bet_usdt_account.pubkey());
+    let not_its_usdt_account = ownership(&program_id, &hp_usdt);
+    assert_eq!(
+        divvy
+            .process(&[not_a_program], &[&authority])
+            .await
+            .unwrap_err(),
+        exchange_error(ExchangeError::InvalidBettingProgramAccount)
+    );
+    assert_eq!(
+        divvy
+            .process(&[not_its_usdt_account], &[&authority])
+            .await
+            .unwrap_err(),
+        exchange_error(ExchangeError::InvalidBettingPoolUsdtAccount)
+    );
+}
+
+#[tokio::test]
+async fn test_shares_are_priced_from_the_house_pool_state() {
+    let program_id = Pubkey::new_unique();
+    let mut divvy = Divvy::start(program_test(&program_id), program_id).await;
+    let hp_usdt = divvy.hp_usdt_account.pubkey();
+    let lp = divvy.create_user(100 * USDT).await;
+    divvy.deposit(&lp, 100 * USDT).await.unwrap();
+
+    // A pool state the house program does not own could price the house token at will
+    let forged_hp_state = Keypair::new();
+    divvy
+        .create_account(&forged_hp_state, HpLiquidity::LEN, &Pubkey::new_unique())
+        .await;
+    let deposit = instruction::deposit(
+        &divvy_house_program_id::ID,
+        &lp.keypair.pubkey(),
+        &divvy.ht_mint.pubkey(),
+        &lp.ht_account.pubkey(),
+        &divvy.hp_pda,
+        &lp.usdt_account.pubkey(),
+        &hp_usdt,
+        &forged_hp_state.pubkey(),
+        USDT,
+        divvy.hp_bump_seed,
+    )
+    .unwrap();
+    let withdraw = instruction::withdraw(
+        &divvy_house_program_id::ID,
+        &lp.keypair.pubkey(),
+        &divvy.ht_mint.pubkey(),
+        &lp.ht_account.pubkey(),
+        &divvy.hp_pda,
+        &lp.usdt_account.pubkey(),
+        &hp_usdt,
+        &forged_hp_state.pubkey(),
+        USDT,
+        divvy.hp_bump_seed,
+    )
+    .unwrap();
+    let request_withdrawal = instruction::request_withdrawal(
+        &divvy_house_program_id::ID,
+        &lp.keypair.pubkey(),
+        &lp.ht_account.pubkey(),
+        &divvy.hp_queue_ht_account.pubkey(),
+        &divvy.ht_mint.pubkey(),
+        &hp_usdt,
+        &forged_hp_state.pubkey(),
+        0,
+        USDT,
+    )
+    .unwrap();
+    let fill_withdrawal = instruction::fill_withdrawal(
+        &divvy_house_program_id::ID,
+        &lp.keypair.pubkey(),
+        &lp.usdt_account.pubkey(),
+        &divvy.hp_queue_ht_account.pubkey(),
+        &divvy.ht_mint.pubkey(),
+        &hp_usdt,
+        &forged_hp_state.pubkey(),
+        &divvy.hp_pda,
+        0,
+        divvy.hp_bump_seed,
+    )
+    .unwrap();
+    for instruction in [deposit, withdraw, request_withdrawal].iter() {
+        assert_eq!(
+            divvy
+                .process(&[instruction.clone()], &[&lp.keypair])
+                .await
+                .unwrap_err(),
+            exchange_error(ExchangeError::InvalidHousePoolStateAccount)
+        );
+    }
+    assert_eq!(
+        divvy.process(&[fill_withdrawal], &[]).await.unwrap_err(),
+        exchange_error(ExchangeError::InvalidHousePoolStateAccount)
+    );
+    assert_eq!(divvy.token_balance(&hp_usdt).await, 100 * USDT);
+}
+
+#[tokio::test]
+async fn test_market_ledger_realizes_profit_and_loss() {
+    let program_id = Pubkey::new_unique();
+    let mut divvy = Divvy::start(program_test(&program_id), program_id).await;
+    let hp_state = divvy.hp_state.pubkey();
+    let hp_usdt = divvy.hp_usdt_account.pubkey();
+    let side_0_feed = divvy.create_manual_feed(150, 0).await.unwrap().pubkey();
+    let side_1_feed = divvy.create_manual_feed(150, 0).await.unwrap().pubkey();
+    let side_2_feed = divvy.create_manual_feed(300, 0).await.unwrap().pubkey();
+    let side_1_won = divvy.create_manual_feed(1, 0).await.unwrap().pubkey();
+    let side_2_won = divvy.create_manual_feed(2, 0).await.unwrap().pubkey();
+    let lp = divvy.create_user(1_000 * USDT).await;
+    let bettor = divvy.create_user(400 * USDT).await;
+    divvy.deposit(&lp, 1_000 * USDT).await.unwrap();
+
+    let mut ledgers = Vec::new();
+    for result_feed in [side_1_won, side_2_won].iter() {
+        let market = divvy
+            .init_market(
+                result_feed,
+                &[&side_0_feed, &side_1_feed, &side_2_feed],
+                &[],
+                BetType::MoneyLine,
+                OracleSource::Manual,
+            )
+            .await
+            .unwrap();
+        divvy
+            .init_bet(&bettor, &market, &side_0_feed, 100 * USDT, 150, 0, 0)
+            .await
+            .unwrap();
+        divvy
+            .init_bet(&bettor, &market, &side_1_feed, 80 * USDT, 150, 1, 0)
+            .await
+            .unwrap();
+        let market_state: Market = divvy.unpack(&market).await;
+        divvy.commence_market(&market).await.unwrap();
+
+        let pool_balance = divvy.token_balance(&hp_usdt).await;
+        divvy.settle_market(&market, result_feed).await.unwrap();
+        let (ledger, _) = find_ledger_address(&divvy_house_program_id::ID, &hp_state, &market);
+        let ledger_data: MarketLedger = divvy.unpack(&ledger).await;
+        assert_eq!(ledger_data.lent, market_state.locked_liquidity);
+        assert_eq!(
+            ledger_data.returned,
+            divvy.token_balance(&hp_usdt).await - pool_balance
+        );
+        assert!(ledger_data.settled);
+        ledgers.push(ledger_data);
+    }
+
+    // Side 1 winning pays 120 USDT against the 100 USDT lost on side 0, and
+    // what is left of the liquidity lent comes back to the house pool
+    assert_eq!(ledgers[0].realized_pnl, -20 * USDT as i64);
+    assert_eq!(ledgers[0].returned, ledgers[0].lent - 20 * USDT);
+    // The house keeps both bets, less fees, when side 2 wins
+    assert!(ledgers[1].realized_pnl > 0);
+    assert!(ledgers[1].realized_pnl < 180 * USDT as i64);
+    assert_eq!(
+        ledgers[1].realized_pnl,
+        ledgers[1].returned as i64 - ledgers[1].lent as i64
+    );
+
+    let hp_state_data: HpLiquidity = divvy.unpack(&hp_state).await;
+    assert_eq!(hp_state_data.committed_liquidity, 0);
+    assert_eq!(
+        hp_state_data.realized_pnl,
+        ledgers[0].realized_pnl + ledgers[1].realized_pnl
+    );
+    assert_eq!(
+        divvy.token_balance(&hp_usdt).await as i64,
+        1_000 * USDT as i64 + hp_state_data.realized_pnl
+    );
+}
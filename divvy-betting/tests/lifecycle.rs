@@ -0,0 +1,212 @@
+mod common;
+
+use common::{add_aggregator, program_test, Divvy};
+use divvybetting::state::{
+    BetType, BettingPoolState, Market, MoneylineMarketOutcome, OracleSource,
+};
+use divvycommon::fees::{fee, DEFAULT_DIVVY_FOUNDATION_FEE_BPS, DEFAULT_INSURANCE_FUND_FEE_BPS};
+use divvyhouse::state::HpLiquidity;
+use fixed::types::U64F64;
+use solana_program::pubkey::Pubkey;
+use solana_sdk::signature::Signer;
+use spl_token::state::Mint;
+
+const USDT: u64 = 1_000_000;
+
+#[tokio::test]
+async fn test_market_lifecycle() {
+    // The betting PDA bump seed differs from the house pool's, so each CPI must sign with its own
+    let (_, hp_bump_seed) = Pubkey::find_program_address(
+        &[b"divvyhouse"],
+        &divvybetting::schema::divvy_house_program_id::ID,
+    );
+    let program_id = std::iter::repeat_with(Pubkey::new_unique)
+        .find(|program_id| {
+            Pubkey::find_program_address(&[b"divvybetting"], program_id).1 != hp_bump_seed
+        })
+        .unwrap();
+    let mut program_test = program_test(&program_id);
+    let side_0_feed = add_aggregator(&mut program_test, -110.0);
+    let side_1_feed = add_aggregator(&mut program_test, 150.0);
+    let side_2_feed = add_aggregator(&mut program_test, 300.0);
+    let result_feed = add_aggregator(&mut program_test, 0.0);
+
+    // Ownership
+    let mut divvy = Divvy::start(program_test, program_id).await;
+    let hp_state: HpLiquidity = divvy.unpack(&divvy.hp_state.pubkey()).await;
+    assert!(hp_state.is_initialized);
+    assert_eq!(hp_state.ht_mint, divvy.ht_mint.pubkey());
+    assert_eq!(hp_state.betting_usdt, divvy.bet_usdt_account.pubkey());
+    assert_eq!(hp_state.pool_usdt, divvy.hp_usdt_account.pubkey());
+    assert_eq!(hp_state.queue_ht, divvy.hp_queue_ht_account.pubkey());
+    assert_eq!(hp_state.queue_tail, 0);
+    assert_eq!(hp_state.committed_liquidity, 0);
+    assert_eq!(hp_state.betting_program, program_id);
+    assert!(!hp_state.frozen_pool);
+    let pool_state: BettingPoolState = divvy.unpack(&divvy.bet_pool_state.pubkey()).await;
+    assert!(pool_state.is_initialized);
+    assert_eq!(pool_state.house_pool_usdt, divvy.hp_usdt_account.pubkey());
+    assert_eq!(
+        pool_state.betting_pool_usdt,
+        divvy.bet_usdt_account.pubkey()
+    );
+    assert_eq!(
+        pool_state.insurance_fund_usdt,
+        divvy.insurance_fund_usdt_account.pubkey()
+    );
+    assert_eq!(
+        pool_state.divvy_foundation_proceeds_usdt,
+        divvy.divvy_foundation_proceeds_usdt_account.pubkey()
+    );
+    assert!(!pool_state.frozen_betting);
+    assert_eq!(
+        pool_state.insurance_fund_fee_bps,
+        DEFAULT_INSURANCE_FUND_FEE_BPS
+    );
+    assert_eq!(
+        pool_state.divvy_foundation_fee_bps,
+        DEFAULT_DIVVY_FOUNDATION_FEE_BPS
+    );
+
+    // Deposit
+    let lp = divvy.create_user(1_000 * USDT).await;
+    divvy.deposit(&lp, 1_000 * USDT).await.unwrap();
+    assert_eq!(divvy.token_balance(&lp.usdt_account.pubkey()).await, 0);
+    assert_eq!(
+        divvy.token_balance(&lp.ht_account.pubkey()).await,
+        1_000 * USDT
+    );
+    let hp_usdt = divvy.hp_usdt_account.pubkey();
+    assert_eq!(divvy.token_balance(&hp_usdt).await, 1_000 * USDT);
+
+    // InitMoneylineMarket
+    let market = divvy
+        .init_market(
+            &result_feed,
+            &[&side_0_feed, &side_1_feed, &side_2_feed],
+            &[],
+            BetType::MoneyLine,
+            OracleSource::SwitchboardV1,
+        )
+        .await
+        .unwrap();
+    let market_state: Market = divvy.unpack(&market).await;
+    assert!(market_state.result == MoneylineMarketOutcome::NotYetCommenced);
+    assert_eq!(market_state.result_feed, result_feed);
+    assert_eq!(
+        market_state.market_sides[2].odds_feed_account,
+        Some(side_2_feed)
+    );
+
+    // Initbet
+    let winner = divvy.create_user(100 * USDT).await;
+    let loser = divvy.create_user(150 * USDT).await;
+    let winning_bet = divvy
+        .init_bet(&winner, &market, &side_0_feed, 100 * USDT, -110, 0, 0)
+        .await
+        .unwrap();
+    let pool_state: BettingPoolState = divvy.unpack(&divvy.bet_pool_state.pubkey()).await;
+    assert_eq!(pool_state.locked_liquidity, 90_909_090);
+    let losing_bet = divvy
+        .init_bet(&loser, &market, &side_1_feed, 150 * USDT, 150, 1, 0)
+        .await
+        .unwrap();
+
+    let market_state: Market = divvy.unpack(&market).await;
+    assert_eq!(market_state.market_sides[0].risk, 100 * USDT);
+    assert_eq!(market_state.market_sides[0].payout, 90_909_090);
+    assert_eq!(market_state.market_sides[1].risk, 150 * USDT);
+    assert_eq!(market_state.market_sides[1].payout, 225 * USDT);
+    assert_eq!(market_state.locked_liquidity, 125 * USDT);
+    assert_eq!(market_state.bettor_balance, 250 * USDT);
+    assert_eq!(market_state.pending_bets, 2);
+    let pool_state: BettingPoolState = divvy.unpack(&divvy.bet_pool_state.pubkey()).await;
+    assert_eq!(pool_state.locked_liquidity, 125 * USDT);
+    assert_eq!(pool_state.live_liquidity, 0);
+    assert_eq!(pool_state.pending_bets, 2);
+    let bet_usdt = divvy.bet_usdt_account.pubkey();
+    assert_eq!(divvy.token_balance(&bet_usdt).await, 250 * USDT);
+    assert_eq!(divvy.token_balance(&winner.usdt_account.pubkey()).await, 0);
+    assert_eq!(divvy.token_balance(&loser.usdt_account.pubkey()).await, 0);
+
+    // CommenceMarket
+    divvy.commence_market(&market).await.unwrap();
+    let market_state: Market = divvy.unpack(&market).await;
+    assert!(market_state.result == MoneylineMarketOutcome::Commenced);
+    let pool_state: BettingPoolState = divvy.unpack(&divvy.bet_pool_state.pubkey()).await;
+    assert_eq!(pool_state.locked_liquidity, 0);
+    assert_eq!(pool_state.live_liquidity, 125 * USDT);
+    assert_eq!(divvy.token_balance(&hp_usdt).await, 875 * USDT);
+    assert_eq!(divvy.token_balance(&bet_usdt).await, 375 * USDT);
+
+    // SettleMoneylineMarket
+    divvy.settle_market(&market, &result_feed).await.unwrap();
+    let winner_balance = 100 * USDT + 90_909_090;
+    let house_profit = 250 * USDT - winner_balance;
+    let insurance_fund_fee = fee(house_profit, DEFAULT_INSURANCE_FUND_FEE_BPS);
+    let divvy_foundation_fee = fee(house_profit, DEFAULT_DIVVY_FOUNDATION_FEE_BPS);
+    let total_house_profit = house_profit - insurance_fund_fee - divvy_foundation_fee;
+    let market_state: Market = divvy.unpack(&market).await;
+    assert!(market_state.result == MoneylineMarketOutcome::MarketSide0Won);
+    assert_eq!(market_state.locked_liquidity, 0);
+    assert_eq!(market_state.bettor_balance, winner_balance);
+    let pool_state: BettingPoolState = divvy.unpack(&divvy.bet_pool_state.pubkey()).await;
+    assert_eq!(pool_state.locked_liquidity, 0);
+    assert_eq!(pool_state.live_liquidity, 0);
+    assert_eq!(
+        divvy
+            .token_balance(&divvy.insurance_fund_usdt_account.pubkey())
+            .await,
+        insurance_fund_fee
+    );
+    assert_eq!(
+        divvy
+            .token_balance(&divvy.divvy_foundation_proceeds_usdt_account.pubkey())
+            .await,
+        divvy_foundation_fee
+    );
+    let house_balance = 1_000 * USDT + total_house_profit;
+    assert_eq!(divvy.token_balance(&hp_usdt).await, house_balance);
+
+    // SettleBet
+    divvy
+        .settle_bet(&winner, &market, &winning_bet)
+        .await
+        .unwrap();
+    assert_eq!(
+        divvy.token_balance(&winner.usdt_account.pubkey()).await,
+        winner_balance
+    );
+    assert!(!divvy.account_exists(&winning_bet).await);
+    divvy
+        .settle_bet(&loser, &market, &losing_bet)
+        .await
+        .unwrap();
+    assert_eq!(divvy.token_balance(&loser.usdt_account.pubkey()).await, 0);
+    assert!(!divvy.account_exists(&losing_bet).await);
+
+    let market_state: Market = divvy.unpack(&market).await;
+    assert_eq!(market_state.pending_bets, 0);
+    assert_eq!(market_state.bettor_balance, 0);
+    let pool_state: BettingPoolState = divvy.unpack(&divvy.bet_pool_state.pubkey()).await;
+    assert_eq!(pool_state.pending_bets, 0);
+    // The house keeps the fee rounding, so nothing is left in the betting pool
+    assert_eq!(divvy.token_balance(&bet_usdt).await, 0);
+
+    // Withdraw
+    divvy.withdraw(&lp, 1_000 * USDT).await.unwrap();
+    let withdrawn: u64 = (U64F64::from_num(house_balance) / U64F64::from_num(1_000 * USDT)
+        * U64F64::from_num(1_000 * USDT))
+    .to_num();
+    assert_eq!(
+        divvy.token_balance(&lp.usdt_account.pubkey()).await,
+        withdrawn
+    );
+    assert_eq!(divvy.token_balance(&lp.ht_account.pubkey()).await, 0);
+    assert_eq!(
+        divvy.token_balance(&hp_usdt).await,
+        house_balance - withdrawn
+    );
+    let ht_mint: Mint = divvy.unpack(&divvy.ht_mint.pubkey()).await;
+    assert_eq!(ht_mint.supply, 0);
+}
@@ -0,0 +1,145 @@
+mod common;
+
+use common::{exchange_error, funded_divvy, program_test, USDT};
+use divvybetting::{
+    error::ExchangeError,
+    instruction,
+    state::{BetType, BettingPoolState, ExposureLimits, Market, OracleSource},
+};
+use solana_program::pubkey::Pubkey;
+use solana_sdk::signature::{Keypair, Signer};
+
+#[tokio::test]
+async fn test_bets_within_exposure_limits() {
+    let program_id = Pubkey::new_unique();
+    let mut divvy = funded_divvy(program_test(&program_id), program_id).await;
+    let bet_pool_state = divvy.bet_pool_state.pubkey();
+    let authority = Keypair::from_bytes(&divvy.authority.to_bytes()).unwrap();
+    let treasurer = Keypair::new();
+    let side_0_feed = divvy.create_manual_feed(-110, 0).await.unwrap().pubkey();
+    let side_1_feed = divvy.create_manual_feed(150, 0).await.unwrap().pubkey();
+    let side_2_feed = divvy.create_manual_feed(300, 0).await.unwrap().pubkey();
+    let result_feed = divvy.create_manual_feed(0, 0).await.unwrap().pubkey();
+    let market = divvy
+        .init_market(
+            &result_feed,
+            &[&side_0_feed, &side_1_feed, &side_2_feed],
+            &[],
+            BetType::MoneyLine,
+            OracleSource::Manual,
+        )
+        .await
+        .unwrap();
+    let user = divvy.create_user(1_000 * USDT).await;
+
+    // A market may lock a fifth of the 1,000 USDT pool
+    let exposure_limits = ExposureLimits {
+        max_market_exposure_bps: 2_000,
+        max_side_payout: 300 * USDT,
+        min_bet_risk: 5 * USDT,
+        max_bet_risk: 250 * USDT,
+        ..ExposureLimits::default()
+    };
+    let instruction = instruction::update_exposure_limits(
+        &program_id,
+        &treasurer.pubkey(),
+        &bet_pool_state,
+        exposure_limits,
+        &[],
+    )
+    .unwrap();
+    assert_eq!(
+        divvy
+            .process(&[instruction], &[&treasurer])
+            .await
+            .unwrap_err(),
+        exchange_error(ExchangeError::NotValidAuthority)
+    );
+    let above_pool = ExposureLimits {
+        max_market_exposure_bps: 10_001,
+        ..exposure_limits
+    };
+    let instruction = instruction::update_exposure_limits(
+        &program_id,
+        &authority.pubkey(),
+        &bet_pool_state,
+        above_pool,
+        &[],
+    )
+    .unwrap();
+    assert_eq!(
+        divvy
+            .process(&[instruction], &[&authority])
+            .await
+            .unwrap_err(),
+        exchange_error(ExchangeError::InvalidExposureLimits)
+    );
+    let instruction = instruction::update_exposure_limits(
+        &program_id,
+        &authority.pubkey(),
+        &bet_pool_state,
+        exposure_limits,
+        &[],
+    )
+    .unwrap();
+    divvy.process(&[instruction], &[&authority]).await.unwrap();
+    let pool_state: BettingPoolState = divvy.unpack(&bet_pool_state).await;
+    assert_eq!(pool_state.exposure_limits, exposure_limits);
+
+    assert_eq!(
+        divvy
+            .init_bet(&user, &market, &side_1_feed, 4 * USDT, 150, 1, 0)
+            .await
+            .unwrap_err(),
+        exchange_error(ExchangeError::BetRiskTooLow)
+    );
+    assert_eq!(
+        divvy
+            .init_bet(&user, &market, &side_1_feed, 260 * USDT, 150, 1, 0)
+            .await
+            .unwrap_err(),
+        exchange_error(ExchangeError::BetRiskTooHigh)
+    );
+    // Paying out 300 USDT would lock more than 200 USDT
+    assert_eq!(
+        divvy
+            .init_bet(&user, &market, &side_2_feed, 100 * USDT, 300, 2, 0)
+            .await
+            .unwrap_err(),
+        exchange_error(ExchangeError::MarketExposureLimitExceeded)
+    );
+    divvy
+        .init_bet(&user, &market, &side_2_feed, 50 * USDT, 300, 2, 0)
+        .await
+        .unwrap();
+    let market_state: Market = divvy.unpack(&market).await;
+    assert_eq!(market_state.locked_liquidity, 150 * USDT);
+    assert_eq!(
+        divvy
+            .init_bet(&user, &market, &side_2_feed, 60 * USDT, 300, 2, 0)
+            .await
+            .unwrap_err(),
+        exchange_error(ExchangeError::SidePayoutLimitExceeded)
+    );
+
+    // Lowering the cap below the market's exposure still lets bets hedge it
+    let lower_cap = ExposureLimits {
+        max_market_exposure_bps: 1_000,
+        ..exposure_limits
+    };
+    let instruction = instruction::update_exposure_limits(
+        &program_id,
+        &authority.pubkey(),
+        &bet_pool_state,
+        lower_cap,
+        &[],
+    )
+    .unwrap();
+    divvy.process(&[instruction], &[&authority]).await.unwrap();
+    divvy
+        .init_bet(&user, &market, &side_0_feed, 110 * USDT, -110, 0, 0)
+        .await
+        .unwrap();
+    let market_state: Market = divvy.unpack(&market).await;
+    assert!(market_state.locked_liquidity < 150 * USDT);
+}
@@ -0,0 +1,311 @@
+mod common;
+
+use common::{
+    add_aggregator, add_aggregator_round, add_pyth_price, exchange_error, funded_divvy, now,
+    program_test, User, USDT,
+};
+use divvybetting::{
+    error::ExchangeError,
+    instruction,
+    state::{BetType, ManualFeed, Market, MoneylineMarketOutcome, OracleSource},
+};
+use solana_program::{program_pack::Pack, pubkey::Pubkey};
+use solana_sdk::signature::{Keypair, Signer};
+
+#[tokio::test]
+async fn test_manual_oracle_market() {
+    let program_id = Pubkey::new_unique();
+    let mut divvy = funded_divvy(program_test(&program_id), program_id).await;
+
+    let side_0_feed = divvy.create_manual_feed(-1_100, -1).await.unwrap().pubkey();
+    let side_1_feed = divvy.create_manual_feed(150, 0).await.unwrap().pubkey();
+    let side_2_feed = divvy.create_manual_feed(300, 0).await.unwrap().pubkey();
+    let result_feed = divvy.create_manual_feed(0, 0).await.unwrap().pubkey();
+    let feed: ManualFeed = divvy.unpack(&side_0_feed).await;
+    assert_eq!(feed.value, -1_100);
+    assert_eq!(feed.expo, -1);
+
+    let market = divvy
+        .init_market(
+            &result_feed,
+            &[&side_0_feed, &side_1_feed, &side_2_feed],
+            &[],
+            BetType::MoneyLine,
+            OracleSource::Manual,
+        )
+        .await
+        .unwrap();
+    let market_state: Market = divvy.unpack(&market).await;
+    assert!(market_state.oracle_source == OracleSource::Manual);
+
+    let winner = divvy.create_user(100 * USDT).await;
+    let loser = divvy.create_user(110 * USDT).await;
+    let winning_bet = divvy
+        .init_bet(&winner, &market, &side_1_feed, 100 * USDT, 150, 1, 0)
+        .await
+        .unwrap();
+    divvy
+        .init_bet(&loser, &market, &side_0_feed, 110 * USDT, -110, 0, 0)
+        .await
+        .unwrap();
+    let market_state: Market = divvy.unpack(&market).await;
+    assert_eq!(market_state.market_sides[0].payout, 100 * USDT);
+    assert_eq!(market_state.market_sides[1].payout, 150 * USDT);
+
+    divvy.commence_market(&market).await.unwrap();
+    divvy.update_manual_feed(&result_feed, 1, 0).await.unwrap();
+    divvy.settle_market(&market, &result_feed).await.unwrap();
+    let market_state: Market = divvy.unpack(&market).await;
+    assert!(market_state.result == MoneylineMarketOutcome::MarketSide1Won);
+
+    divvy
+        .settle_bet(&winner, &market, &winning_bet)
+        .await
+        .unwrap();
+    assert_eq!(
+        divvy.token_balance(&winner.usdt_account.pubkey()).await,
+        250 * USDT
+    );
+}
+
+#[tokio::test]
+async fn test_odds_tolerance() {
+    let program_id = Pubkey::new_unique();
+    let mut divvy = funded_divvy(program_test(&program_id), program_id).await;
+    let side_0_feed = divvy.create_manual_feed(-110, 0).await.unwrap().pubkey();
+    let side_1_feed = divvy.create_manual_feed(150, 0).await.unwrap().pubkey();
+    let side_2_feed = divvy.create_manual_feed(300, 0).await.unwrap().pubkey();
+    let result_feed = divvy.create_manual_feed(0, 0).await.unwrap().pubkey();
+    let market = divvy
+        .init_market(
+            &result_feed,
+            &[&side_0_feed, &side_1_feed, &side_2_feed],
+            &[],
+            BetType::MoneyLine,
+            OracleSource::Manual,
+        )
+        .await
+        .unwrap();
+    let user = divvy.create_user(100 * USDT).await;
+
+    // Quoted +200, the feed pays 25% less at +150
+    assert_eq!(
+        divvy
+            .init_bet(&user, &market, &side_1_feed, 100 * USDT, 200, 1, 2_000)
+            .await
+            .unwrap_err(),
+        exchange_error(ExchangeError::OddsSlippageExceeded)
+    );
+    assert_eq!(
+        divvy
+            .init_bet(&user, &market, &side_1_feed, 100 * USDT, 200, 1, 10_001)
+            .await
+            .unwrap_err(),
+        exchange_error(ExchangeError::InvalidOddsTolerance)
+    );
+    divvy
+        .init_bet(&user, &market, &side_1_feed, 100 * USDT, 200, 1, 2_500)
+        .await
+        .unwrap();
+    let market_state: Market = divvy.unpack(&market).await;
+    assert_eq!(market_state.market_sides[1].payout, 150 * USDT);
+}
+
+#[tokio::test]
+async fn test_pyth_oracle_odds() {
+    let program_id = Pubkey::new_unique();
+    let mut program_test = program_test(&program_id);
+    let side_0_feed = add_pyth_price(&mut program_test, -11_000_000_000, -8);
+    let side_1_feed = add_pyth_price(&mut program_test, 15_000, -2);
+    let side_2_feed = add_pyth_price(&mut program_test, 300, 0);
+    let result_feed = add_pyth_price(&mut program_test, 0, 0);
+    let mut divvy = funded_divvy(program_test, program_id).await;
+
+    let market = divvy
+        .init_market(
+            &result_feed,
+            &[&side_0_feed, &side_1_feed, &side_2_feed],
+            &[],
+            BetType::MoneyLine,
+            OracleSource::Pyth,
+        )
+        .await
+        .unwrap();
+
+    let user = divvy.create_user(200 * USDT).await;
+    divvy
+        .init_bet(&user, &market, &side_0_feed, 100 * USDT, -110, 0, 0)
+        .await
+        .unwrap();
+    divvy
+        .init_bet(&user, &market, &side_1_feed, 100 * USDT, 150, 1, 0)
+        .await
+        .unwrap();
+    let market_state: Market = divvy.unpack(&market).await;
+    assert_eq!(market_state.market_sides[0].payout, 90_909_090);
+    assert_eq!(market_state.market_sides[1].payout, 150 * USDT);
+}
+
+#[tokio::test]
+async fn test_market_reads_its_own_oracle_source() {
+    let program_id = Pubkey::new_unique();
+    let mut program_test = program_test(&program_id);
+    let side_0_feed = add_aggregator(&mut program_test, -110.0);
+    let side_1_feed = add_aggregator(&mut program_test, 150.0);
+    let side_2_feed = add_aggregator(&mut program_test, 300.0);
+    let result_feed = add_aggregator(&mut program_test, 0.0);
+    let mut divvy = funded_divvy(program_test, program_id).await;
+
+    // Switchboard feeds are not manual feeds owned by the program
+    let market = divvy
+        .init_market(
+            &result_feed,
+            &[&side_0_feed, &side_1_feed, &side_2_feed],
+            &[],
+            BetType::MoneyLine,
+            OracleSource::Manual,
+        )
+        .await
+        .unwrap();
+    let user = divvy.create_user(100 * USDT).await;
+    assert_eq!(
+        divvy
+            .init_bet(&user, &market, &side_0_feed, 100 * USDT, -110, 0, 0)
+            .await
+            .unwrap_err(),
+        exchange_error(ExchangeError::InvalidFeedAccount)
+    );
+}
+
+#[tokio::test]
+async fn test_manual_feed_requires_authority() {
+    let program_id = Pubkey::new_unique();
+    let mut divvy = funded_divvy(program_test(&program_id), program_id).await;
+    let feed = Keypair::new();
+    divvy
+        .create_account(&feed, ManualFeed::LEN, &program_id)
+        .await;
+
+    let User { keypair, .. } = divvy.create_user(0).await;
+    let instruction = instruction::update_manual_feed(
+        &program_id,
+        &keypair.pubkey(),
+        &feed.pubkey(),
+        &divvy.bet_pool_state.pubkey(),
+        1,
+        0,
+        &[],
+    )
+    .unwrap();
+    assert_eq!(
+        divvy
+            .process(&[instruction], &[&keypair])
+            .await
+            .unwrap_err(),
+        exchange_error(ExchangeError::NotValidAuthority)
+    );
+
+    // An unwritten manual feed can not price bets
+    let market = divvy
+        .init_market(
+            &feed.pubkey(),
+            &[&feed.pubkey(), &feed.pubkey(), &feed.pubkey()],
+            &[],
+            BetType::MoneyLine,
+            OracleSource::Manual,
+        )
+        .await
+        .unwrap();
+    let user = divvy.create_user(100 * USDT).await;
+    assert_eq!(
+        divvy
+            .init_bet(&user, &market, &feed.pubkey(), 100 * USDT, -110, 0, 0)
+            .await
+            .unwrap_err(),
+        exchange_error(ExchangeError::FeedNotInitialized)
+    );
+}
+
+#[tokio::test]
+async fn test_stale_feed_rejected() {
+    let program_id = Pubkey::new_unique();
+    let mut program_test = program_test(&program_id);
+    let stale_feed = add_aggregator_round(&mut program_test, -110.0, now() - 3_600, 1);
+    let fresh_feed = add_aggregator(&mut program_test, 150.0);
+    let side_2_feed = add_aggregator(&mut program_test, 300.0);
+    let result_feed = add_aggregator(&mut program_test, 1.0);
+    let mut divvy = funded_divvy(program_test, program_id).await;
+
+    let market = divvy
+        .init_market_with_limits(
+            &result_feed,
+            &[&stale_feed, &fresh_feed, &side_2_feed],
+            &[],
+            BetType::MoneyLine,
+            OracleSource::SwitchboardV1,
+            600,
+            1,
+        )
+        .await
+        .unwrap();
+    let user = divvy.create_user(200 * USDT).await;
+    assert_eq!(
+        divvy
+            .init_bet(&user, &market, &stale_feed, 100 * USDT, -110, 0, 0)
+            .await
+            .unwrap_err(),
+        exchange_error(ExchangeError::StaleFeed)
+    );
+    divvy
+        .init_bet(&user, &market, &fresh_feed, 100 * USDT, 150, 1, 0)
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn test_feed_min_responses() {
+    let program_id = Pubkey::new_unique();
+    let mut program_test = program_test(&program_id);
+    let side_0_feed = add_aggregator_round(&mut program_test, -110.0, now(), 2);
+    let side_1_feed = add_aggregator_round(&mut program_test, 150.0, now(), 3);
+    let side_2_feed = add_aggregator_round(&mut program_test, 300.0, now(), 3);
+    let result_feed = add_aggregator_round(&mut program_test, 1.0, now(), 2);
+    let mut divvy = funded_divvy(program_test, program_id).await;
+
+    let market = divvy
+        .init_market_with_limits(
+            &result_feed,
+            &[&side_0_feed, &side_1_feed, &side_2_feed],
+            &[],
+            BetType::MoneyLine,
+            OracleSource::SwitchboardV1,
+            0,
+            3,
+        )
+        .await
+        .unwrap();
+    let user = divvy.create_user(200 * USDT).await;
+    assert_eq!(
+        divvy
+            .init_bet(&user, &market, &side_0_feed, 100 * USDT, -110, 0, 0)
+            .await
+            .unwrap_err(),
+        exchange_error(ExchangeError::NotEnoughFeedResponses)
+    );
+    divvy
+        .init_bet(&user, &market, &side_1_feed, 100 * USDT, 150, 1, 0)
+        .await
+        .unwrap();
+
+    // A half populated result round can not settle the market
+    divvy.commence_market(&market).await.unwrap();
+    assert_eq!(
+        divvy
+            .settle_market(&market, &result_feed)
+            .await
+            .unwrap_err(),
+        exchange_error(ExchangeError::NotEnoughFeedResponses)
+    );
+    let market_state: Market = divvy.unpack(&market).await;
+    assert!(market_state.result == MoneylineMarketOutcome::Commenced);
+}
@@ -0,0 +1,170 @@
+mod common;
+
+use common::{exchange_error, funded_divvy, program_test, USDT};
+use divvybetting::{
+    error::ExchangeError,
+    instruction,
+    state::{Bet, BetType, Market, OracleSource},
+};
+use divvycommon::{
+    pda::{find_bet_address, find_market_address},
+    unpack_text,
+};
+use solana_program::{pubkey::Pubkey, system_instruction};
+use solana_sdk::signature::{Keypair, Signer};
+
+#[tokio::test]
+async fn test_one_market_per_event_and_bet_type() {
+    let program_id = Pubkey::new_unique();
+    let mut divvy = funded_divvy(program_test(&program_id), program_id).await;
+    let authority = Keypair::from_bytes(&divvy.authority.to_bytes()).unwrap();
+    let side_0_feed = divvy.create_manual_feed(-110, 0).await.unwrap().pubkey();
+    let side_1_feed = divvy.create_manual_feed(150, 0).await.unwrap().pubkey();
+    let side_2_feed = divvy.create_manual_feed(300, 0).await.unwrap().pubkey();
+    let result_feed = divvy.create_manual_feed(0, 0).await.unwrap().pubkey();
+    let init_market = |event_id, oracle_source| {
+        instruction::init_moneyline_market(
+            &program_id,
+            &authority.pubkey(),
+            &divvy.payer.pubkey(),
+            &result_feed,
+            &divvy.bet_pool_state.pubkey(),
+            &[&side_0_feed, &side_1_feed, &side_2_feed],
+            &[],
+            event_id,
+            BetType::MoneyLine,
+            oracle_source,
+            0,
+            0,
+            "NBA",
+            1_700_000_000,
+            &["Home", "Away", "Draw"],
+            &[],
+        )
+        .unwrap()
+    };
+    let first = init_market(7, OracleSource::Manual);
+    let duplicate = init_market(7, OracleSource::Pyth);
+    let other_event = init_market(8, OracleSource::Manual);
+
+    // Lamports sent to the address beforehand do not block the market
+    let (market, _) = find_market_address(&program_id, 7, BetType::MoneyLine);
+    let rent = divvy.banks_client.get_rent().await.unwrap();
+    let fund =
+        system_instruction::transfer(&divvy.payer.pubkey(), &market, rent.minimum_balance(0));
+    divvy.process(&[fund], &[]).await.unwrap();
+    divvy.process(&[first], &[&authority]).await.unwrap();
+    let market_state: Market = divvy.unpack(&market).await;
+    assert_eq!(market_state.event_id, 7);
+    assert_eq!(market_state.oracle_source, OracleSource::Manual);
+    assert_eq!(unpack_text(&market_state.league), Some("NBA"));
+    assert_eq!(market_state.start_time, 1_700_000_000);
+    assert_eq!(
+        unpack_text(&market_state.market_sides[1].label),
+        Some("Away")
+    );
+
+    assert_eq!(
+        divvy
+            .process(&[duplicate], &[&authority])
+            .await
+            .unwrap_err(),
+        exchange_error(ExchangeError::MarketAlreadyInitialized)
+    );
+    let mut wrong_address = other_event.clone();
+    wrong_address.accounts[1].pubkey = Pubkey::new_unique();
+    assert_eq!(
+        divvy
+            .process(&[wrong_address], &[&authority])
+            .await
+            .unwrap_err(),
+        exchange_error(ExchangeError::InvalidMarketAccount)
+    );
+    divvy.process(&[other_event], &[&authority]).await.unwrap();
+}
+
+#[tokio::test]
+async fn test_bets_at_derived_addresses() {
+    let program_id = Pubkey::new_unique();
+    let mut divvy = funded_divvy(program_test(&program_id), program_id).await;
+    let side_0_feed = divvy.create_manual_feed(-110, 0).await.unwrap().pubkey();
+    let side_1_feed = divvy.create_manual_feed(150, 0).await.unwrap().pubkey();
+    let side_2_feed = divvy.create_manual_feed(300, 0).await.unwrap().pubkey();
+    let result_feed = divvy.create_manual_feed(0, 0).await.unwrap().pubkey();
+    let market = divvy
+        .init_market(
+            &result_feed,
+            &[&side_0_feed, &side_1_feed, &side_2_feed],
+            &[],
+            BetType::MoneyLine,
+            OracleSource::Manual,
+        )
+        .await
+        .unwrap();
+    let user = divvy.create_user(300 * USDT).await;
+    let bet_pool_state = divvy.bet_pool_state.pubkey();
+    let hp_usdt = divvy.hp_usdt_account.pubkey();
+    let hp_state = divvy.hp_state.pubkey();
+    let bet_usdt = divvy.bet_usdt_account.pubkey();
+    let init_bet = |nonce, risk| {
+        instruction::init_bet(
+            &program_id,
+            &user.keypair.pubkey(),
+            &side_1_feed,
+            None,
+            &market,
+            &bet_pool_state,
+            &hp_usdt,
+            &hp_state,
+            &bet_usdt,
+            &user.usdt_account.pubkey(),
+            risk,
+            150,
+            1,
+            0,
+            nonce,
+        )
+        .unwrap()
+    };
+
+    // The bettor's bets are found by walking their nonces
+    let (first_bet, _) = find_bet_address(&program_id, &market, &user.keypair.pubkey(), 0);
+    let (second_bet, _) = find_bet_address(&program_id, &market, &user.keypair.pubkey(), 1);
+    divvy
+        .process(
+            &[init_bet(0, 100 * USDT), init_bet(1, 50 * USDT)],
+            &[&user.keypair],
+        )
+        .await
+        .unwrap();
+    let bet: Bet = divvy.unpack(&first_bet).await;
+    assert_eq!(bet.user_main_account, user.keypair.pubkey());
+    assert_eq!(bet.user_risk, 100 * USDT);
+    let bet: Bet = divvy.unpack(&second_bet).await;
+    assert_eq!(bet.user_risk, 50 * USDT);
+
+    assert_eq!(
+        divvy
+            .process(&[init_bet(0, 10 * USDT)], &[&user.keypair])
+            .await
+            .unwrap_err(),
+        exchange_error(ExchangeError::BetAlreadyInitialized)
+    );
+    // A bet under another bettor's address is rejected
+    let mut wrong_address = init_bet(2, 10 * USDT);
+    wrong_address.accounts[2].pubkey =
+        find_bet_address(&program_id, &market, &divvy.payer.pubkey(), 2).0;
+    assert_eq!(
+        divvy
+            .process(&[wrong_address], &[&user.keypair])
+            .await
+            .unwrap_err(),
+        exchange_error(ExchangeError::InvalidBetAccount)
+    );
+
+    // Settling closes the bet and returns the rent to the bettor
+    divvy.commence_market(&market).await.unwrap();
+    divvy.settle_market(&market, &result_feed).await.unwrap();
+    divvy.settle_bet(&user, &market, &first_bet).await.unwrap();
+    assert!(!divvy.account_exists(&first_bet).await);
+}
@@ -0,0 +1,417 @@
+mod common;
+
+use common::{add_aggregator, exchange_error, funded_divvy, program_test, USDT};
+use divvybetting::{
+    error::ExchangeError,
+    instruction,
+    schema::divvy_house_program_id,
+    state::{Bet, BetType, Market, MoneylineMarketOutcome, OracleSource},
+};
+use divvycommon::pda::find_ledger_address;
+use divvyhouse::state::{HpLiquidity, MarketLedger};
+use solana_program::pubkey::Pubkey;
+use solana_sdk::signature::Signer;
+
+#[tokio::test]
+async fn test_spread_market() {
+    let program_id = Pubkey::new_unique();
+    let mut program_test = program_test(&program_id);
+    let home_odds_feed = add_aggregator(&mut program_test, -110.0);
+    let away_odds_feed = add_aggregator(&mut program_test, -110.0);
+    let home_points_feed = add_aggregator(&mut program_test, -3.5);
+    let away_points_feed = add_aggregator(&mut program_test, 3.5);
+    // Home wins 24 to 20
+    let result_feed = add_aggregator(&mut program_test, 24_020.0);
+    let mut divvy = funded_divvy(program_test, program_id).await;
+
+    let market = divvy
+        .init_market(
+            &result_feed,
+            &[&home_odds_feed, &away_odds_feed],
+            &[&home_points_feed, &away_points_feed],
+            BetType::Spread,
+            OracleSource::SwitchboardV1,
+        )
+        .await
+        .unwrap();
+
+    let home = divvy.create_user(220 * USDT).await;
+    let away = divvy.create_user(110 * USDT).await;
+    // Spread bets need the points feed of their side
+    assert_eq!(
+        divvy
+            .init_bet_with_points_feed(
+                &home,
+                &market,
+                &home_odds_feed,
+                Some(&away_points_feed),
+                220 * USDT,
+                -110,
+                0,
+                0,
+            )
+            .await
+            .unwrap_err(),
+        exchange_error(ExchangeError::InvalidFeedAccount)
+    );
+    let home_bet = divvy
+        .init_bet_with_points_feed(
+            &home,
+            &market,
+            &home_odds_feed,
+            Some(&home_points_feed),
+            220 * USDT,
+            -110,
+            0,
+            0,
+        )
+        .await
+        .unwrap();
+    let away_bet = divvy
+        .init_bet_with_points_feed(
+            &away,
+            &market,
+            &away_odds_feed,
+            Some(&away_points_feed),
+            110 * USDT,
+            -110,
+            1,
+            0,
+        )
+        .await
+        .unwrap();
+
+    let bet_state: Bet = divvy.unpack(&home_bet).await;
+    assert_eq!(bet_state.line, -35);
+    let bet_state: Bet = divvy.unpack(&away_bet).await;
+    assert_eq!(bet_state.line, 35);
+    let market_state: Market = divvy.unpack(&market).await;
+    assert_eq!(market_state.market_sides[0].line, -35);
+    assert_eq!(market_state.market_sides[1].line, 35);
+    assert_eq!(market_state.locked_liquidity, 90 * USDT);
+
+    divvy.commence_market(&market).await.unwrap();
+    // Bets can not be graded before the final score is in. The bettor settles
+    // so the later settlement is a different transaction.
+    let instruction = instruction::settle_bet(
+        &program_id,
+        &home.keypair.pubkey(),
+        &market,
+        &home_bet,
+        &divvy.pda,
+        &divvy.bet_usdt_account.pubkey(),
+        &home.usdt_account.pubkey(),
+        &home.keypair.pubkey(),
+        &divvy.bet_pool_state.pubkey(),
+        &divvy.hp_usdt_account.pubkey(),
+        &divvy.hp_state.pubkey(),
+        &divvy_house_program_id::ID,
+        divvy.bump_seed,
+    )
+    .unwrap();
+    assert_eq!(
+        divvy
+            .process(&[instruction], &[&home.keypair])
+            .await
+            .unwrap_err(),
+        exchange_error(ExchangeError::MarketNotSettled)
+    );
+
+    divvy.settle_market(&market, &result_feed).await.unwrap();
+    let market_state: Market = divvy.unpack(&market).await;
+    assert!(market_state.result == MoneylineMarketOutcome::FinalScore);
+    assert_eq!(market_state.home_score, 24);
+    assert_eq!(market_state.away_score, 20);
+    assert_eq!(market_state.bettor_balance, 420 * USDT);
+    // The house lost, no fees are taken
+    assert_eq!(
+        divvy
+            .token_balance(&divvy.insurance_fund_usdt_account.pubkey())
+            .await,
+        0
+    );
+
+    divvy.settle_bet(&home, &market, &home_bet).await.unwrap();
+    divvy.settle_bet(&away, &market, &away_bet).await.unwrap();
+    assert_eq!(
+        divvy.token_balance(&home.usdt_account.pubkey()).await,
+        420 * USDT
+    );
+    assert_eq!(divvy.token_balance(&away.usdt_account.pubkey()).await, 0);
+    let market_state: Market = divvy.unpack(&market).await;
+    assert_eq!(market_state.bettor_balance, 0);
+    assert_eq!(market_state.pending_bets, 0);
+    assert_eq!(
+        divvy.token_balance(&divvy.bet_usdt_account.pubkey()).await,
+        0
+    );
+}
+
+#[tokio::test]
+async fn test_whole_point_spread_pushes() {
+    let program_id = Pubkey::new_unique();
+    let mut program_test = program_test(&program_id);
+    let home_odds_feed = add_aggregator(&mut program_test, -110.0);
+    let away_odds_feed = add_aggregator(&mut program_test, -110.0);
+    let home_points_feed = add_aggregator(&mut program_test, -3.0);
+    let away_points_feed = add_aggregator(&mut program_test, 3.0);
+    // Home wins 24 to 21, landing on the number
+    let result_feed = add_aggregator(&mut program_test, 24_021.0);
+    let mut divvy = funded_divvy(program_test, program_id).await;
+
+    let market = divvy
+        .init_market(
+            &result_feed,
+            &[&home_odds_feed, &away_odds_feed],
+            &[&home_points_feed, &away_points_feed],
+            BetType::Spread,
+            OracleSource::SwitchboardV1,
+        )
+        .await
+        .unwrap();
+    let home = divvy.create_user(110 * USDT).await;
+    let away = divvy.create_user(110 * USDT).await;
+    let home_bet = divvy
+        .init_bet_with_points_feed(
+            &home,
+            &market,
+            &home_odds_feed,
+            Some(&home_points_feed),
+            110 * USDT,
+            -110,
+            0,
+            0,
+        )
+        .await
+        .unwrap();
+    let away_bet = divvy
+        .init_bet_with_points_feed(
+            &away,
+            &market,
+            &away_odds_feed,
+            Some(&away_points_feed),
+            110 * USDT,
+            -110,
+            1,
+            0,
+        )
+        .await
+        .unwrap();
+    let market_state: Market = divvy.unpack(&market).await;
+    assert_eq!(market_state.locked_liquidity, 0);
+
+    divvy.commence_market(&market).await.unwrap();
+    divvy.settle_market(&market, &result_feed).await.unwrap();
+    let market_state: Market = divvy.unpack(&market).await;
+    // Every bet pushed, the house neither wins nor loses
+    assert_eq!(market_state.bettor_balance, 220 * USDT);
+    assert_eq!(
+        divvy
+            .token_balance(&divvy.insurance_fund_usdt_account.pubkey())
+            .await,
+        0
+    );
+
+    divvy.settle_bet(&home, &market, &home_bet).await.unwrap();
+    divvy.settle_bet(&away, &market, &away_bet).await.unwrap();
+    assert_eq!(
+        divvy.token_balance(&home.usdt_account.pubkey()).await,
+        110 * USDT
+    );
+    assert_eq!(
+        divvy.token_balance(&away.usdt_account.pubkey()).await,
+        110 * USDT
+    );
+    let market_state: Market = divvy.unpack(&market).await;
+    assert_eq!(market_state.bettor_balance, 0);
+    assert_eq!(market_state.market_sides[0].risk, 0);
+    assert_eq!(market_state.market_sides[1].risk, 0);
+}
+
+#[tokio::test]
+async fn test_total_market() {
+    let program_id = Pubkey::new_unique();
+    let mut program_test = program_test(&program_id);
+    let over_odds_feed = add_aggregator(&mut program_test, -110.0);
+    let under_odds_feed = add_aggregator(&mut program_test, -110.0);
+    let over_points_feed = add_aggregator(&mut program_test, 45.5);
+    let under_points_feed = add_aggregator(&mut program_test, 45.5);
+    // 45 points in total
+    let result_feed = add_aggregator(&mut program_test, 24_021.0);
+    let mut divvy = funded_divvy(program_test, program_id).await;
+
+    let market = divvy
+        .init_market(
+            &result_feed,
+            &[&over_odds_feed, &under_odds_feed],
+            &[&over_points_feed, &under_points_feed],
+            BetType::Total,
+            OracleSource::SwitchboardV1,
+        )
+        .await
+        .unwrap();
+    let over = divvy.create_user(110 * USDT).await;
+    let under = divvy.create_user(110 * USDT).await;
+    let over_bet = divvy
+        .init_bet_with_points_feed(
+            &over,
+            &market,
+            &over_odds_feed,
+            Some(&over_points_feed),
+            110 * USDT,
+            -110,
+            0,
+            0,
+        )
+        .await
+        .unwrap();
+    let under_bet = divvy
+        .init_bet_with_points_feed(
+            &under,
+            &market,
+            &under_odds_feed,
+            Some(&under_points_feed),
+            110 * USDT,
+            -110,
+            1,
+            0,
+        )
+        .await
+        .unwrap();
+
+    divvy.commence_market(&market).await.unwrap();
+    divvy.settle_market(&market, &result_feed).await.unwrap();
+    let market_state: Market = divvy.unpack(&market).await;
+    assert_eq!(market_state.bettor_balance, 210 * USDT);
+
+    divvy.settle_bet(&over, &market, &over_bet).await.unwrap();
+    divvy.settle_bet(&under, &market, &under_bet).await.unwrap();
+    assert_eq!(divvy.token_balance(&over.usdt_account.pubkey()).await, 0);
+    assert_eq!(
+        divvy.token_balance(&under.usdt_account.pubkey()).await,
+        210 * USDT
+    );
+    let market_state: Market = divvy.unpack(&market).await;
+    assert_eq!(market_state.bettor_balance, 0);
+}
+
+#[tokio::test]
+async fn test_bets_graded_at_their_own_line() {
+    let program_id = Pubkey::new_unique();
+    let mut divvy = funded_divvy(program_test(&program_id), program_id).await;
+    let home_odds_feed = divvy.create_manual_feed(-110, 0).await.unwrap().pubkey();
+    let away_odds_feed = divvy.create_manual_feed(-110, 0).await.unwrap().pubkey();
+    let home_points_feed = divvy.create_manual_feed(-35, -1).await.unwrap().pubkey();
+    let away_points_feed = divvy.create_manual_feed(35, -1).await.unwrap().pubkey();
+    let result_feed = divvy.create_manual_feed(0, 0).await.unwrap().pubkey();
+
+    let market = divvy
+        .init_market(
+            &result_feed,
+            &[&home_odds_feed, &away_odds_feed],
+            &[&home_points_feed, &away_points_feed],
+            BetType::Spread,
+            OracleSource::Manual,
+        )
+        .await
+        .unwrap();
+    let early = divvy.create_user(110 * USDT).await;
+    let late = divvy.create_user(110 * USDT).await;
+    let away = divvy.create_user(110 * USDT).await;
+    let early_bet = divvy
+        .init_bet_with_points_feed(
+            &early,
+            &market,
+            &home_odds_feed,
+            Some(&home_points_feed),
+            110 * USDT,
+            -110,
+            0,
+            0,
+        )
+        .await
+        .unwrap();
+    // The line moves to home -4.5
+    divvy
+        .update_manual_feed(&home_points_feed, -45, -1)
+        .await
+        .unwrap();
+    let late_bet = divvy
+        .init_bet_with_points_feed(
+            &late,
+            &market,
+            &home_odds_feed,
+            Some(&home_points_feed),
+            110 * USDT,
+            -110,
+            0,
+            0,
+        )
+        .await
+        .unwrap();
+    let away_bet = divvy
+        .init_bet_with_points_feed(
+            &away,
+            &market,
+            &away_odds_feed,
+            Some(&away_points_feed),
+            110 * USDT,
+            -110,
+            1,
+            0,
+        )
+        .await
+        .unwrap();
+
+    let bet_state: Bet = divvy.unpack(&late_bet).await;
+    assert_eq!(bet_state.line, -45);
+    // Liquidity is locked as if both home bets had the more generous -3.5
+    let market_state: Market = divvy.unpack(&market).await;
+    assert_eq!(market_state.market_sides[0].line, -35);
+    assert_eq!(market_state.locked_liquidity, 90 * USDT);
+
+    // Home wins by 4, covering -3.5 but not -4.5
+    divvy.commence_market(&market).await.unwrap();
+    divvy
+        .update_manual_feed(&result_feed, 24_020, 0)
+        .await
+        .unwrap();
+    divvy.settle_market(&market, &result_feed).await.unwrap();
+    // The settlement pays both home bets, so the house looks to lose its 90
+    let hp_usdt = divvy.hp_usdt_account.pubkey();
+    let bet_usdt = divvy.bet_usdt_account.pubkey();
+    let (ledger, _) = find_ledger_address(
+        &divvy_house_program_id::ID,
+        &divvy.hp_state.pubkey(),
+        &market,
+    );
+    assert_eq!(divvy.token_balance(&hp_usdt).await, 910 * USDT);
+    assert_eq!(divvy.token_balance(&bet_usdt).await, 420 * USDT);
+    let ledger_state: MarketLedger = divvy.unpack(&ledger).await;
+    assert_eq!(ledger_state.realized_pnl, -90 * USDT as i64);
+
+    divvy.settle_bet(&early, &market, &early_bet).await.unwrap();
+    divvy.settle_bet(&late, &market, &late_bet).await.unwrap();
+    divvy.settle_bet(&away, &market, &away_bet).await.unwrap();
+    assert_eq!(
+        divvy.token_balance(&early.usdt_account.pubkey()).await,
+        210 * USDT
+    );
+    assert_eq!(divvy.token_balance(&late.usdt_account.pubkey()).await, 0);
+    assert_eq!(divvy.token_balance(&away.usdt_account.pubkey()).await, 0);
+    let market_state: Market = divvy.unpack(&market).await;
+    assert_eq!(market_state.bettor_balance, 0);
+    assert_eq!(market_state.pending_bets, 0);
+
+    // What was kept for the late bet goes back to the house, which won 120
+    assert_eq!(divvy.token_balance(&hp_usdt).await, 1_120 * USDT);
+    assert_eq!(divvy.token_balance(&bet_usdt).await, 0);
+    let ledger_state: MarketLedger = divvy.unpack(&ledger).await;
+    assert_eq!(ledger_state.lent, 90 * USDT);
+    assert_eq!(ledger_state.returned, 210 * USDT);
+    assert_eq!(ledger_state.realized_pnl, 120 * USDT as i64);
+    assert!(ledger_state.settled);
+    let hp_state: HpLiquidity = divvy.unpack(&divvy.hp_state.pubkey()).await;
+    assert_eq!(hp_state.committed_liquidity, 0);
+    assert_eq!(hp_state.realized_pnl, 120 * USDT as i64);
+}
@@ -0,0 +1,114 @@
+mod common;
+
+use common::{exchange_error, funded_divvy, program_test, USDT};
+use divvybetting::{
+    error::ExchangeError,
+    instruction,
+    state::{BetType, ExposureLimits, OracleSource, Position},
+};
+use divvycommon::pda::find_position_address;
+use solana_program::pubkey::Pubkey;
+use solana_sdk::signature::{Keypair, Signer};
+
+#[tokio::test]
+async fn test_wallet_position_limits() {
+    let program_id = Pubkey::new_unique();
+    let mut divvy = funded_divvy(program_test(&program_id), program_id).await;
+    let bet_pool_state = divvy.bet_pool_state.pubkey();
+    let authority = Keypair::from_bytes(&divvy.authority.to_bytes()).unwrap();
+    let side_0_feed = divvy.create_manual_feed(-110, 0).await.unwrap().pubkey();
+    let side_1_feed = divvy.create_manual_feed(150, 0).await.unwrap().pubkey();
+    let side_2_feed = divvy.create_manual_feed(200, 0).await.unwrap().pubkey();
+    let result_feed = divvy.create_manual_feed(0, 0).await.unwrap().pubkey();
+    let market = divvy
+        .init_market(
+            &result_feed,
+            &[&side_0_feed, &side_1_feed, &side_2_feed],
+            &[],
+            BetType::MoneyLine,
+            OracleSource::Manual,
+        )
+        .await
+        .unwrap();
+    let whale = divvy.create_user(300 * USDT).await;
+    let other = divvy.create_user(300 * USDT).await;
+
+    let exposure_limits = ExposureLimits {
+        max_wallet_risk: 100 * USDT,
+        max_wallet_payout: 100 * USDT,
+        ..ExposureLimits::default()
+    };
+    let instruction = instruction::update_exposure_limits(
+        &program_id,
+        &authority.pubkey(),
+        &bet_pool_state,
+        exposure_limits,
+        &[],
+    )
+    .unwrap();
+    divvy.process(&[instruction], &[&authority]).await.unwrap();
+
+    // Splitting a position into small bets does not get around the caps
+    divvy
+        .init_bet(&whale, &market, &side_1_feed, 40 * USDT, 150, 1, 0)
+        .await
+        .unwrap();
+    assert_eq!(
+        divvy
+            .init_bet(&whale, &market, &side_1_feed, 40 * USDT, 150, 1, 0)
+            .await
+            .unwrap_err(),
+        exchange_error(ExchangeError::WalletPayoutLimitExceeded)
+    );
+    divvy
+        .init_bet(&whale, &market, &side_2_feed, 20 * USDT, 200, 2, 0)
+        .await
+        .unwrap();
+    assert_eq!(
+        divvy
+            .init_bet(&whale, &market, &side_0_feed, 50 * USDT, -110, 0, 0)
+            .await
+            .unwrap_err(),
+        exchange_error(ExchangeError::WalletRiskLimitExceeded)
+    );
+    // Every wallet has its own position
+    divvy
+        .init_bet(&other, &market, &side_1_feed, 40 * USDT, 150, 1, 0)
+        .await
+        .unwrap();
+
+    let (position, _) = find_position_address(&program_id, &market, &whale.keypair.pubkey());
+    let position_state: Position = divvy.unpack(&position).await;
+    assert_eq!(position_state.market, market);
+    assert_eq!(position_state.user_main_account, whale.keypair.pubkey());
+    assert_eq!(position_state.risk, [0, 40 * USDT, 20 * USDT]);
+    assert_eq!(position_state.payout, [0, 60 * USDT, 40 * USDT]);
+
+    // A bet can not be added to another wallet's position
+    let mut wrong_position = instruction::init_bet(
+        &program_id,
+        &other.keypair.pubkey(),
+        &side_0_feed,
+        None,
+        &market,
+        &bet_pool_state,
+        &divvy.hp_usdt_account.pubkey(),
+        &divvy.hp_state.pubkey(),
+        &divvy.bet_usdt_account.pubkey(),
+        &other.usdt_account.pubkey(),
+        10 * USDT,
+        -110,
+        0,
+        0,
+        100,
+    )
+    .unwrap();
+    wrong_position.accounts[3].pubkey = position;
+    assert_eq!(
+        divvy
+            .process(&[wrong_position], &[&other.keypair])
+            .await
+            .unwrap_err(),
+        exchange_error(ExchangeError::InvalidPositionAccount)
+    );
+}
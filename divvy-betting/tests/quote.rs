@@ -0,0 +1,141 @@
+mod common;
+
+use common::{exchange_error, program_test, Divvy, USDT};
+use divvybetting::{
+    error::ExchangeError,
+    schema::divvy_house_program_id,
+    state::{BetType, OracleSource},
+};
+use divvyhouse::{calculate_ht_amount, calculate_shares, instruction, state::HpLiquidity};
+use solana_program::{program_pack::Pack, pubkey::Pubkey};
+use solana_sdk::signature::{Keypair, Signer};
+use spl_token::state::{Account as TokenAccount, Mint as TokenMint};
+
+#[tokio::test]
+async fn test_quotes_match_execution() {
+    let program_id = Pubkey::new_unique();
+    let mut divvy = Divvy::start(program_test(&program_id), program_id).await;
+    let hp_state = divvy.hp_state.pubkey();
+    let hp_usdt = divvy.hp_usdt_account.pubkey();
+    let ht_mint = divvy.ht_mint.pubkey();
+    let side_0_feed = divvy.create_manual_feed(-110, 0).await.unwrap().pubkey();
+    let side_1_feed = divvy.create_manual_feed(150, 0).await.unwrap().pubkey();
+    let side_2_feed = divvy.create_manual_feed(300, 0).await.unwrap().pubkey();
+    let side_2_won = divvy.create_manual_feed(2, 0).await.unwrap().pubkey();
+    let market = divvy
+        .init_market(
+            &side_2_won,
+            &[&side_0_feed, &side_1_feed, &side_2_feed],
+            &[],
+            BetType::MoneyLine,
+            OracleSource::Manual,
+        )
+        .await
+        .unwrap();
+    let first_lp = divvy.create_user(1_000 * USDT).await;
+    let second_lp = divvy.create_user(100 * USDT).await;
+    let bettor = divvy.create_user(100 * USDT).await;
+    divvy.deposit(&first_lp, 1_000 * USDT).await.unwrap();
+    divvy
+        .init_bet(&bettor, &market, &side_1_feed, 100 * USDT, 150, 1, 0)
+        .await
+        .unwrap();
+    divvy.commence_market(&market).await.unwrap();
+    divvy.settle_market(&market, &side_2_won).await.unwrap();
+
+    // The house token is now worth more than a USDT
+    let pool_state: HpLiquidity = divvy.unpack(&hp_state).await;
+    assert!(pool_state.realized_pnl > 0);
+    let quote_deposit = |usdt_amount: u64| {
+        instruction::quote_deposit(
+            &divvy_house_program_id::ID,
+            &ht_mint,
+            &hp_usdt,
+            &hp_state,
+            usdt_amount,
+        )
+        .unwrap()
+    };
+    let quote_withdraw = |ht_amount: u64| {
+        instruction::quote_withdraw(
+            &divvy_house_program_id::ID,
+            &ht_mint,
+            &hp_usdt,
+            &hp_state,
+            ht_amount,
+        )
+        .unwrap()
+    };
+    divvy
+        .process(&[quote_deposit(100 * USDT)], &[])
+        .await
+        .unwrap();
+    let pool_usdt_state: TokenAccount = divvy.unpack(&hp_usdt).await;
+    let ht_mint_state: TokenMint = divvy.unpack(&ht_mint).await;
+    let (pool_value, ht_supply) =
+        calculate_shares(&pool_usdt_state, &ht_mint_state, &pool_state).unwrap();
+    assert_eq!(pool_value, 1_000 * USDT + pool_state.realized_pnl as u64);
+    let ht_amount = calculate_ht_amount(100 * USDT, pool_value, ht_supply).unwrap();
+    assert!(ht_amount < 100 * USDT);
+    divvy.deposit(&second_lp, 100 * USDT).await.unwrap();
+    assert_eq!(
+        divvy.token_balance(&second_lp.ht_account.pubkey()).await,
+        ht_amount
+    );
+
+    // A quote fails where the withdrawal would
+    let pool_balance = divvy.token_balance(&hp_usdt).await;
+    assert_eq!(
+        divvy
+            .process(&[quote_withdraw(1_100 * USDT)], &[])
+            .await
+            .unwrap_err(),
+        exchange_error(ExchangeError::NotEnoughAvailableLiquidityForWithdrawal)
+    );
+    divvy
+        .process(&[quote_withdraw(ht_amount)], &[])
+        .await
+        .unwrap();
+    divvy.withdraw(&second_lp, ht_amount).await.unwrap();
+    assert!(divvy.token_balance(&second_lp.usdt_account.pubkey()).await <= 100 * USDT);
+    assert_eq!(
+        divvy.token_balance(&hp_usdt).await,
+        pool_balance - divvy.token_balance(&second_lp.usdt_account.pubkey()).await
+    );
+
+    let authority = Keypair::from_bytes(&divvy.authority.to_bytes()).unwrap();
+    let freeze = instruction::freeze(
+        &divvy_house_program_id::ID,
+        &authority.pubkey(),
+        &hp_state,
+        true,
+        &[],
+    )
+    .unwrap();
+    divvy.process(&[freeze], &[&authority]).await.unwrap();
+    assert_eq!(
+        divvy
+            .process(&[quote_deposit(10 * USDT)], &[])
+            .await
+            .unwrap_err(),
+        exchange_error(ExchangeError::PoolFrozen)
+    );
+
+    // Quotes only read the pool state of the house program
+    let fake_hp_state = Keypair::new();
+    divvy
+        .create_account(&fake_hp_state, HpLiquidity::LEN, &Pubkey::new_unique())
+        .await;
+    let quote = instruction::quote_withdraw(
+        &divvy_house_program_id::ID,
+        &ht_mint,
+        &hp_usdt,
+        &fake_hp_state.pubkey(),
+        USDT,
+    )
+    .unwrap();
+    assert_eq!(
+        divvy.process(&[quote], &[]).await.unwrap_err(),
+        exchange_error(ExchangeError::InvalidHousePoolStateAccount)
+    );
+}
@@ -0,0 +1,155 @@
+mod common;
+
+use common::{exchange_error, funded_divvy, program_test, USDT};
+use divvybetting::{
+    error::ExchangeError,
+    instruction,
+    schema::divvy_house_program_id,
+    state::{BetType, Market, MoneylineMarketOutcome, OracleSource, Role},
+};
+use solana_program::pubkey::Pubkey;
+use solana_sdk::signature::{Keypair, Signer};
+
+#[tokio::test]
+async fn test_suspended_market_takes_no_bets_and_does_not_settle() {
+    let program_id = Pubkey::new_unique();
+    let mut divvy = funded_divvy(program_test(&program_id), program_id).await;
+    let bet_pool_state = divvy.bet_pool_state.pubkey();
+    let authority = Keypair::from_bytes(&divvy.authority.to_bytes()).unwrap();
+    let pauser = Keypair::new();
+    let side_0_feed = divvy.create_manual_feed(-110, 0).await.unwrap().pubkey();
+    let side_1_feed = divvy.create_manual_feed(150, 0).await.unwrap().pubkey();
+    let side_2_feed = divvy.create_manual_feed(300, 0).await.unwrap().pubkey();
+    let result_feed = divvy.create_manual_feed(0, 0).await.unwrap().pubkey();
+    let market = divvy
+        .init_market(
+            &result_feed,
+            &[&side_0_feed, &side_1_feed, &side_2_feed],
+            &[],
+            BetType::MoneyLine,
+            OracleSource::Manual,
+        )
+        .await
+        .unwrap();
+    let other_market = divvy
+        .init_market(
+            &result_feed,
+            &[&side_0_feed, &side_1_feed, &side_2_feed],
+            &[],
+            BetType::MoneyLine,
+            OracleSource::Manual,
+        )
+        .await
+        .unwrap();
+    let user = divvy.create_user(300 * USDT).await;
+    let bet = divvy
+        .init_bet(&user, &market, &side_0_feed, 110 * USDT, -110, 0, 0)
+        .await
+        .unwrap();
+
+    // Only the authority or a pauser suspends a market
+    let suspend = instruction::suspend_market(
+        &program_id,
+        &pauser.pubkey(),
+        &market,
+        &bet_pool_state,
+        true,
+        &[],
+    )
+    .unwrap();
+    assert_eq!(
+        divvy
+            .process(&[suspend.clone()], &[&pauser])
+            .await
+            .unwrap_err(),
+        exchange_error(ExchangeError::NotValidAuthority)
+    );
+    let grant = instruction::grant_role(
+        &program_id,
+        &authority.pubkey(),
+        &bet_pool_state,
+        Role::Pauser,
+        &pauser.pubkey(),
+        &[],
+    )
+    .unwrap();
+    divvy
+        .process(&[grant, suspend], &[&authority, &pauser])
+        .await
+        .unwrap();
+    let market_state: Market = divvy.unpack(&market).await;
+    assert!(market_state.suspended);
+
+    // A suspended market takes no bets while other markets still do
+    assert_eq!(
+        divvy
+            .init_bet(&user, &market, &side_1_feed, 10 * USDT, 150, 1, 0)
+            .await
+            .unwrap_err(),
+        exchange_error(ExchangeError::MarketSuspended)
+    );
+    divvy
+        .init_bet(&user, &other_market, &side_1_feed, 10 * USDT, 150, 1, 0)
+        .await
+        .unwrap();
+
+    let settle = |initializer: &Pubkey| {
+        instruction::settle_moneyline_market(
+            &program_id,
+            initializer,
+            &market,
+            &bet_pool_state,
+            &result_feed,
+            &divvy.pda,
+            &divvy.hp_usdt_account.pubkey(),
+            &divvy.bet_usdt_account.pubkey(),
+            &divvy.insurance_fund_usdt_account.pubkey(),
+            &divvy.divvy_foundation_proceeds_usdt_account.pubkey(),
+            &divvy.hp_state.pubkey(),
+            &divvy_house_program_id::ID,
+            divvy.bump_seed,
+        )
+        .unwrap()
+    };
+    let pauser_settle = settle(&pauser.pubkey());
+    let user_settle = settle(&user.keypair.pubkey());
+    divvy.commence_market(&market).await.unwrap();
+    assert_eq!(
+        divvy
+            .process(&[pauser_settle], &[&pauser])
+            .await
+            .unwrap_err(),
+        exchange_error(ExchangeError::MarketSuspended)
+    );
+
+    // The global freeze still stops a resumed market
+    let resume = instruction::suspend_market(
+        &program_id,
+        &pauser.pubkey(),
+        &market,
+        &bet_pool_state,
+        false,
+        &[],
+    )
+    .unwrap();
+    let freeze =
+        instruction::freeze(&program_id, &pauser.pubkey(), &bet_pool_state, true, &[]).unwrap();
+    divvy.process(&[resume, freeze], &[&pauser]).await.unwrap();
+    let market_state: Market = divvy.unpack(&market).await;
+    assert!(!market_state.suspended);
+    assert_eq!(
+        divvy
+            .process(&[user_settle], &[&user.keypair])
+            .await
+            .unwrap_err(),
+        exchange_error(ExchangeError::BettingFrozen)
+    );
+
+    let unfreeze =
+        instruction::freeze(&program_id, &pauser.pubkey(), &bet_pool_state, false, &[]).unwrap();
+    divvy.process(&[unfreeze], &[&pauser]).await.unwrap();
+    divvy.settle_market(&market, &result_feed).await.unwrap();
+    let market_state: Market = divvy.unpack(&market).await;
+    assert!(market_state.result == MoneylineMarketOutcome::MarketSide0Won);
+    divvy.settle_bet(&user, &market, &bet).await.unwrap();
+}
@@ -0,0 +1,320 @@
+mod common;
+
+use common::{add_aggregator, exchange_error, funded_divvy, program_test, USDT};
+use divvybetting::{
+    error::ExchangeError,
+    instruction,
+    schema::divvy_house_program_id,
+    state::{BetType, BettingPoolState, Market, MoneylineMarketOutcome, OracleSource, VOID_RESULT},
+};
+use solana_program::pubkey::Pubkey;
+use solana_sdk::signature::{Keypair, Signer};
+
+#[tokio::test]
+async fn test_void_moneyline_market() {
+    let program_id = Pubkey::new_unique();
+    let mut divvy = funded_divvy(program_test(&program_id), program_id).await;
+
+    let side_0_feed = divvy.create_manual_feed(-110, 0).await.unwrap().pubkey();
+    let side_1_feed = divvy.create_manual_feed(150, 0).await.unwrap().pubkey();
+    let side_2_feed = divvy.create_manual_feed(300, 0).await.unwrap().pubkey();
+    let result_feed = divvy.create_manual_feed(0, 0).await.unwrap().pubkey();
+    let market = divvy
+        .init_market(
+            &result_feed,
+            &[&side_0_feed, &side_1_feed, &side_2_feed],
+            &[],
+            BetType::MoneyLine,
+            OracleSource::Manual,
+        )
+        .await
+        .unwrap();
+
+    let home = divvy.create_user(110 * USDT).await;
+    let away = divvy.create_user(100 * USDT).await;
+    let home_bet = divvy
+        .init_bet(&home, &market, &side_0_feed, 110 * USDT, -110, 0, 0)
+        .await
+        .unwrap();
+    let away_bet = divvy
+        .init_bet(&away, &market, &side_1_feed, 100 * USDT, 150, 1, 0)
+        .await
+        .unwrap();
+    let market_state: Market = divvy.unpack(&market).await;
+    assert_eq!(market_state.locked_liquidity, 40 * USDT);
+
+    divvy.commence_market(&market).await.unwrap();
+    assert_eq!(
+        divvy.token_balance(&divvy.hp_usdt_account.pubkey()).await,
+        960 * USDT
+    );
+
+    // The game is cancelled
+    divvy
+        .update_manual_feed(&result_feed, VOID_RESULT as i64, 0)
+        .await
+        .unwrap();
+    divvy.settle_market(&market, &result_feed).await.unwrap();
+    let market_state: Market = divvy.unpack(&market).await;
+    assert!(market_state.result == MoneylineMarketOutcome::Void);
+    assert_eq!(market_state.bettor_balance, 210 * USDT);
+    assert_eq!(market_state.locked_liquidity, 0);
+    // The locked liquidity goes back to the house without fees
+    assert_eq!(
+        divvy.token_balance(&divvy.hp_usdt_account.pubkey()).await,
+        1_000 * USDT
+    );
+    assert_eq!(
+        divvy
+            .token_balance(&divvy.insurance_fund_usdt_account.pubkey())
+            .await,
+        0
+    );
+    assert_eq!(
+        divvy
+            .token_balance(&divvy.divvy_foundation_proceeds_usdt_account.pubkey())
+            .await,
+        0
+    );
+
+    divvy.settle_bet(&home, &market, &home_bet).await.unwrap();
+    divvy.settle_bet(&away, &market, &away_bet).await.unwrap();
+    assert_eq!(
+        divvy.token_balance(&home.usdt_account.pubkey()).await,
+        110 * USDT
+    );
+    assert_eq!(
+        divvy.token_balance(&away.usdt_account.pubkey()).await,
+        100 * USDT
+    );
+    let market_state: Market = divvy.unpack(&market).await;
+    assert_eq!(market_state.bettor_balance, 0);
+    assert_eq!(market_state.pending_bets, 0);
+    for market_side in market_state.market_sides.iter() {
+        assert_eq!(market_side.risk, 0);
+        assert_eq!(market_side.payout, 0);
+    }
+    assert_eq!(
+        divvy.token_balance(&divvy.bet_usdt_account.pubkey()).await,
+        0
+    );
+}
+
+#[tokio::test]
+async fn test_void_spread_market() {
+    let program_id = Pubkey::new_unique();
+    let mut program_test = program_test(&program_id);
+    let home_odds_feed = add_aggregator(&mut program_test, -110.0);
+    let away_odds_feed = add_aggregator(&mut program_test, -110.0);
+    let home_points_feed = add_aggregator(&mut program_test, -3.5);
+    let away_points_feed = add_aggregator(&mut program_test, 3.5);
+    let result_feed = add_aggregator(&mut program_test, VOID_RESULT);
+    let mut divvy = funded_divvy(program_test, program_id).await;
+
+    let market = divvy
+        .init_market(
+            &result_feed,
+            &[&home_odds_feed, &away_odds_feed],
+            &[&home_points_feed, &away_points_feed],
+            BetType::Spread,
+            OracleSource::SwitchboardV1,
+        )
+        .await
+        .unwrap();
+    let home = divvy.create_user(110 * USDT).await;
+    let home_bet = divvy
+        .init_bet_with_points_feed(
+            &home,
+            &market,
+            &home_odds_feed,
+            Some(&home_points_feed),
+            110 * USDT,
+            -110,
+            0,
+            0,
+        )
+        .await
+        .unwrap();
+
+    divvy.commence_market(&market).await.unwrap();
+    divvy.settle_market(&market, &result_feed).await.unwrap();
+    let market_state: Market = divvy.unpack(&market).await;
+    assert!(market_state.result == MoneylineMarketOutcome::Void);
+    assert_eq!(market_state.bettor_balance, 110 * USDT);
+    assert_eq!(
+        divvy.token_balance(&divvy.hp_usdt_account.pubkey()).await,
+        1_000 * USDT
+    );
+
+    divvy.settle_bet(&home, &market, &home_bet).await.unwrap();
+    assert_eq!(
+        divvy.token_balance(&home.usdt_account.pubkey()).await,
+        110 * USDT
+    );
+    assert!(!divvy.account_exists(&home_bet).await);
+    let market_state: Market = divvy.unpack(&market).await;
+    assert_eq!(market_state.bettor_balance, 0);
+    assert_eq!(market_state.market_sides[0].risk, 0);
+    assert_eq!(
+        divvy.token_balance(&divvy.bet_usdt_account.pubkey()).await,
+        0
+    );
+}
+
+#[tokio::test]
+async fn test_void_market_before_commence() {
+    let program_id = Pubkey::new_unique();
+    let mut divvy = funded_divvy(program_test(&program_id), program_id).await;
+    let side_0_feed = divvy.create_manual_feed(-110, 0).await.unwrap().pubkey();
+    let side_1_feed = divvy.create_manual_feed(150, 0).await.unwrap().pubkey();
+    let side_2_feed = divvy.create_manual_feed(300, 0).await.unwrap().pubkey();
+    let result_feed = divvy.create_manual_feed(0, 0).await.unwrap().pubkey();
+    let market = divvy
+        .init_market(
+            &result_feed,
+            &[&side_0_feed, &side_1_feed, &side_2_feed],
+            &[],
+            BetType::MoneyLine,
+            OracleSource::Manual,
+        )
+        .await
+        .unwrap();
+    let away = divvy.create_user(100 * USDT).await;
+    let away_bet = divvy
+        .init_bet(&away, &market, &side_1_feed, 100 * USDT, 150, 1, 0)
+        .await
+        .unwrap();
+    let pool_state: BettingPoolState = divvy.unpack(&divvy.bet_pool_state.pubkey()).await;
+    assert_eq!(pool_state.locked_liquidity, 150 * USDT);
+
+    // Only the authority or a settler can void a market
+    let outsider = Keypair::new();
+    let instruction = instruction::void_market(
+        &program_id,
+        &outsider.pubkey(),
+        &market,
+        &divvy.bet_pool_state.pubkey(),
+        &divvy.pda,
+        &divvy.hp_usdt_account.pubkey(),
+        &divvy.bet_usdt_account.pubkey(),
+        &divvy.hp_state.pubkey(),
+        &divvy_house_program_id::ID,
+        divvy.bump_seed,
+        &[],
+    )
+    .unwrap();
+    assert_eq!(
+        divvy
+            .process(&[instruction], &[&outsider])
+            .await
+            .unwrap_err(),
+        exchange_error(ExchangeError::NotValidAuthority)
+    );
+
+    divvy.void_market(&market).await.unwrap();
+    let market_state: Market = divvy.unpack(&market).await;
+    assert!(market_state.result == MoneylineMarketOutcome::Void);
+    assert_eq!(market_state.locked_liquidity, 0);
+    assert_eq!(market_state.bettor_balance, 100 * USDT);
+    let pool_state: BettingPoolState = divvy.unpack(&divvy.bet_pool_state.pubkey()).await;
+    assert_eq!(pool_state.locked_liquidity, 0);
+    assert_eq!(pool_state.live_liquidity, 0);
+    // The liquidity never left the house pool
+    assert_eq!(
+        divvy.token_balance(&divvy.hp_usdt_account.pubkey()).await,
+        1_000 * USDT
+    );
+
+    // A voided market takes no bets and can not be commenced
+    assert_eq!(
+        divvy
+            .init_bet(&away, &market, &side_1_feed, 10 * USDT, 150, 1, 0)
+            .await
+            .unwrap_err(),
+        exchange_error(ExchangeError::MarketCommenced)
+    );
+    assert_eq!(
+        divvy.commence_market(&market).await.unwrap_err(),
+        exchange_error(ExchangeError::MarketCommenced)
+    );
+
+    divvy.settle_bet(&away, &market, &away_bet).await.unwrap();
+    assert_eq!(
+        divvy.token_balance(&away.usdt_account.pubkey()).await,
+        100 * USDT
+    );
+    assert_eq!(
+        divvy.token_balance(&divvy.bet_usdt_account.pubkey()).await,
+        0
+    );
+}
+
+#[tokio::test]
+async fn test_void_market_after_commence() {
+    let program_id = Pubkey::new_unique();
+    let mut divvy = funded_divvy(program_test(&program_id), program_id).await;
+    let side_0_feed = divvy.create_manual_feed(-110, 0).await.unwrap().pubkey();
+    let side_1_feed = divvy.create_manual_feed(150, 0).await.unwrap().pubkey();
+    let side_2_feed = divvy.create_manual_feed(300, 0).await.unwrap().pubkey();
+    let result_feed = divvy.create_manual_feed(0, 0).await.unwrap().pubkey();
+    let market = divvy
+        .init_market(
+            &result_feed,
+            &[&side_0_feed, &side_1_feed, &side_2_feed],
+            &[],
+            BetType::MoneyLine,
+            OracleSource::Manual,
+        )
+        .await
+        .unwrap();
+    let home = divvy.create_user(110 * USDT).await;
+    let away = divvy.create_user(100 * USDT).await;
+    let home_bet = divvy
+        .init_bet(&home, &market, &side_0_feed, 110 * USDT, -110, 0, 0)
+        .await
+        .unwrap();
+    let away_bet = divvy
+        .init_bet(&away, &market, &side_1_feed, 100 * USDT, 150, 1, 0)
+        .await
+        .unwrap();
+    divvy.commence_market(&market).await.unwrap();
+    assert_eq!(
+        divvy.token_balance(&divvy.hp_usdt_account.pubkey()).await,
+        960 * USDT
+    );
+
+    divvy.void_market(&market).await.unwrap();
+    let market_state: Market = divvy.unpack(&market).await;
+    assert!(market_state.result == MoneylineMarketOutcome::Void);
+    let pool_state: BettingPoolState = divvy.unpack(&divvy.bet_pool_state.pubkey()).await;
+    assert_eq!(pool_state.live_liquidity, 0);
+    // The live liquidity goes back to the house without fees
+    assert_eq!(
+        divvy.token_balance(&divvy.hp_usdt_account.pubkey()).await,
+        1_000 * USDT
+    );
+    assert_eq!(
+        divvy
+            .settle_market(&market, &result_feed)
+            .await
+            .unwrap_err(),
+        exchange_error(ExchangeError::MarketAlreadySettled)
+    );
+
+    divvy.settle_bet(&home, &market, &home_bet).await.unwrap();
+    divvy.settle_bet(&away, &market, &away_bet).await.unwrap();
+    assert_eq!(
+        divvy.token_balance(&home.usdt_account.pubkey()).await,
+        110 * USDT
+    );
+    assert_eq!(
+        divvy.token_balance(&away.usdt_account.pubkey()).await,
+        100 * USDT
+    );
+    let market_state: Market = divvy.unpack(&market).await;
+    assert_eq!(market_state.bettor_balance, 0);
+    assert_eq!(
+        divvy.token_balance(&divvy.bet_usdt_account.pubkey()).await,
+        0
+    );
+}
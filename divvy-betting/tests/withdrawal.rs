@@ -0,0 +1,266 @@
+mod common;
+
+use common::{exchange_error, program_test, Divvy, USDT};
+use divvybetting::{
+    error::ExchangeError,
+    schema::divvy_house_program_id,
+    state::{BetType, OracleSource},
+};
+use divvycommon::pda::find_withdrawal_address;
+use divvyhouse::{
+    calculate_released_liquidity, calculate_usdt_amount, instruction,
+    state::{HpLiquidity, WithdrawalRequest},
+};
+use solana_program::pubkey::Pubkey;
+use solana_sdk::signature::{Keypair, Signer};
+use spl_token::state::Mint;
+
+#[tokio::test]
+async fn test_withdrawal_queue() {
+    let program_id = Pubkey::new_unique();
+    let mut divvy = Divvy::start(program_test(&program_id), program_id).await;
+    let hp_state = divvy.hp_state.pubkey();
+    let hp_usdt = divvy.hp_usdt_account.pubkey();
+    let side_0_feed = divvy.create_manual_feed(-110, 0).await.unwrap().pubkey();
+    let side_1_feed = divvy.create_manual_feed(150, 0).await.unwrap().pubkey();
+    let side_2_feed = divvy.create_manual_feed(300, 0).await.unwrap().pubkey();
+    let result_feed = divvy.create_manual_feed(1, 0).await.unwrap().pubkey();
+    let market = divvy
+        .init_market(
+            &result_feed,
+            &[&side_0_feed, &side_1_feed, &side_2_feed],
+            &[],
+            BetType::MoneyLine,
+            OracleSource::Manual,
+        )
+        .await
+        .unwrap();
+    let first_lp = divvy.create_user(600 * USDT).await;
+    let second_lp = divvy.create_user(400 * USDT).await;
+    let bettor = divvy.create_user(300 * USDT).await;
+    divvy.deposit(&first_lp, 600 * USDT).await.unwrap();
+    divvy.deposit(&second_lp, 400 * USDT).await.unwrap();
+    divvy
+        .init_bet(&bettor, &market, &side_1_feed, 300 * USDT, 150, 1, 0)
+        .await
+        .unwrap();
+
+    // The house pool lends the 450 USDT locked on the market
+    divvy.commence_market(&market).await.unwrap();
+    assert_eq!(divvy.token_balance(&hp_usdt).await, 550 * USDT);
+    let hp_state_data: HpLiquidity = divvy.unpack(&hp_state).await;
+    assert_eq!(hp_state_data.committed_liquidity, 450 * USDT);
+
+    // Requests queued during the game escrow their house tokens and hold
+    // back what they are worth now
+    let first = divvy
+        .request_withdrawal(&first_lp, 600 * USDT)
+        .await
+        .unwrap();
+    let second = divvy
+        .request_withdrawal(&second_lp, 100 * USDT)
+        .await
+        .unwrap();
+    assert_eq!((first, second), (0, 1));
+    let (second_request, _) = find_withdrawal_address(&divvy_house_program_id::ID, &hp_state, 1);
+    let request: WithdrawalRequest = divvy.unpack(&second_request).await;
+    assert_eq!(request.user, second_lp.keypair.pubkey());
+    assert_eq!(request.ht_amount, 100 * USDT);
+    let hp_state_data: HpLiquidity = divvy.unpack(&hp_state).await;
+    assert_eq!(hp_state_data.queued_ht, 700 * USDT);
+    assert_eq!(hp_state_data.queued_liquidity, 700 * USDT);
+    assert_eq!(divvy.token_balance(&first_lp.ht_account.pubkey()).await, 0);
+    assert_eq!(
+        divvy
+            .token_balance(&divvy.hp_queue_ht_account.pubkey())
+            .await,
+        700 * USDT
+    );
+
+    // Any USDT account of the requester may be paid
+    let other_usdt_account = Keypair::new();
+    let usdt_mint = divvy.usdt_mint.pubkey();
+    divvy
+        .create_token_account(&other_usdt_account, &usdt_mint, &first_lp.keypair.pubkey())
+        .await;
+    let fill = instruction::fill_withdrawal(
+        &divvy_house_program_id::ID,
+        &first_lp.keypair.pubkey(),
+        &other_usdt_account.pubkey(),
+        &divvy.hp_queue_ht_account.pubkey(),
+        &divvy.ht_mint.pubkey(),
+        &hp_usdt,
+        &hp_state,
+        &divvy.hp_pda,
+        first,
+        divvy.hp_bump_seed,
+    )
+    .unwrap();
+    assert_eq!(
+        divvy.process(&[fill], &[]).await.unwrap_err(),
+        exchange_error(ExchangeError::NotEnoughAvailableLiquidityForWithdrawal)
+    );
+    let out_of_order = instruction::fill_withdrawal(
+        &divvy_house_program_id::ID,
+        &second_lp.keypair.pubkey(),
+        &other_usdt_account.pubkey(),
+        &divvy.hp_queue_ht_account.pubkey(),
+        &divvy.ht_mint.pubkey(),
+        &hp_usdt,
+        &hp_state,
+        &divvy.hp_pda,
+        second,
+        divvy.hp_bump_seed,
+    )
+    .unwrap();
+    assert_eq!(
+        divvy.process(&[out_of_order], &[]).await.unwrap_err(),
+        exchange_error(ExchangeError::InvalidWithdrawalRequest)
+    );
+
+    // The queue is filled in order once the market settles, at the share
+    // price after the 450 USDT the house lost on it
+    divvy.settle_market(&market, &result_feed).await.unwrap();
+    let hp_state_data: HpLiquidity = divvy.unpack(&hp_state).await;
+    assert_eq!(hp_state_data.realized_pnl, -450 * USDT as i64);
+    assert_eq!(divvy.token_balance(&hp_usdt).await, 550 * USDT);
+    divvy.fill_withdrawal(&first_lp).await.unwrap();
+    let first_usdt_amount = calculate_usdt_amount(600 * USDT, 550 * USDT, 1_000 * USDT).unwrap();
+    assert_eq!(
+        divvy.token_balance(&first_lp.usdt_account.pubkey()).await,
+        first_usdt_amount
+    );
+    assert!(first_usdt_amount <= 330 * USDT);
+    let hp_state_data: HpLiquidity = divvy.unpack(&hp_state).await;
+    assert_eq!(hp_state_data.queued_liquidity, 100 * USDT);
+    divvy.fill_withdrawal(&second_lp).await.unwrap();
+    let second_usdt_amount =
+        calculate_usdt_amount(100 * USDT, 550 * USDT - first_usdt_amount, 400 * USDT).unwrap();
+    assert_eq!(
+        divvy.token_balance(&second_lp.usdt_account.pubkey()).await,
+        second_usdt_amount
+    );
+    assert!(second_usdt_amount <= 55 * USDT);
+    assert!(!divvy.account_exists(&second_request).await);
+    let hp_state_data: HpLiquidity = divvy.unpack(&hp_state).await;
+    assert_eq!(hp_state_data.queued_ht, 0);
+    assert_eq!(hp_state_data.queued_liquidity, 0);
+    assert_eq!(hp_state_data.queue_head, 2);
+    assert_eq!(hp_state_data.queue_tail, 2);
+    assert_eq!(hp_state_data.committed_liquidity, 0);
+    let ht_mint: Mint = divvy.unpack(&divvy.ht_mint.pubkey()).await;
+    assert_eq!(ht_mint.supply, 300 * USDT);
+
+    // Queued liquidity takes no new bets
+    let pool_balance = divvy.token_balance(&hp_usdt).await;
+    divvy
+        .request_withdrawal(&second_lp, 300 * USDT)
+        .await
+        .unwrap();
+    let hp_state_data: HpLiquidity = divvy.unpack(&hp_state).await;
+    assert!(pool_balance - hp_state_data.queued_liquidity <= 1);
+    let other_market = divvy
+        .init_market(
+            &result_feed,
+            &[&side_0_feed, &side_1_feed, &side_2_feed],
+            &[],
+            BetType::MoneyLine,
+            OracleSource::Manual,
+        )
+        .await
+        .unwrap();
+    let other_bettor = divvy.create_user(10 * USDT).await;
+    assert_eq!(
+        divvy
+            .init_bet(
+                &other_bettor,
+                &other_market,
+                &side_1_feed,
+                10 * USDT,
+                150,
+                1,
+                0
+            )
+            .await
+            .unwrap_err(),
+        exchange_error(ExchangeError::NotEnoughAvailableLiquidityForBet)
+    );
+}
+
+#[tokio::test]
+async fn test_markets_do_not_borrow_the_queued_liquidity() {
+    let program_id = Pubkey::new_unique();
+    let mut divvy = Divvy::start(program_test(&program_id), program_id).await;
+    let hp_usdt = divvy.hp_usdt_account.pubkey();
+    let side_0_feed = divvy.create_manual_feed(-110, 0).await.unwrap().pubkey();
+    let side_1_feed = divvy.create_manual_feed(150, 0).await.unwrap().pubkey();
+    let side_2_feed = divvy.create_manual_feed(300, 0).await.unwrap().pubkey();
+    let result_feed = divvy.create_manual_feed(0, 0).await.unwrap().pubkey();
+    let market = divvy
+        .init_market(
+            &result_feed,
+            &[&side_0_feed, &side_1_feed, &side_2_feed],
+            &[],
+            BetType::MoneyLine,
+            OracleSource::Manual,
+        )
+        .await
+        .unwrap();
+    let lp = divvy.create_user(1_000 * USDT).await;
+    let bettor = divvy.create_user(300 * USDT).await;
+    divvy.deposit(&lp, 1_000 * USDT).await.unwrap();
+    divvy
+        .init_bet(&bettor, &market, &side_1_feed, 300 * USDT, 150, 1, 0)
+        .await
+        .unwrap();
+
+    // The 450 USDT locked is more than the 400 USDT the queue leaves available
+    divvy.request_withdrawal(&lp, 600 * USDT).await.unwrap();
+    assert_eq!(
+        divvy.commence_market(&market).await.unwrap_err(),
+        exchange_error(ExchangeError::NotEnoughAvailableLiquidityForBet)
+    );
+    assert_eq!(divvy.token_balance(&hp_usdt).await, 1_000 * USDT);
+    divvy.fill_withdrawal(&lp).await.unwrap();
+    assert_eq!(
+        divvy.token_balance(&lp.usdt_account.pubkey()).await,
+        600 * USDT
+    );
+}
+
+#[tokio::test]
+async fn test_zero_withdrawal_request_rejected() {
+    let program_id = Pubkey::new_unique();
+    let mut divvy = Divvy::start(program_test(&program_id), program_id).await;
+    let lp = divvy.create_user(100 * USDT).await;
+    divvy.deposit(&lp, 100 * USDT).await.unwrap();
+
+    assert_eq!(
+        divvy.request_withdrawal(&lp, 0).await.unwrap_err(),
+        exchange_error(ExchangeError::WithdrawalAmountZero)
+    );
+    let hp_state: HpLiquidity = divvy.unpack(&divvy.hp_state.pubkey()).await;
+    assert_eq!(hp_state.queue_tail, 0);
+
+    // The queue still takes and fills requests
+    divvy.request_withdrawal(&lp, 100 * USDT).await.unwrap();
+    divvy.fill_withdrawal(&lp).await.unwrap();
+    assert_eq!(
+        divvy.token_balance(&lp.usdt_account.pubkey()).await,
+        100 * USDT
+    );
+}
+
+#[test]
+fn test_released_liquidity() {
+    assert_eq!(
+        calculate_released_liquidity(150 * USDT, 600 * USDT, 300 * USDT).unwrap(),
+        75 * USDT
+    );
+    // A request for no house tokens releases nothing, from an empty queue too
+    assert_eq!(
+        calculate_released_liquidity(0, 600 * USDT, 300 * USDT).unwrap(),
+        0
+    );
+    assert_eq!(calculate_released_liquidity(0, 0, 0).unwrap(), 0);
+}
@@ -0,0 +1,277 @@
+//! The authority of a pool, a single key or an M-of-N multisig.
+//!
+//! Each program keeps its authority in its pool state. An authority that is
+//! a `Multisig` account owned by the program is satisfied by `m` of its
+//! signers, passed as signing accounts after the instruction's own accounts.
+//!
+//! The authority can grant `Role`s to other keys, which may be multisigs as
+//! well. A role holder can run the admin instructions of its role, the
+//! authority can run all of them.
+
+use solana_program::{
+    account_info::AccountInfo,
+    entrypoint::ProgramResult,
+    program_error::ProgramError,
+    program_pack::{IsInitialized, Pack, Sealed},
+    pubkey::Pubkey,
+    rent::Rent,
+    sysvar::Sysvar,
+};
+
+use arrayref::{array_mut_ref, array_ref, array_refs, mut_array_refs};
+
+use crate::error::ExchangeError;
+
+/// Most signers a multisig can have.
+pub const MAX_SIGNERS: usize = 11;
+/// Most keys that can hold the same role.
+pub const MAX_ROLE_MEMBERS: usize = 4;
+/// Number of roles.
+pub const ROLE_COUNT: usize = 4;
+
+/// The admin duties the authority can hand out.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Role {
+    /// Initializes markets.
+    MarketCreator,
+    /// Commences and voids markets, writes manual feeds and sets the bet cutoff.
+    Settler,
+    /// Freezes and unfreezes the pools and suspends markets.
+    Pauser,
+    /// Changes the fees, the crank reward and the exposure limits.
+    Treasurer,
+}
+
+impl Role {
+    pub fn unpack(input: &u8) -> Result<Self, ProgramError> {
+        Ok(match input {
+            0 => Self::MarketCreator,
+            1 => Self::Settler,
+            2 => Self::Pauser,
+            3 => Self::Treasurer,
+            _ => return Err(ExchangeError::InvalidInstruction.into()),
+        })
+    }
+
+    pub fn pack(&self) -> u8 {
+        match *self {
+            Role::MarketCreator => 0,
+            Role::Settler => 1,
+            Role::Pauser => 2,
+            Role::Treasurer => 3,
+        }
+    }
+}
+
+/// The keys holding each role. Empty slots are the default pubkey.
+#[derive(Debug, PartialEq, Clone, Copy, Default)]
+pub struct Roles {
+    pub members: [[Pubkey; MAX_ROLE_MEMBERS]; ROLE_COUNT],
+}
+
+impl Roles {
+    pub const LEN: usize = 32 * MAX_ROLE_MEMBERS * ROLE_COUNT;
+
+    pub fn has_role(&self, role: Role, key: &Pubkey) -> bool {
+        *key != Pubkey::default() && self.members[role.pack() as usize].contains(key)
+    }
+
+    pub fn grant(&mut self, role: Role, key: &Pubkey) -> Result<(), ExchangeError> {
+        if *key == Pubkey::default() {
+            return Err(ExchangeError::InvalidInstruction);
+        }
+        if self.has_role(role, key) {
+            return Err(ExchangeError::RoleAlreadyGranted);
+        }
+        let slot = self.members[role.pack() as usize]
+            .iter_mut()
+            .find(|member| **member == Pubkey::default())
+            .ok_or(ExchangeError::RoleFull)?;
+        *slot = *key;
+        Ok(())
+    }
+
+    pub fn revoke(&mut self, role: Role, key: &Pubkey) -> Result<(), ExchangeError> {
+        if *key == Pubkey::default() {
+            return Err(ExchangeError::MissingRole);
+        }
+        let slot = self.members[role.pack() as usize]
+            .iter_mut()
+            .find(|member| *member == key)
+            .ok_or(ExchangeError::MissingRole)?;
+        *slot = Pubkey::default();
+        Ok(())
+    }
+
+    pub fn unpack_from_slice(src: &[u8; Roles::LEN]) -> Self {
+        let mut roles = Roles::default();
+        for (member, src) in roles
+            .members
+            .iter_mut()
+            .flat_map(|members| members.iter_mut())
+            .zip(src.chunks(32))
+        {
+            *member = Pubkey::new(src);
+        }
+        roles
+    }
+
+    pub fn pack_into_slice(&self, dst: &mut [u8; Roles::LEN]) {
+        for (dst, member) in dst
+            .chunks_mut(32)
+            .zip(self.members.iter().flat_map(|members| members.iter()))
+        {
+            dst.copy_from_slice(member.as_ref());
+        }
+    }
+}
+
+/// An M-of-N signer set that can act as a pool authority.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct Multisig {
+    /// Number of signers required.
+    pub m: u8,
+    /// Number of valid signers.
+    pub n: u8,
+    pub is_initialized: bool,
+    pub signers: [Pubkey; MAX_SIGNERS],
+}
+
+impl Sealed for Multisig {}
+
+impl IsInitialized for Multisig {
+    fn is_initialized(&self) -> bool {
+        self.is_initialized
+    }
+}
+
+impl Pack for Multisig {
+    const LEN: usize = 3 + 32 * MAX_SIGNERS;
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        let src = array_ref![src, 0, Multisig::LEN];
+        let (m, n, is_initialized, signers_flat) = array_refs![src, 1, 1, 1, 32 * MAX_SIGNERS];
+        let mut signers = [Pubkey::default(); MAX_SIGNERS];
+        for (signer, src) in signers.iter_mut().zip(signers_flat.chunks(32)) {
+            *signer = Pubkey::new(src);
+        }
+        Ok(Multisig {
+            m: m[0],
+            n: n[0],
+            is_initialized: is_initialized[0] != 0,
+            signers,
+        })
+    }
+
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        let dst = array_mut_ref![dst, 0, Multisig::LEN];
+        let (m_dst, n_dst, is_initialized_dst, signers_flat_dst) =
+            mut_array_refs![dst, 1, 1, 1, 32 * MAX_SIGNERS];
+
+        m_dst[0] = self.m;
+        n_dst[0] = self.n;
+        is_initialized_dst[0] = self.is_initialized as u8;
+        for (dst, signer) in signers_flat_dst.chunks_mut(32).zip(self.signers.iter()) {
+            dst.copy_from_slice(signer.as_ref());
+        }
+    }
+}
+
+/// Checks that `authority_info` is the pool `authority` and approved the
+/// instruction. A wallet authority must sign, a multisig authority owned by
+/// `program_id` needs `m` of its signers among `signers`.
+pub fn validate_authority(
+    program_id: &Pubkey,
+    authority: &Pubkey,
+    authority_info: &AccountInfo,
+    signers: &[AccountInfo],
+) -> ProgramResult {
+    if authority_info.key != authority {
+        return Err(ExchangeError::NotValidAuthority.into());
+    }
+    if authority_info.owner != program_id {
+        if !authority_info.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+        return Ok(());
+    }
+
+    let multisig = Multisig::unpack(&authority_info.data.borrow())?;
+    let mut matched = [false; MAX_SIGNERS];
+    let mut num_signers = 0u8;
+    for signer in signers.iter().filter(|signer| signer.is_signer) {
+        // Each key counts once, however often it is passed or listed
+        if let Some(position) = multisig.signers[..multisig.n as usize]
+            .iter()
+            .position(|key| key == signer.key)
+        {
+            if !matched[position] {
+                matched[position] = true;
+                num_signers += 1;
+            }
+        }
+    }
+    if num_signers < multisig.m {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    Ok(())
+}
+
+/// Checks that `signer_info` is the pool `authority` or holds `role`, and
+/// approved the instruction like `validate_authority` checks.
+pub fn validate_role(
+    program_id: &Pubkey,
+    authority: &Pubkey,
+    roles: &Roles,
+    role: Role,
+    signer_info: &AccountInfo,
+    signers: &[AccountInfo],
+) -> ProgramResult {
+    if signer_info.key != authority && !roles.has_role(role, signer_info.key) {
+        return Err(ExchangeError::NotValidAuthority.into());
+    }
+    validate_authority(program_id, signer_info.key, signer_info, signers)
+}
+
+/// Initializes a rent exempt multisig account owned by `program_id` with
+/// `signers` as its signer set, `m` of which must sign. The signers must be
+/// distinct.
+pub fn init_multisig(
+    program_id: &Pubkey,
+    multisig_info: &AccountInfo,
+    signers: &[AccountInfo],
+    m: u8,
+) -> ProgramResult {
+    if multisig_info.owner != program_id {
+        return Err(ExchangeError::InvalidMultisig.into());
+    }
+    if !Rent::get()?.is_exempt(**multisig_info.lamports.borrow(), multisig_info.data_len()) {
+        return Err(ProgramError::AccountNotRentExempt);
+    }
+    let mut multisig = Multisig::unpack_unchecked(&multisig_info.data.borrow())?;
+    if multisig.is_initialized {
+        return Err(ExchangeError::MultisigAlreadyInitialized.into());
+    }
+    if signers.is_empty() || signers.len() > MAX_SIGNERS {
+        return Err(ExchangeError::InvalidMultisig.into());
+    }
+    if m == 0 || m as usize > signers.len() {
+        return Err(ExchangeError::InvalidMultisig.into());
+    }
+    for (position, signer) in signers.iter().enumerate() {
+        if signers[..position]
+            .iter()
+            .any(|other| other.key == signer.key)
+        {
+            return Err(ExchangeError::InvalidMultisig.into());
+        }
+    }
+
+    multisig.m = m;
+    multisig.n = signers.len() as u8;
+    multisig.is_initialized = true;
+    for (position, signer) in signers.iter().enumerate() {
+        multisig.signers[position] = *signer.key;
+    }
+    Multisig::pack(multisig, &mut multisig_info.data.borrow_mut())?;
+    Ok(())
+}
@@ -0,0 +1,353 @@
+use num_derive::FromPrimitive as DeriveFromPrimitive;
+use num_traits::FromPrimitive as TraitsFromPrimitive;
+use solana_program::{
+    decode_error::DecodeError,
+    msg,
+    program_error::{PrintProgramError, ProgramError},
+};
+use thiserror::Error;
+
+#[derive(Error, Debug, Copy, Clone, DeriveFromPrimitive, PartialEq, Eq)]
+pub enum ExchangeError {
+    /// Invalid instruction
+    #[error("Invalid Instruction")]
+    InvalidInstruction,
+    /// Not Valid Authority
+    #[error("Not Valid Authority")]
+    NotValidAuthority,
+    /// Expected Amount Mismatch
+    #[error("Expected Amount Mismatch")]
+    ExpectedAmountMismatch,
+    /// Payout Zero
+    #[error("Payout came out as Zero, please  debug")]
+    PayoutZero,
+    /// Expected Data Mismatch
+    #[error("Expected Data Mismatch")]
+    ExpectedDataMismatch,
+    /// Amount Overflow
+    #[error("Amount Overflow")]
+    AmountOverflow,
+    /// Invalid feed account
+    #[error("Invalid feed account")]
+    InvalidFeedAccount,
+    #[error("Invalid house token mint account")]
+    InvalidHtMintAccount,
+    #[error("Invalid house pool USDT account")]
+    InvalidPoolUsdtAccount,
+    #[error("Invalid withdrawal queue HT account")]
+    InvalidQueueHtAccount,
+    #[error("Invalid house pool state account")]
+    InvalidHousePoolStateAccount,
+    #[error("Invalid market ledger account")]
+    InvalidLedgerAccount,
+    #[error("Invalid betting pool USDT account")]
+    InvalidBettingPoolUsdtAccount,
+    #[error("Invalid betting pool state account")]
+    InvalidBettingPoolStateAccount,
+    #[error("Invalid betting program account")]
+    InvalidBettingProgramAccount,
+    #[error("Invalid betting program PDA")]
+    InvalidBettingPda,
+    #[error("Invalid market account")]
+    InvalidMarketAccount,
+    #[error("Invalid bet account")]
+    InvalidBetAccount,
+    #[error("Invalid position account")]
+    InvalidPositionAccount,
+    #[error("Invalid insurance fund USDT account")]
+    InvalidInsuranceFundUsdtAccount,
+    #[error("Invalid divvy foundation USDT account")]
+    InvalidDivvyFoundationUsdtAccount,
+
+    // Deposit withdraw errors
+    #[error("Not enough available liquidity for withdrawal")]
+    NotEnoughAvailableLiquidityForWithdrawal,
+    #[error("Can not use the house pool when there are bets placed on live games")]
+    GamesAreLive,
+    #[error("Pool is frozen")]
+    PoolFrozen,
+    #[error("Invalid withdrawal request")]
+    InvalidWithdrawalRequest,
+    #[error("Withdrawal amount is zero")]
+    WithdrawalAmountZero,
+
+    // Betting errors
+    #[error("Betting is frozen")]
+    BettingFrozen,
+    #[error("Betting on the market has closed")]
+    BettingClosed,
+    #[error("Market is suspended")]
+    MarketSuspended,
+
+    // Already settled errors
+    #[error("Market already settled")]
+    MarketAlreadySettled,
+    #[error("Market not settled")]
+    MarketNotSettled,
+    #[error("Bet already settled")]
+    BetAlreadySettled,
+
+    // Betting init errors
+    #[error("Not enough available liquidity for bet")]
+    NotEnoughAvailableLiquidityForBet,
+    #[error("Bet risk is zero")]
+    BetRiskZero,
+    #[error("Feed odds are worse than the quoted odds beyond the tolerance")]
+    OddsSlippageExceeded,
+    #[error("Odds tolerance is above 10000 basis points")]
+    InvalidOddsTolerance,
+    #[error("Odds are not valid American odds")]
+    InvalidOdds,
+    #[error("Points feed value is not a valid line")]
+    InvalidPoints,
+    #[error("Bet risk is below the minimum")]
+    BetRiskTooLow,
+    #[error("Bet risk is above the maximum")]
+    BetRiskTooHigh,
+    #[error("Market side payout is above its limit")]
+    SidePayoutLimitExceeded,
+    #[error("Market locked liquidity is above its share of the pool")]
+    MarketExposureLimitExceeded,
+    #[error("Wallet risk on the market is above its limit")]
+    WalletRiskLimitExceeded,
+    #[error("Wallet payout on the market side is above its limit")]
+    WalletPayoutLimitExceeded,
+
+    // Market settlement errors
+    #[error("Feed result not valid when settling market")]
+    NotValidMarketResult,
+
+    // Fee errors
+    #[error("Fee is above its upper bound")]
+    FeeTooHigh,
+    #[error("Crank reward is above the bet account rent")]
+    CrankRewardTooHigh,
+    #[error("Exposure limits are not valid")]
+    InvalidExposureLimits,
+
+    // Authority errors
+    #[error("No authority has been proposed")]
+    NoPendingAuthority,
+    #[error("Multisig needs 1 to 11 signers and at most as many required signers")]
+    InvalidMultisig,
+    #[error("Key does not hold the role")]
+    MissingRole,
+    #[error("Key already holds the role")]
+    RoleAlreadyGranted,
+    #[error("Role already has the most members")]
+    RoleFull,
+
+    // Oracle errors
+    #[error("Feed round is older than the market max staleness")]
+    StaleFeed,
+    #[error("Feed round has fewer responses than the market requires")]
+    NotEnoughFeedResponses,
+
+    // Market commence errors
+    #[error("Market has already commenced")]
+    MarketCommenced,
+
+    // Initialized errors
+    #[error("HP liquidity not initialized")]
+    HpLiquidityNotInitialized,
+    #[error("HP liquidity already initialized")]
+    HpLiquidityAlreadyInitialized,
+    #[error("Betting pool state not initialized")]
+    BettingPoolStateNotInitialized,
+    #[error("Betting pool state already initialized")]
+    BettingPoolStateAlreadyInitialized,
+    #[error("Market not initialized")]
+    MarketNotInitialized,
+    #[error("Market already initialized")]
+    MarketAlreadyInitialized,
+    #[error("Bet already initialized")]
+    BetAlreadyInitialized,
+    #[error("Feed not initialized")]
+    FeedNotInitialized,
+    #[error("Multisig already initialized")]
+    MultisigAlreadyInitialized,
+
+    // Assertion errors
+    #[error("Market side risk underflow.")]
+    MarketSideRiskUnderflow,
+    #[error("Market side payout underflow.")]
+    MarketSidePayoutUnderflow,
+    #[error("All bets in market settled and market side risk is positive.")]
+    MarketSideRiskRemaining,
+    #[error("All bets in market settled and market side payout is positive.")]
+    MarketSidePayoutRemaining,
+    #[error("All bets in market settled and market bettor balance is positive.")]
+    MarketBettorBalanceRemaining,
+    #[error("All bets settled and house pool bettor balance is positive.")]
+    HousePoolBettorBalanceRemaining,
+    #[error("All bets settled and the locked liquidity in the house pool is positive.")]
+    HousePoolLockedLiquidityRemaining,
+    #[error("All bets settled and the live liquidity in the house pool is positive.")]
+    HousePoolLiveLiquidityRemaining,
+}
+
+impl PrintProgramError for ExchangeError {
+    fn print<E>(&self)
+    where
+        E: 'static + std::error::Error + DecodeError<E> + PrintProgramError + TraitsFromPrimitive,
+    {
+        match self {
+            ExchangeError::InvalidInstruction => msg!("Invalid Instruction"),
+            ExchangeError::NotValidAuthority => msg!("Not Valid Authority"),
+            ExchangeError::ExpectedAmountMismatch => msg!("Expected Amount Mismatch"),
+            ExchangeError::PayoutZero => msg!("Payout cameout as Zero"),
+            ExchangeError::ExpectedDataMismatch => msg!("Expected Data Mismatch"),
+            ExchangeError::AmountOverflow => msg!("Amount Overflow"),
+            ExchangeError::InvalidFeedAccount => msg!("Invalid feed account"),
+            ExchangeError::InvalidHtMintAccount => msg!("Invalid house token mint account"),
+            ExchangeError::InvalidBettingPoolUsdtAccount => {
+                msg!("Invalid betting pool USDT account")
+            }
+            ExchangeError::InvalidBettingPoolStateAccount => {
+                msg!("Invalid betting pool state account")
+            }
+            ExchangeError::InvalidBettingProgramAccount => msg!("Invalid betting program account"),
+            ExchangeError::InvalidBettingPda => msg!("Invalid betting program PDA"),
+            ExchangeError::InvalidPoolUsdtAccount => msg!("Invalid house pool USDT account"),
+            ExchangeError::InvalidQueueHtAccount => msg!("Invalid withdrawal queue HT account"),
+            ExchangeError::InvalidHousePoolStateAccount => msg!("Invalid house pool state account"),
+            ExchangeError::InvalidLedgerAccount => msg!("Invalid market ledger account"),
+            ExchangeError::InvalidMarketAccount => msg!("Invalid market account"),
+            ExchangeError::InvalidBetAccount => msg!("Invalid bet account"),
+            ExchangeError::InvalidPositionAccount => msg!("Invalid position account"),
+            ExchangeError::InvalidInsuranceFundUsdtAccount => {
+                msg!("Invalid insurance fund USDT account")
+            }
+            ExchangeError::InvalidDivvyFoundationUsdtAccount => {
+                msg!("Invalid divvy foundation USDT account")
+            }
+
+            // Deposit withdraw errors
+            ExchangeError::NotEnoughAvailableLiquidityForWithdrawal => {
+                msg!("Not enough available liquidity for withdrawal")
+            }
+            ExchangeError::GamesAreLive => {
+                msg!("Can not use the house pool when there are bets placed on live games")
+            }
+            ExchangeError::PoolFrozen => msg!("Pool is frozen"),
+            ExchangeError::InvalidWithdrawalRequest => {
+                msg!("Invalid withdrawal request")
+            }
+            ExchangeError::WithdrawalAmountZero => msg!("Withdrawal amount is zero"),
+
+            // Betting errors
+            ExchangeError::BettingFrozen => msg!("Betting is frozen"),
+            ExchangeError::BettingClosed => msg!("Betting on the market has closed"),
+            ExchangeError::MarketSuspended => msg!("Market is suspended"),
+
+            // Settled errors
+            ExchangeError::MarketAlreadySettled => msg!("Market already settled"),
+            ExchangeError::MarketNotSettled => msg!("Market not settled"),
+            ExchangeError::BetAlreadySettled => msg!("Bet already settled"),
+
+            // Betting init errors
+            ExchangeError::NotEnoughAvailableLiquidityForBet => {
+                msg!("Not enough available liquidity for bet")
+            }
+            ExchangeError::BetRiskZero => msg!("Bet risk is zero"),
+            ExchangeError::OddsSlippageExceeded => {
+                msg!("Feed odds are worse than the quoted odds beyond the tolerance")
+            }
+            ExchangeError::InvalidOddsTolerance => {
+                msg!("Odds tolerance is above 10000 basis points")
+            }
+            ExchangeError::InvalidOdds => msg!("Odds are not valid American odds"),
+            ExchangeError::InvalidPoints => msg!("Points feed value is not a valid line"),
+            ExchangeError::BetRiskTooLow => msg!("Bet risk is below the minimum"),
+            ExchangeError::BetRiskTooHigh => msg!("Bet risk is above the maximum"),
+            ExchangeError::SidePayoutLimitExceeded => {
+                msg!("Market side payout is above its limit")
+            }
+            ExchangeError::MarketExposureLimitExceeded => {
+                msg!("Market locked liquidity is above its share of the pool")
+            }
+            ExchangeError::WalletRiskLimitExceeded => {
+                msg!("Wallet risk on the market is above its limit")
+            }
+            ExchangeError::WalletPayoutLimitExceeded => {
+                msg!("Wallet payout on the market side is above its limit")
+            }
+
+            // Market settlement errors
+            ExchangeError::NotValidMarketResult => {
+                msg!("Feed result not valid when settling market")
+            }
+            ExchangeError::FeeTooHigh => msg!("Fee is above its upper bound"),
+            ExchangeError::CrankRewardTooHigh => msg!("Crank reward is above the bet account rent"),
+            ExchangeError::InvalidExposureLimits => msg!("Exposure limits are not valid"),
+            ExchangeError::NoPendingAuthority => msg!("No authority has been proposed"),
+            ExchangeError::InvalidMultisig => {
+                msg!("Multisig needs 1 to 11 signers and at most as many required signers")
+            }
+            ExchangeError::MissingRole => msg!("Key does not hold the role"),
+            ExchangeError::RoleAlreadyGranted => msg!("Key already holds the role"),
+            ExchangeError::RoleFull => msg!("Role already has the most members"),
+
+            // Oracle errors
+            ExchangeError::StaleFeed => msg!("Feed round is older than the market max staleness"),
+            ExchangeError::NotEnoughFeedResponses => {
+                msg!("Feed round has fewer responses than the market requires")
+            }
+
+            // Market commence errors
+            ExchangeError::MarketCommenced => msg!("Market has already commenced"),
+
+            // Initialized errors
+            ExchangeError::HpLiquidityNotInitialized => {
+                msg!("HP liquidity not initialized");
+            }
+            ExchangeError::HpLiquidityAlreadyInitialized => {
+                msg!("HP liquidity already initialized")
+            }
+            ExchangeError::BettingPoolStateNotInitialized => {
+                msg!("Betting pool state not initialized");
+            }
+            ExchangeError::BettingPoolStateAlreadyInitialized => {
+                msg!("Betting pool state already initialized")
+            }
+            ExchangeError::MarketNotInitialized => msg!("Market not initialized"),
+            ExchangeError::MarketAlreadyInitialized => msg!("Market already initialized"),
+            ExchangeError::BetAlreadyInitialized => msg!("Bet already initialized"),
+            ExchangeError::FeedNotInitialized => msg!("Feed not initialized"),
+            ExchangeError::MultisigAlreadyInitialized => msg!("Multisig already initialized"),
+
+            // Assertion errors
+            ExchangeError::MarketSideRiskUnderflow => msg!("Market side risk underflow."),
+            ExchangeError::MarketSidePayoutUnderflow => msg!("Market side payout underflow."),
+            ExchangeError::MarketSideRiskRemaining => {
+                msg!("All bets in market settled and market side risk is positive.")
+            }
+            ExchangeError::MarketSidePayoutRemaining => {
+                msg!("All bets in market settled and market side payout is positive.")
+            }
+            ExchangeError::MarketBettorBalanceRemaining => {
+                msg!("All bets in market settled and market bettor balance is positive.")
+            }
+            ExchangeError::HousePoolBettorBalanceRemaining => {
+                msg!("All bets settled and house pool bettor balance is positive.")
+            }
+            ExchangeError::HousePoolLockedLiquidityRemaining => {
+                msg!("The balance in the house pool does not equal available liquidity.")
+            }
+            ExchangeError::HousePoolLiveLiquidityRemaining => {
+                msg!("All bets settled and the live liquidity in the house pool is positive.")
+            }
+        }
+    }
+}
+
+impl From<ExchangeError> for ProgramError {
+    fn from(e: ExchangeError) -> Self {
+        ProgramError::Custom(e as u32)
+    }
+}
+impl<T> DecodeError<T> for ExchangeError {
+    fn type_of() -> &'static str {
+        "ExchangeError"
+    }
+}
@@ -0,0 +1,64 @@
+//! How the house profit of a settled market is split.
+//!
+//! The insurance fund and the Divvy foundation take their fees in basis
+//! points of the house profit and the rest goes back to the house pool.
+
+use crate::error::ExchangeError;
+
+/// Basis points in a whole.
+pub const BPS: u64 = 10_000;
+/// Insurance fund fee of a newly initialized pool.
+pub const DEFAULT_INSURANCE_FUND_FEE_BPS: u16 = 100;
+/// Divvy foundation fee of a newly initialized pool.
+pub const DEFAULT_DIVVY_FOUNDATION_FEE_BPS: u16 = 500;
+/// Highest insurance fund fee the authority can set.
+pub const MAX_INSURANCE_FUND_FEE_BPS: u16 = 1_000;
+/// Highest Divvy foundation fee the authority can set.
+pub const MAX_DIVVY_FOUNDATION_FEE_BPS: u16 = 2_000;
+
+/// The shares of a house profit.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct FeeSplit {
+    pub insurance_fund_fee: u64,
+    pub divvy_foundation_fee: u64,
+    pub house_share: u64,
+}
+
+/// Rejects fees above their upper bounds.
+pub fn check_fees(
+    insurance_fund_fee_bps: u16,
+    divvy_foundation_fee_bps: u16,
+) -> Result<(), ExchangeError> {
+    if insurance_fund_fee_bps > MAX_INSURANCE_FUND_FEE_BPS
+        || divvy_foundation_fee_bps > MAX_DIVVY_FOUNDATION_FEE_BPS
+    {
+        return Err(ExchangeError::FeeTooHigh);
+    }
+    Ok(())
+}
+
+/// Takes `fee_bps` basis points of `amount`, rounded down.
+pub fn fee(amount: u64, fee_bps: u16) -> u64 {
+    (amount as u128 * fee_bps as u128 / BPS as u128) as u64
+}
+
+/// Splits a house profit into the fees and the house share. The fees are
+/// rounded down, so the house share keeps the rounding.
+pub fn split_house_profit(
+    house_profit: u64,
+    insurance_fund_fee_bps: u16,
+    divvy_foundation_fee_bps: u16,
+) -> Result<FeeSplit, ExchangeError> {
+    check_fees(insurance_fund_fee_bps, divvy_foundation_fee_bps)?;
+    let insurance_fund_fee = fee(house_profit, insurance_fund_fee_bps);
+    let divvy_foundation_fee = fee(house_profit, divvy_foundation_fee_bps);
+    let house_share = house_profit
+        .checked_sub(insurance_fund_fee)
+        .and_then(|rest| rest.checked_sub(divvy_foundation_fee))
+        .ok_or(ExchangeError::AmountOverflow)?;
+    Ok(FeeSplit {
+        insurance_fund_fee,
+        divvy_foundation_fee,
+        house_share,
+    })
+}
@@ -0,0 +1,195 @@
+use error::ExchangeError;
+use points::{grade, grade_margin, margins_around, Grade};
+use solana_program::{program_error::ProgramError, pubkey::Pubkey};
+use state::{BetType, Market};
+use std::convert::TryFrom;
+
+pub mod authority;
+pub mod error;
+pub mod fees;
+pub mod limits;
+pub mod odds;
+pub mod oracle;
+pub mod pda;
+pub mod points;
+pub mod state;
+
+pub fn calculate_min_payout(
+    quoted_payout: u64,
+    odds_tolerance_bps: u16,
+) -> Result<u64, ExchangeError> {
+    //Lowest payout the bettor accepts, rounded up so the tolerance is never exceeded
+    let accepted_bps = 10_000u128.saturating_sub(odds_tolerance_bps as u128);
+    let min_payout = (quoted_payout as u128)
+        .checked_mul(accepted_bps)
+        .ok_or(ExchangeError::AmountOverflow)?
+        .checked_add(9_999)
+        .ok_or(ExchangeError::AmountOverflow)?
+        / 10_000;
+    return Ok(min_payout as u64);
+}
+
+pub fn calculate_locked_liquidity(market_state: &Market) -> Result<u64, ExchangeError> {
+    if market_state.bet_type != BetType::MoneyLine {
+        return calculate_points_locked_liquidity(market_state);
+    }
+    //Calculating max loss
+    let mut locked_side_0 = 0u64;
+    let mut locked_side_1 = 0u64;
+    let mut locked_side_2 = 0u64;
+
+    if market_state.market_sides[0].payout
+        > market_state.market_sides[1]
+            .risk
+            .checked_add(market_state.market_sides[2].risk)
+            .ok_or(ExchangeError::AmountOverflow)?
+    {
+        locked_side_0 = market_state.market_sides[0]
+            .payout
+            .checked_sub(market_state.market_sides[1].risk)
+            .ok_or(ExchangeError::AmountOverflow)?
+            .checked_sub(market_state.market_sides[2].risk)
+            .ok_or(ExchangeError::AmountOverflow)?;
+    };
+    if market_state.market_sides[1].payout
+        > market_state.market_sides[0]
+            .risk
+            .checked_add(market_state.market_sides[2].risk)
+            .ok_or(ExchangeError::AmountOverflow)?
+    {
+        locked_side_1 = market_state.market_sides[1]
+            .payout
+            .checked_sub(market_state.market_sides[0].risk)
+            .ok_or(ExchangeError::AmountOverflow)?
+            .checked_sub(market_state.market_sides[2].risk)
+            .ok_or(ExchangeError::AmountOverflow)?;
+    };
+
+    if market_state.market_sides[2].payout
+        > market_state.market_sides[0]
+            .risk
+            .checked_add(market_state.market_sides[1].risk)
+            .ok_or(ExchangeError::AmountOverflow)?
+    {
+        locked_side_2 = market_state.market_sides[2]
+            .payout
+            .checked_sub(market_state.market_sides[0].risk)
+            .ok_or(ExchangeError::AmountOverflow)?
+            .checked_sub(market_state.market_sides[1].risk)
+            .ok_or(ExchangeError::AmountOverflow)?;
+    };
+
+    let locked_liquidity = *[locked_side_0, locked_side_1, locked_side_2]
+        .iter()
+        .max()
+        .ok_or(ExchangeError::InvalidInstruction)?;
+
+    return Ok(locked_liquidity);
+}
+
+/// The most a spread or total market can lose at any final score, taking every
+/// bet on a side to be at the side's most generous line. Exact when the bets on
+/// a side share a line and an upper bound otherwise, as mixed lines can only
+/// make fewer bets win.
+pub fn calculate_points_locked_liquidity(market_state: &Market) -> Result<u64, ExchangeError> {
+    let bet_type = market_state.bet_type;
+    let sides = &market_state.market_sides[..2];
+    let mut locked_liquidity = 0u64;
+    //The loss only changes where a side's line is crossed, so checking the
+    //margins around every line covers every final score
+    for (threshold_side, threshold_side_state) in sides.iter().enumerate() {
+        for margin in
+            margins_around(bet_type, threshold_side as u8, threshold_side_state.line).iter()
+        {
+            let mut house_loss = 0i128;
+            for (market_side, market_side_state) in sides.iter().enumerate() {
+                house_loss += match grade_margin(
+                    bet_type,
+                    market_side as u8,
+                    market_side_state.line,
+                    *margin,
+                ) {
+                    Grade::Win => market_side_state.payout as i128,
+                    Grade::Push => 0,
+                    Grade::Loss => -(market_side_state.risk as i128),
+                };
+            }
+            if house_loss > locked_liquidity as i128 {
+                locked_liquidity =
+                    u64::try_from(house_loss).map_err(|_| ExchangeError::AmountOverflow)?;
+            }
+        }
+    }
+    return Ok(locked_liquidity);
+}
+
+/// What a spread or total market owes its bettors at the final score: risk and
+/// payout of winning sides and risk of pushed sides, graded at each side's most
+/// generous line.
+pub fn calculate_points_bettor_balance(
+    market_state: &Market,
+    home: u32,
+    away: u32,
+) -> Result<u64, ExchangeError> {
+    let mut bettor_balance = 0u64;
+    for (market_side, market_side_state) in market_state.market_sides[..2].iter().enumerate() {
+        let owed = grade(
+            market_state.bet_type,
+            market_side as u8,
+            market_side_state.line,
+            home,
+            away,
+        )
+        .balance(market_side_state.risk, market_side_state.payout)
+        .ok_or(ExchangeError::AmountOverflow)?;
+        bettor_balance = bettor_balance
+            .checked_add(owed)
+            .ok_or(ExchangeError::AmountOverflow)?;
+    }
+    return Ok(bettor_balance);
+}
+
+pub fn unpack_pubkey_option(input: &[u8]) -> Result<(Option<Pubkey>, &[u8]), ProgramError> {
+    match input.split_first() {
+        Option::Some((&0, rest)) => Ok((Option::None, rest)),
+        Option::Some((&1, rest)) if rest.len() >= 32 => {
+            let (key, rest) = rest.split_at(32);
+            let pubkey = Pubkey::new(key);
+            Ok((Option::Some(pubkey), rest))
+        }
+        _ => Err(ExchangeError::InvalidInstruction.into()),
+    }
+}
+
+/// Pads `text` with zeros into a fixed size field like a market side label.
+/// Fails if the text does not fit.
+pub fn pack_text<T: Default + AsMut<[u8]>>(text: &str) -> Result<T, ExchangeError> {
+    let mut packed = T::default();
+    let dst = packed.as_mut();
+    if text.len() > dst.len() {
+        return Err(ExchangeError::InvalidInstruction);
+    }
+    dst[..text.len()].copy_from_slice(text.as_bytes());
+    Ok(packed)
+}
+
+/// The text of a zero padded field written by `pack_text`, `None` unless it
+/// is UTF-8.
+pub fn unpack_text(packed: &[u8]) -> Option<&str> {
+    let len = packed
+        .iter()
+        .rposition(|byte| *byte != 0)
+        .map_or(0, |last| last + 1);
+    std::str::from_utf8(&packed[..len]).ok()
+}
+
+pub fn pack_pubkey_option(value: &Option<Pubkey>, dst: &mut [u8; 33]) {
+    match *value {
+        Option::Some(ref key) => {
+            let (some, rest) = dst.split_at_mut(1);
+            some[0] = 1;
+            rest.copy_from_slice(key.as_ref());
+        }
+        Option::None => dst.copy_from_slice(&[0; 33]),
+    }
+}
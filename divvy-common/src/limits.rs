@@ -0,0 +1,129 @@
+//! Caps on the exposure bets put on the house pool.
+//!
+//! A market's locked liquidity is capped in basis points of the pool size,
+//! a market side's total payout, a bet's risk and a wallet's position on a
+//! market in USDT. A zero cap is no cap, so a newly initialized pool takes
+//! any bet it has liquidity for.
+
+use arrayref::{array_refs, mut_array_refs};
+
+use crate::{error::ExchangeError, fees::BPS, state::Position};
+
+/// The exposure limits of a pool.
+#[derive(Debug, PartialEq, Clone, Copy, Default)]
+pub struct ExposureLimits {
+    /// Highest locked liquidity of a market, in basis points of the pool size.
+    pub max_market_exposure_bps: u16,
+    /// Highest total payout of a market side.
+    pub max_side_payout: u64,
+    /// Lowest risk of a bet.
+    pub min_bet_risk: u64,
+    /// Highest risk of a bet.
+    pub max_bet_risk: u64,
+    /// Highest total risk of a wallet's bets on a market.
+    pub max_wallet_risk: u64,
+    /// Highest total payout of a wallet's bets on a market side.
+    pub max_wallet_payout: u64,
+}
+
+impl ExposureLimits {
+    pub const LEN: usize = 42;
+
+    /// Rejects a market cap above the whole pool and a minimum risk above
+    /// the maximum.
+    pub fn check(&self) -> Result<(), ExchangeError> {
+        if self.max_market_exposure_bps as u64 > BPS
+            || (self.max_bet_risk != 0 && self.min_bet_risk > self.max_bet_risk)
+        {
+            return Err(ExchangeError::InvalidExposureLimits);
+        }
+        Ok(())
+    }
+
+    pub fn check_bet_risk(&self, risk: u64) -> Result<(), ExchangeError> {
+        if risk < self.min_bet_risk {
+            return Err(ExchangeError::BetRiskTooLow);
+        }
+        if self.max_bet_risk != 0 && risk > self.max_bet_risk {
+            return Err(ExchangeError::BetRiskTooHigh);
+        }
+        Ok(())
+    }
+
+    pub fn check_side_payout(&self, side_payout: u64) -> Result<(), ExchangeError> {
+        if self.max_side_payout != 0 && side_payout > self.max_side_payout {
+            return Err(ExchangeError::SidePayoutLimitExceeded);
+        }
+        Ok(())
+    }
+
+    /// Checks a wallet's position once a bet on `market_side` is added to it.
+    pub fn check_position(
+        &self,
+        position: &Position,
+        market_side: u8,
+    ) -> Result<(), ExchangeError> {
+        if self.max_wallet_risk != 0 && position.total_risk()? > self.max_wallet_risk {
+            return Err(ExchangeError::WalletRiskLimitExceeded);
+        }
+        if self.max_wallet_payout != 0
+            && position.payout[market_side as usize] > self.max_wallet_payout
+        {
+            return Err(ExchangeError::WalletPayoutLimitExceeded);
+        }
+        Ok(())
+    }
+
+    /// Checks a market's locked liquidity against its share of `pool_size`.
+    pub fn check_market_exposure(
+        &self,
+        locked_liquidity: u64,
+        pool_size: u64,
+    ) -> Result<(), ExchangeError> {
+        if self.max_market_exposure_bps == 0 {
+            return Ok(());
+        }
+        let max_market_exposure =
+            pool_size as u128 * self.max_market_exposure_bps as u128 / BPS as u128;
+        if locked_liquidity as u128 > max_market_exposure {
+            return Err(ExchangeError::MarketExposureLimitExceeded);
+        }
+        Ok(())
+    }
+
+    pub fn unpack_from_slice(src: &[u8; ExposureLimits::LEN]) -> Self {
+        let (
+            max_market_exposure_bps,
+            max_side_payout,
+            min_bet_risk,
+            max_bet_risk,
+            max_wallet_risk,
+            max_wallet_payout,
+        ) = array_refs![src, 2, 8, 8, 8, 8, 8];
+        ExposureLimits {
+            max_market_exposure_bps: u16::from_le_bytes(*max_market_exposure_bps),
+            max_side_payout: u64::from_le_bytes(*max_side_payout),
+            min_bet_risk: u64::from_le_bytes(*min_bet_risk),
+            max_bet_risk: u64::from_le_bytes(*max_bet_risk),
+            max_wallet_risk: u64::from_le_bytes(*max_wallet_risk),
+            max_wallet_payout: u64::from_le_bytes(*max_wallet_payout),
+        }
+    }
+
+    pub fn pack_into_slice(&self, dst: &mut [u8; ExposureLimits::LEN]) {
+        let (
+            max_market_exposure_bps_dst,
+            max_side_payout_dst,
+            min_bet_risk_dst,
+            max_bet_risk_dst,
+            max_wallet_risk_dst,
+            max_wallet_payout_dst,
+        ) = mut_array_refs![dst, 2, 8, 8, 8, 8, 8];
+        *max_market_exposure_bps_dst = self.max_market_exposure_bps.to_le_bytes();
+        *max_side_payout_dst = self.max_side_payout.to_le_bytes();
+        *min_bet_risk_dst = self.min_bet_risk.to_le_bytes();
+        *max_bet_risk_dst = self.max_bet_risk.to_le_bytes();
+        *max_wallet_risk_dst = self.max_wallet_risk.to_le_bytes();
+        *max_wallet_payout_dst = self.max_wallet_payout.to_le_bytes();
+    }
+}
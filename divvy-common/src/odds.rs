@@ -0,0 +1,99 @@
+//! Odds conversions and payout math.
+//!
+//! A payout is what a winning bet is paid on top of its risk. Every step
+//! rounds down, so any rounding error is kept by the house and never paid
+//! out to the bettor.
+
+use fixed::types::U64F64;
+
+/// Smallest absolute value valid American odds can take.
+pub const MIN_AMERICAN_ODDS: u64 = 100;
+
+/// Payout of a bet at American odds. Positive odds pay `odds` per 100 risked,
+/// negative odds pay 100 per `-odds` risked. Odds between -100 and 100 are
+/// invalid.
+pub fn payout_from_american(odds: i64, risk: u64) -> Option<u64> {
+    let abs_odds = odds.checked_abs()? as u64;
+    if abs_odds < MIN_AMERICAN_ODDS {
+        return None;
+    }
+    let payout = if odds > 0 {
+        (risk as u128).checked_mul(abs_odds as u128)? / MIN_AMERICAN_ODDS as u128
+    } else {
+        (risk as u128).checked_mul(MIN_AMERICAN_ODDS as u128)? / abs_odds as u128
+    };
+    if payout > u64::MAX as u128 {
+        return None;
+    }
+    Some(payout as u64)
+}
+
+/// Payout of a bet at decimal odds, which include the returned risk. Decimal
+/// odds below 1 are invalid.
+pub fn payout_from_decimal(decimal_odds: U64F64, risk: u64) -> Option<u64> {
+    let winnings_per_unit = decimal_odds.checked_sub(U64F64::from_num(1))?;
+    winnings_per_unit
+        .checked_mul(U64F64::checked_from_num(risk)?)?
+        .checked_to_num()
+}
+
+/// Payout of a bet priced at an implied probability. Probabilities outside
+/// (0, 1] are invalid.
+pub fn payout_from_implied_probability(probability: U64F64, risk: u64) -> Option<u64> {
+    let one = U64F64::from_num(1);
+    if probability == U64F64::from_num(0) || probability > one {
+        return None;
+    }
+    let winnings_per_unit = (one - probability).checked_div(probability)?;
+    winnings_per_unit
+        .checked_mul(U64F64::checked_from_num(risk)?)?
+        .checked_to_num()
+}
+
+/// Converts American odds to decimal odds.
+pub fn american_to_decimal(odds: i64) -> Option<U64F64> {
+    let abs_odds = odds.checked_abs()? as u64;
+    if abs_odds < MIN_AMERICAN_ODDS {
+        return None;
+    }
+    let one = U64F64::from_num(1);
+    let winnings_per_unit = if odds > 0 {
+        U64F64::from_num(abs_odds).checked_div(U64F64::from_num(MIN_AMERICAN_ODDS))?
+    } else {
+        U64F64::from_num(MIN_AMERICAN_ODDS).checked_div(U64F64::from_num(abs_odds))?
+    };
+    one.checked_add(winnings_per_unit)
+}
+
+/// Converts American odds to the probability they imply, rounded up so a
+/// payout priced from it is never above the one priced from the odds.
+pub fn implied_probability(odds: i64) -> Option<U64F64> {
+    let abs_odds = odds.checked_abs()? as u64;
+    if abs_odds < MIN_AMERICAN_ODDS {
+        return None;
+    }
+    let total = U64F64::from_num(abs_odds).checked_add(U64F64::from_num(MIN_AMERICAN_ODDS))?;
+    let numerator = if odds > 0 {
+        U64F64::from_num(MIN_AMERICAN_ODDS)
+    } else {
+        U64F64::from_num(abs_odds)
+    };
+    let probability = numerator.checked_div(total)?;
+    if probability.checked_mul(total)? < numerator {
+        return probability.checked_add(U64F64::from_bits(1));
+    }
+    Some(probability)
+}
+
+/// Reads American odds from a Switchboard feed result. Fractional odds are
+/// rounded down, which lowers the payout on both positive and negative odds.
+pub fn american_from_feed(result: f64) -> Option<i64> {
+    if !result.is_finite() {
+        return None;
+    }
+    let odds = result.floor();
+    if odds < i64::MIN as f64 || odds >= i64::MAX as f64 {
+        return None;
+    }
+    Some(odds as i64)
+}
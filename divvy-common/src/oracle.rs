@@ -0,0 +1,228 @@
+use solana_program::{
+    account_info::AccountInfo,
+    program_error::ProgramError,
+    program_pack::{IsInitialized, Pack, Sealed},
+    pubkey::Pubkey,
+};
+
+use arrayref::{array_mut_ref, array_ref, array_refs, mut_array_refs};
+
+//Switchboard dependencies
+use switchboard_program::{get_aggregator, get_aggregator_result, AggregatorState, RoundResult};
+
+use crate::error::ExchangeError;
+
+/// Pyth price account header values, see pyth-client `Price`.
+pub const PYTH_MAGIC: u32 = 0xa1b2c3d4;
+pub const PYTH_VERSION: u32 = 2;
+pub const PYTH_PRICE_ACCOUNT_TYPE: u32 = 3;
+pub const PYTH_STATUS_TRADING: u32 = 1;
+/// Bytes of a Pyth price account needed to read the aggregate price.
+pub const PYTH_PRICE_LEN: usize = 240;
+
+/// The latest round of a feed.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct OracleRound {
+    pub value: f64,
+    /// Unix timestamp the round opened or was published at.
+    pub timestamp: i64,
+    /// Number of oracles or publishers that contributed to the round.
+    pub responses: u32,
+}
+
+impl OracleRound {
+    /// Checks the round against a market's thresholds. A `max_staleness` of
+    /// zero disables the staleness check.
+    pub fn check(
+        &self,
+        now: i64,
+        max_staleness: u64,
+        min_responses: u32,
+    ) -> Result<f64, ExchangeError> {
+        if max_staleness != 0 && now.saturating_sub(self.timestamp) > max_staleness as i64 {
+            return Err(ExchangeError::StaleFeed);
+        }
+        if self.responses < min_responses {
+            return Err(ExchangeError::NotEnoughFeedResponses);
+        }
+        Ok(self.value)
+    }
+}
+
+/// The oracle a market reads its odds, points and result feeds from.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum OracleSource {
+    SwitchboardV1,
+    Pyth,
+    /// A `ManualFeed` account owned by the program and written by the authority.
+    Manual,
+}
+
+impl OracleSource {
+    pub fn unpack(input: &u8) -> Result<Self, ProgramError> {
+        Ok(match input {
+            0 => Self::SwitchboardV1,
+            1 => Self::Pyth,
+            2 => Self::Manual,
+            _ => return Err(ExchangeError::InvalidInstruction.into()),
+        })
+    }
+
+    pub fn pack(&self) -> u8 {
+        match *self {
+            OracleSource::SwitchboardV1 => 0,
+            OracleSource::Pyth => 1,
+            OracleSource::Manual => 2,
+        }
+    }
+
+    /// Reads the latest round of a feed.
+    pub fn read_round(
+        &self,
+        program_id: &Pubkey,
+        feed: &AccountInfo,
+    ) -> Result<OracleRound, ProgramError> {
+        match self {
+            OracleSource::SwitchboardV1 => {
+                let aggregator: AggregatorState = get_aggregator(feed)?;
+                let round_result: RoundResult = get_aggregator_result(&aggregator)?;
+                Ok(OracleRound {
+                    value: round_result
+                        .result
+                        .ok_or(ExchangeError::FeedNotInitialized)?,
+                    timestamp: round_result.round_open_timestamp.unwrap_or(0),
+                    responses: round_result.num_success.unwrap_or(0).max(0) as u32,
+                })
+            }
+            OracleSource::Pyth => read_pyth_price(&feed.data.borrow()),
+            OracleSource::Manual => {
+                if feed.owner != program_id {
+                    return Err(ExchangeError::InvalidFeedAccount.into());
+                }
+                let manual_feed = ManualFeed::unpack_unchecked(&feed.data.borrow())?;
+                if !manual_feed.is_initialized {
+                    return Err(ExchangeError::FeedNotInitialized.into());
+                }
+                Ok(OracleRound {
+                    value: scale(manual_feed.value, manual_feed.expo),
+                    timestamp: manual_feed.timestamp,
+                    responses: 1,
+                })
+            }
+        }
+    }
+}
+
+impl From<OracleSource> for &str {
+    fn from(val: OracleSource) -> Self {
+        match val {
+            OracleSource::SwitchboardV1 => "Switchboard v1",
+            OracleSource::Pyth => "Pyth",
+            OracleSource::Manual => "Manual",
+        }
+    }
+}
+
+/// Reads the aggregate price of a Pyth price account, scaled by its exponent.
+pub fn read_pyth_price(data: &[u8]) -> Result<OracleRound, ProgramError> {
+    if data.len() < PYTH_PRICE_LEN {
+        return Err(ExchangeError::InvalidFeedAccount.into());
+    }
+    let src = array_ref![data, 0, PYTH_PRICE_LEN];
+    let (
+        magic,
+        ver,
+        atype,
+        _size_and_type,
+        expo,
+        _num,
+        num_qt,
+        _slots_and_ema,
+        timestamp,
+        _skipped,
+        price,
+        _conf,
+        status,
+        _corp_act,
+        _pub_slot,
+    ) = array_refs![src, 4, 4, 4, 8, 4, 4, 4, 64, 8, 104, 8, 8, 4, 4, 8];
+    if u32::from_le_bytes(*magic) != PYTH_MAGIC
+        || u32::from_le_bytes(*ver) != PYTH_VERSION
+        || u32::from_le_bytes(*atype) != PYTH_PRICE_ACCOUNT_TYPE
+    {
+        return Err(ExchangeError::InvalidFeedAccount.into());
+    }
+    if u32::from_le_bytes(*status) != PYTH_STATUS_TRADING {
+        return Err(ExchangeError::FeedNotInitialized.into());
+    }
+    Ok(OracleRound {
+        value: scale(i64::from_le_bytes(*price), i32::from_le_bytes(*expo)),
+        timestamp: i64::from_le_bytes(*timestamp),
+        responses: u32::from_le_bytes(*num_qt),
+    })
+}
+
+/// Returns `value * 10^expo`. Negative exponents divide by an exact power of
+/// ten so whole odds such as -110 are not nudged below the integer.
+fn scale(value: i64, expo: i32) -> f64 {
+    if expo < 0 {
+        value as f64 / 10f64.powi(-expo)
+    } else {
+        value as f64 * 10f64.powi(expo)
+    }
+}
+
+/// A feed the authority writes by hand, for events no oracle network carries.
+/// The value read from it is `value * 10^expo`.
+pub struct ManualFeed {
+    pub is_initialized: bool,
+    pub value: i64,
+    pub expo: i32,
+    /// Unix timestamp of the last write.
+    pub timestamp: i64,
+}
+
+impl Sealed for ManualFeed {}
+
+impl IsInitialized for ManualFeed {
+    fn is_initialized(&self) -> bool {
+        self.is_initialized
+    }
+}
+
+impl Pack for ManualFeed {
+    const LEN: usize = 21;
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        let src = array_ref![src, 0, ManualFeed::LEN];
+        let (is_initialized, value, expo, timestamp) = array_refs![src, 1, 8, 4, 8];
+        let is_initialized = match is_initialized {
+            [0] => false,
+            [1] => true,
+            _ => return Err(ProgramError::InvalidAccountData),
+        };
+        Ok(ManualFeed {
+            is_initialized,
+            value: i64::from_le_bytes(*value),
+            expo: i32::from_le_bytes(*expo),
+            timestamp: i64::from_le_bytes(*timestamp),
+        })
+    }
+
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        let dst = array_mut_ref![dst, 0, ManualFeed::LEN];
+        let (is_initialized_dst, value_dst, expo_dst, timestamp_dst) =
+            mut_array_refs![dst, 1, 8, 4, 8];
+
+        let ManualFeed {
+            is_initialized,
+            value,
+            expo,
+            timestamp,
+        } = self;
+
+        is_initialized_dst[0] = *is_initialized as u8;
+        *value_dst = value.to_le_bytes();
+        *expo_dst = expo.to_le_bytes();
+        *timestamp_dst = timestamp.to_le_bytes();
+    }
+}
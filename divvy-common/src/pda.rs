@@ -0,0 +1,140 @@
+//! Program derived addresses of markets, bets and withdrawal requests.
+//!
+//! A market lives at `["market", event_id, bet_type]`, so an event has at
+//! most one market of each bet type. A bet lives at
+//! `["bet", market, user, nonce]`, so a client can find a user's bets by
+//! walking their nonces, and the sum of them at `["position", market, user]`.
+//! A house pool withdrawal request lives at `["withdrawal", pool, sequence]`,
+//! so the queue is walked from its head to its tail, and the house pool's
+//! ledger of a market at `["ledger", pool, market]`. The programs create
+//! these accounts themselves.
+
+use solana_program::{
+    account_info::AccountInfo,
+    entrypoint::ProgramResult,
+    program::{invoke, invoke_signed},
+    pubkey::Pubkey,
+    rent::Rent,
+    system_instruction,
+    sysvar::Sysvar,
+};
+
+use crate::state::BetType;
+
+pub const MARKET_SEED: &[u8] = b"market";
+pub const BET_SEED: &[u8] = b"bet";
+pub const POSITION_SEED: &[u8] = b"position";
+pub const WITHDRAWAL_SEED: &[u8] = b"withdrawal";
+pub const LEDGER_SEED: &[u8] = b"ledger";
+
+/// Address and bump seed of the `bet_type` market on `event_id`.
+pub fn find_market_address(program_id: &Pubkey, event_id: u64, bet_type: BetType) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[MARKET_SEED, &event_id.to_le_bytes(), &[bet_type.pack()]],
+        program_id,
+    )
+}
+
+/// Address and bump seed of `user`'s bet number `nonce` on `market`.
+pub fn find_bet_address(
+    program_id: &Pubkey,
+    market: &Pubkey,
+    user: &Pubkey,
+    nonce: u64,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            BET_SEED,
+            market.as_ref(),
+            user.as_ref(),
+            &nonce.to_le_bytes(),
+        ],
+        program_id,
+    )
+}
+
+/// Address and bump seed of `user`'s position on `market`.
+pub fn find_position_address(program_id: &Pubkey, market: &Pubkey, user: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[POSITION_SEED, market.as_ref(), user.as_ref()], program_id)
+}
+
+/// Address and bump seed of withdrawal request number `sequence` of the
+/// house pool `pool_state`.
+pub fn find_withdrawal_address(
+    program_id: &Pubkey,
+    pool_state: &Pubkey,
+    sequence: u64,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            WITHDRAWAL_SEED,
+            pool_state.as_ref(),
+            &sequence.to_le_bytes(),
+        ],
+        program_id,
+    )
+}
+
+/// Address and bump seed of the ledger of the liquidity the house pool
+/// `pool_state` lent to `market`.
+pub fn find_ledger_address(
+    program_id: &Pubkey,
+    pool_state: &Pubkey,
+    market: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[LEDGER_SEED, pool_state.as_ref(), market.as_ref()],
+        program_id,
+    )
+}
+
+/// Creates the rent exempt account at the address of `seeds`, bump seed
+/// included, with `space` bytes owned by `program_id`. `payer` funds it.
+///
+/// Lamports sent to the address beforehand are kept, so nobody can block
+/// the address by funding it.
+pub fn create_pda_account<'a>(
+    program_id: &Pubkey,
+    payer: &AccountInfo<'a>,
+    new_account: &AccountInfo<'a>,
+    system_program: &AccountInfo<'a>,
+    space: usize,
+    seeds: &[&[u8]],
+) -> ProgramResult {
+    let rent_exempt_balance = Rent::get()?.minimum_balance(space);
+    let lamports = new_account.lamports();
+    if lamports == 0 {
+        return invoke_signed(
+            &system_instruction::create_account(
+                payer.key,
+                new_account.key,
+                rent_exempt_balance,
+                space as u64,
+                program_id,
+            ),
+            &[payer.clone(), new_account.clone(), system_program.clone()],
+            &[seeds],
+        );
+    }
+
+    if lamports < rent_exempt_balance {
+        invoke(
+            &system_instruction::transfer(
+                payer.key,
+                new_account.key,
+                rent_exempt_balance - lamports,
+            ),
+            &[payer.clone(), new_account.clone(), system_program.clone()],
+        )?;
+    }
+    invoke_signed(
+        &system_instruction::allocate(new_account.key, space as u64),
+        &[new_account.clone(), system_program.clone()],
+        &[seeds],
+    )?;
+    invoke_signed(
+        &system_instruction::assign(new_account.key, program_id),
+        &[new_account.clone(), system_program.clone()],
+        &[seeds],
+    )
+}
@@ -0,0 +1,105 @@
+//! Spread and total lines and how bets are graded against a final score.
+//!
+//! Lines are kept in tenths of a point so half-point lines are exact. A
+//! spread line is added to the chosen side's score, side 0 being the home
+//! team and side 1 the away team. A total line is compared to the combined
+//! score, side 0 taking the over and side 1 the under.
+
+use crate::state::BetType;
+
+/// Lines are stored as `points * POINTS_SCALE`, so -3.5 is stored as -35.
+pub const POINTS_SCALE: i64 = 10;
+/// Largest absolute line, in points, a points feed may quote.
+pub const MAX_POINTS: f64 = 10_000.0;
+/// Spread and total markets read the final score from the result feed as
+/// `home * SCORE_BASE + away`.
+pub const SCORE_BASE: u64 = 1_000;
+
+/// How a single bet fared against the final score.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Grade {
+    Win,
+    /// The bet landed exactly on its line and gets its risk back.
+    Push,
+    Loss,
+}
+
+impl Grade {
+    /// What a bet of `risk` and `payout` is paid back at this grade.
+    pub fn balance(&self, risk: u64, payout: u64) -> Option<u64> {
+        match self {
+            Grade::Win => risk.checked_add(payout),
+            Grade::Push => Some(risk),
+            Grade::Loss => Some(0),
+        }
+    }
+}
+
+/// Converts a points feed value to a line in tenths of a point.
+pub fn points_from_feed(value: f64) -> Option<i64> {
+    if !value.is_finite() || value.abs() > MAX_POINTS {
+        return None;
+    }
+    Some((value * POINTS_SCALE as f64).round() as i64)
+}
+
+/// Splits a result feed value into the home and away scores.
+pub fn score_from_feed(value: f64) -> Option<(u32, u32)> {
+    if !value.is_finite() || value < 0.0 || value.fract() != 0.0 {
+        return None;
+    }
+    if value >= (SCORE_BASE * SCORE_BASE) as f64 {
+        return None;
+    }
+    let score = value as u64;
+    Some(((score / SCORE_BASE) as u32, (score % SCORE_BASE) as u32))
+}
+
+/// The score the market is graded on: the home margin for spreads and the
+/// combined score for totals.
+pub fn margin(bet_type: BetType, home: u32, away: u32) -> i64 {
+    match bet_type {
+        BetType::Total => home as i64 + away as i64,
+        _ => home as i64 - away as i64,
+    }
+}
+
+/// The points a line adds to its side, in tenths of a point. Higher is
+/// always better for the bettor, so for the over a lower line is better.
+pub fn handicap(bet_type: BetType, market_side: u8, line: i64) -> i64 {
+    match (bet_type, market_side) {
+        (BetType::Total, 0) => -line,
+        _ => line,
+    }
+}
+
+/// Grades a bet on `market_side` at `line` against the market `margin`.
+pub fn grade_margin(bet_type: BetType, market_side: u8, line: i64, margin: i64) -> Grade {
+    let side_margin = if market_side == 0 { margin } else { -margin };
+    let graded = side_margin * POINTS_SCALE + handicap(bet_type, market_side, line);
+    if graded > 0 {
+        Grade::Win
+    } else if graded == 0 {
+        Grade::Push
+    } else {
+        Grade::Loss
+    }
+}
+
+/// Grades a bet on `market_side` at `line` against the final score.
+pub fn grade(bet_type: BetType, market_side: u8, line: i64, home: u32, away: u32) -> Grade {
+    grade_margin(bet_type, market_side, line, margin(bet_type, home, away))
+}
+
+/// The margins at which a bet on `market_side` at `line` changes grade: the
+/// margin just below, on and just above the line.
+pub fn margins_around(bet_type: BetType, market_side: u8, line: i64) -> [i64; 3] {
+    let handicap = handicap(bet_type, market_side, line);
+    let threshold = if market_side == 0 {
+        -handicap
+    } else {
+        handicap
+    };
+    let floor = threshold.div_euclid(POINTS_SCALE);
+    [floor - 1, floor, floor + 1]
+}
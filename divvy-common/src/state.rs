@@ -0,0 +1,657 @@
+use std::convert::TryFrom;
+
+use solana_program::{
+    account_info::AccountInfo,
+    program_error::ProgramError,
+    program_pack::{IsInitialized, Pack, Sealed},
+    pubkey::Pubkey,
+};
+
+use arrayref::{array_mut_ref, array_ref, array_refs, mut_array_refs};
+
+use crate::{
+    error::ExchangeError::{self, InvalidInstruction},
+    odds::american_from_feed,
+    oracle::OracleSource,
+    pack_pubkey_option,
+    points::{points_from_feed, score_from_feed},
+    unpack_pubkey_option,
+};
+
+pub struct Market {
+    pub is_initialized: bool,
+    pub market_sides: [MarketSide; 3],
+    pub locked_liquidity: u64,
+    pub result_feed: Pubkey,
+    pub result: MoneylineMarketOutcome,
+    /// The amount of risk the bettors have entered into the market.
+    /// When the market settles, this equals to the winning sides unsettled risk and payout
+    pub bettor_balance: u64,
+    pub pending_bets: u64,
+    pub bet_type: BetType,
+    /// The oracle the odds, points and result feeds of this market are read from.
+    pub oracle_source: OracleSource,
+    /// The oldest feed round, in seconds, bets and settlement accept. Zero disables the check.
+    pub max_staleness: u64,
+    /// The fewest oracle responses a feed round needs for bets and settlement.
+    pub min_responses: u32,
+    /// Final home score of a settled spread or total market.
+    pub home_score: u32,
+    /// Final away score of a settled spread or total market.
+    pub away_score: u32,
+    /// The event the market is on, part of its address seeds.
+    pub event_id: u64,
+    /// Code of the league or sport of the event, see `unpack_text`.
+    pub league: [u8; LEAGUE_LEN],
+    /// Unix timestamp the event is scheduled to start at.
+    pub start_time: i64,
+    /// Set while the market alone is suspended, blocking its bets and settlement.
+    pub suspended: bool,
+}
+
+pub struct MarketSide {
+    pub odds_feed_account: Option<Pubkey>,
+    pub points_feed_account: Option<Pubkey>,
+    pub payout: u64,
+    pub risk: u64,
+    /// The line most generous to the side's bettors, in tenths of a point.
+    /// Spread and total markets only.
+    pub line: i64,
+    /// What the side stands for, like a team name, see `unpack_text`.
+    pub label: [u8; LABEL_LEN],
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum BetType {
+    MoneyLine,
+    Spread,
+    Total,
+}
+
+/// Bytes of a market side label.
+pub const LABEL_LEN: usize = 32;
+/// Bytes of a league code.
+pub const LEAGUE_LEN: usize = 8;
+
+pub struct Bet {
+    pub is_initialized: bool,
+    pub market: Pubkey,
+    pub user_usdt_account: Pubkey,
+    pub user_main_account: Pubkey,
+    pub user_risk: u64,
+    pub user_payout: u64,
+    pub user_market_side: u8,
+    pub outcome: u8,
+    /// The spread or total line when the bet was placed, in tenths of a point.
+    pub line: i64,
+}
+
+/// A wallet's bets on a market, summed by side. It lives at
+/// `find_position_address` of the market and the wallet, and is created
+/// with the wallet's first bet.
+pub struct Position {
+    pub is_initialized: bool,
+    pub market: Pubkey,
+    pub user_main_account: Pubkey,
+    /// Total risk of the wallet's bets on each market side.
+    pub risk: [u64; 3],
+    /// Total payout of the wallet's bets on each market side.
+    pub payout: [u64; 3],
+}
+
+#[derive(PartialEq, Clone, Copy)]
+pub enum MoneylineMarketOutcome {
+    MarketSide0Won,
+    MarketSide1Won,
+    MarketSide2Won,
+    NotYetCommenced,
+    Commenced,
+    /// A spread or total market has its final score, bets are graded per line.
+    FinalScore,
+    /// The event was cancelled or postponed, every bet gets its risk back.
+    Void,
+}
+
+/// A result feed reading this value voids the market instead of naming a
+/// winner or a final score.
+pub const VOID_RESULT: f64 = -1.0;
+
+impl MoneylineMarketOutcome {
+    pub fn unpack(input: &u8) -> Result<Self, ProgramError> {
+        Ok(match input {
+            0 => Self::MarketSide0Won,
+            1 => Self::MarketSide1Won,
+            2 => Self::MarketSide2Won,
+            3 => Self::NotYetCommenced,
+            4 => Self::Commenced,
+            5 => Self::FinalScore,
+            6 => Self::Void,
+            _ => return Err(InvalidInstruction.into()),
+        })
+    }
+
+    pub fn pack(&self) -> u8 {
+        match *self {
+            MoneylineMarketOutcome::MarketSide0Won => 0,
+            MoneylineMarketOutcome::MarketSide1Won => 1,
+            MoneylineMarketOutcome::MarketSide2Won => 2,
+            MoneylineMarketOutcome::NotYetCommenced => 3,
+            MoneylineMarketOutcome::Commenced => 4,
+            MoneylineMarketOutcome::FinalScore => 5,
+            MoneylineMarketOutcome::Void => 6,
+        }
+    }
+}
+impl From<MoneylineMarketOutcome> for &str {
+    fn from(val: MoneylineMarketOutcome) -> Self {
+        match val {
+            MoneylineMarketOutcome::MarketSide0Won => "Market side 0 won",
+            MoneylineMarketOutcome::MarketSide1Won => "Market side 1 won",
+            MoneylineMarketOutcome::MarketSide2Won => "Market side 2 won",
+            MoneylineMarketOutcome::NotYetCommenced => "Not yet commenced",
+            MoneylineMarketOutcome::Commenced => "Commenced",
+            MoneylineMarketOutcome::FinalScore => "Final score",
+            MoneylineMarketOutcome::Void => "Void",
+        }
+    }
+}
+
+impl BetType {
+    pub fn unpack(input: &u8) -> Result<Self, ProgramError> {
+        Ok(match input {
+            0 => Self::MoneyLine,
+            1 => Self::Spread,
+            2 => Self::Total,
+            _ => return Err(InvalidInstruction.into()),
+        })
+    }
+
+    pub fn pack(&self) -> u8 {
+        match *self {
+            BetType::MoneyLine => 0,
+            BetType::Spread => 1,
+            BetType::Total => 2,
+        }
+    }
+}
+impl From<BetType> for &str {
+    fn from(val: BetType) -> Self {
+        match val {
+            BetType::MoneyLine => "Money Line 3 Way",
+            BetType::Spread => "Points Spread",
+            BetType::Total => "Total Score",
+        }
+    }
+}
+
+impl Market {
+    /// Unix timestamp betting on the market closes at, `bet_cutoff_secs`
+    /// before its start time. From then on anyone may commence the market.
+    pub fn bet_cutoff(&self, bet_cutoff_secs: u64) -> i64 {
+        self.start_time
+            .saturating_sub(i64::try_from(bet_cutoff_secs).unwrap_or(i64::MAX))
+    }
+
+    /// Reads a feed from the market oracle, rejecting rounds that are too old
+    /// or have too few responses at unix timestamp `now`.
+    pub fn read_feed(
+        &self,
+        program_id: &Pubkey,
+        feed: &AccountInfo,
+        now: i64,
+    ) -> Result<f64, ProgramError> {
+        let round = self.oracle_source.read_round(program_id, feed)?;
+        Ok(round.check(now, self.max_staleness, self.min_responses)?)
+    }
+
+    /// Reads American odds from an odds feed.
+    pub fn read_odds(
+        &self,
+        program_id: &Pubkey,
+        feed: &AccountInfo,
+        now: i64,
+    ) -> Result<i64, ProgramError> {
+        let value = self.read_feed(program_id, feed, now)?;
+        Ok(american_from_feed(value).ok_or(ExchangeError::InvalidOdds)?)
+    }
+
+    /// Reads the line, in tenths of a point, from a spread or total points feed.
+    pub fn read_points(
+        &self,
+        program_id: &Pubkey,
+        feed: &AccountInfo,
+        now: i64,
+    ) -> Result<i64, ProgramError> {
+        let value = self.read_feed(program_id, feed, now)?;
+        Ok(points_from_feed(value).ok_or(ExchangeError::InvalidPoints)?)
+    }
+
+    /// Reads the final home and away scores from the result feed, or `None`
+    /// if the feed voids the market.
+    pub fn read_score(
+        &self,
+        program_id: &Pubkey,
+        feed: &AccountInfo,
+        now: i64,
+    ) -> Result<Option<(u32, u32)>, ProgramError> {
+        let value = self.read_feed(program_id, feed, now)?;
+        if value == VOID_RESULT {
+            return Ok(None);
+        }
+        Ok(Some(
+            score_from_feed(value).ok_or(ExchangeError::NotValidMarketResult)?,
+        ))
+    }
+
+    /// Reads the winning market side, or a void, from the result feed.
+    pub fn read_result(
+        &self,
+        program_id: &Pubkey,
+        feed: &AccountInfo,
+        now: i64,
+    ) -> Result<MoneylineMarketOutcome, ProgramError> {
+        let value = self.read_feed(program_id, feed, now)?;
+        if value == VOID_RESULT {
+            return Ok(MoneylineMarketOutcome::Void);
+        }
+        if !(0.0..3.0).contains(&value) {
+            return Err(ExchangeError::NotValidMarketResult.into());
+        }
+        MoneylineMarketOutcome::unpack(&(value as u8))
+    }
+}
+
+impl Sealed for Market {}
+
+impl Sealed for Bet {}
+
+impl Sealed for Position {}
+
+impl IsInitialized for Market {
+    fn is_initialized(&self) -> bool {
+        self.is_initialized
+    }
+}
+
+impl IsInitialized for Bet {
+    fn is_initialized(&self) -> bool {
+        self.is_initialized
+    }
+}
+
+impl IsInitialized for Position {
+    fn is_initialized(&self) -> bool {
+        self.is_initialized
+    }
+}
+
+impl Pack for Market {
+    const LEN: usize = 471;
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        let src = array_ref![src, 0, Market::LEN];
+        let (
+            is_initialized,
+            option_0_odds_pubkey,
+            option_0_points_pubkey,
+            option_0_loss,
+            option_0_win,
+            option_1_odds_pubkey,
+            option_1_points_pubkey,
+            option_1_loss,
+            option_1_win,
+            option_2_odds_pubkey,
+            option_2_points_pubkey,
+            option_2_loss,
+            option_2_win,
+            locked_liquidity,
+            result_feed,
+            result,
+            user_risk,
+            pending_bets,
+            bet_type,
+            oracle_source,
+            max_staleness,
+            min_responses,
+            option_0_line,
+            option_1_line,
+            option_2_line,
+            home_score,
+            away_score,
+            event_id,
+            option_0_label,
+            option_1_label,
+            option_2_label,
+            league,
+            start_time,
+            suspended,
+        ) = array_refs![
+            src, 1, 33, 33, 8, 8, 33, 33, 8, 8, 33, 33, 8, 8, 8, 32, 1, 8, 8, 1, 1, 8, 4, 8, 8, 8,
+            4, 4, 8, 32, 32, 32, 8, 8, 1
+        ];
+        let is_initialized = match is_initialized {
+            [0] => false,
+            [1] => true,
+            _ => return Err(ProgramError::InvalidAccountData),
+        };
+        Ok(Market {
+            is_initialized,
+            market_sides: [
+                MarketSide {
+                    odds_feed_account: unpack_pubkey_option(option_0_odds_pubkey)?.0,
+                    points_feed_account: unpack_pubkey_option(option_0_points_pubkey)?.0,
+                    payout: u64::from_le_bytes(*option_0_loss),
+                    risk: u64::from_le_bytes(*option_0_win),
+                    line: i64::from_le_bytes(*option_0_line),
+                    label: *option_0_label,
+                },
+                MarketSide {
+                    odds_feed_account: unpack_pubkey_option(option_1_odds_pubkey)?.0,
+                    points_feed_account: unpack_pubkey_option(option_1_points_pubkey)?.0,
+                    payout: u64::from_le_bytes(*option_1_loss),
+                    risk: u64::from_le_bytes(*option_1_win),
+                    line: i64::from_le_bytes(*option_1_line),
+                    label: *option_1_label,
+                },
+                MarketSide {
+                    odds_feed_account: unpack_pubkey_option(option_2_odds_pubkey)?.0,
+                    points_feed_account: unpack_pubkey_option(option_2_points_pubkey)?.0,
+                    payout: u64::from_le_bytes(*option_2_loss),
+                    risk: u64::from_le_bytes(*option_2_win),
+                    line: i64::from_le_bytes(*option_2_line),
+                    label: *option_2_label,
+                },
+            ],
+            locked_liquidity: u64::from_le_bytes(*locked_liquidity),
+            result_feed: Pubkey::new_from_array(*result_feed),
+            result: MoneylineMarketOutcome::unpack(&(u8::from_le_bytes(*result))).unwrap(),
+            bettor_balance: u64::from_le_bytes(*user_risk),
+            pending_bets: u64::from_le_bytes(*pending_bets),
+            bet_type: BetType::unpack(&bet_type[0])?,
+            oracle_source: OracleSource::unpack(&oracle_source[0])?,
+            max_staleness: u64::from_le_bytes(*max_staleness),
+            min_responses: u32::from_le_bytes(*min_responses),
+            home_score: u32::from_le_bytes(*home_score),
+            away_score: u32::from_le_bytes(*away_score),
+            event_id: u64::from_le_bytes(*event_id),
+            league: *league,
+            start_time: i64::from_le_bytes(*start_time),
+            suspended: suspended[0] != 0,
+        })
+    }
+
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        let dst = array_mut_ref![dst, 0, Market::LEN];
+        let (
+            is_initialized_dst,
+            option_0_odds_pubkey_dst,
+            option_0_points_pubkey_dst,
+            option_0_loss_dst,
+            option_0_win_dst,
+            option_1_odds_pubkey_dst,
+            option_1_points_pubkey_dst,
+            option_1_loss_dst,
+            option_1_win_dst,
+            option_2_odds_pubkey_dst,
+            option_2_points_pubkey_dst,
+            option_2_loss_dst,
+            option_2_win_dst,
+            locked_liquidity_dst,
+            result_feed_dst,
+            result_dst,
+            user_risk_dst,
+            pending_bets_dst,
+            bet_type_dst,
+            oracle_source_dst,
+            max_staleness_dst,
+            min_responses_dst,
+            option_0_line_dst,
+            option_1_line_dst,
+            option_2_line_dst,
+            home_score_dst,
+            away_score_dst,
+            event_id_dst,
+            option_0_label_dst,
+            option_1_label_dst,
+            option_2_label_dst,
+            league_dst,
+            start_time_dst,
+            suspended_dst,
+        ) = mut_array_refs![
+            dst, 1, 33, 33, 8, 8, 33, 33, 8, 8, 33, 33, 8, 8, 8, 32, 1, 8, 8, 1, 1, 8, 4, 8, 8, 8,
+            4, 4, 8, 32, 32, 32, 8, 8, 1
+        ];
+
+        let Market {
+            is_initialized,
+            market_sides,
+            locked_liquidity,
+            result_feed,
+            result,
+            bettor_balance: user_risk,
+            pending_bets,
+            bet_type,
+            oracle_source,
+            max_staleness,
+            min_responses,
+            home_score,
+            away_score,
+            event_id,
+            league,
+            start_time,
+            suspended,
+        } = self;
+
+        is_initialized_dst[0] = *is_initialized as u8;
+        pack_pubkey_option(&market_sides[0].odds_feed_account, option_0_odds_pubkey_dst);
+        pack_pubkey_option(
+            &market_sides[0].points_feed_account,
+            option_0_points_pubkey_dst,
+        );
+        *option_0_loss_dst = market_sides[0].payout.to_le_bytes();
+        *option_0_win_dst = market_sides[0].risk.to_le_bytes();
+        pack_pubkey_option(&market_sides[1].odds_feed_account, option_1_odds_pubkey_dst);
+        pack_pubkey_option(
+            &market_sides[1].points_feed_account,
+            option_1_points_pubkey_dst,
+        );
+        *option_1_loss_dst = market_sides[1].payout.to_le_bytes();
+        *option_1_win_dst = market_sides[1].risk.to_le_bytes();
+        pack_pubkey_option(&market_sides[2].odds_feed_account, option_2_odds_pubkey_dst);
+        pack_pubkey_option(
+            &market_sides[2].points_feed_account,
+            option_2_points_pubkey_dst,
+        );
+        *option_2_loss_dst = market_sides[2].payout.to_le_bytes();
+        *option_2_win_dst = market_sides[2].risk.to_le_bytes();
+        *locked_liquidity_dst = locked_liquidity.to_le_bytes();
+        result_feed_dst.copy_from_slice(result_feed.as_ref());
+        *result_dst = result.pack().to_le_bytes();
+        *user_risk_dst = user_risk.to_le_bytes();
+        *pending_bets_dst = pending_bets.to_le_bytes();
+        bet_type_dst[0] = bet_type.pack();
+        oracle_source_dst[0] = oracle_source.pack();
+        *max_staleness_dst = max_staleness.to_le_bytes();
+        *min_responses_dst = min_responses.to_le_bytes();
+        *option_0_line_dst = market_sides[0].line.to_le_bytes();
+        *option_1_line_dst = market_sides[1].line.to_le_bytes();
+        *option_2_line_dst = market_sides[2].line.to_le_bytes();
+        *home_score_dst = home_score.to_le_bytes();
+        *away_score_dst = away_score.to_le_bytes();
+        *event_id_dst = event_id.to_le_bytes();
+        *option_0_label_dst = market_sides[0].label;
+        *option_1_label_dst = market_sides[1].label;
+        *option_2_label_dst = market_sides[2].label;
+        *league_dst = *league;
+        *start_time_dst = start_time.to_le_bytes();
+        suspended_dst[0] = *suspended as u8;
+    }
+}
+
+impl Pack for Bet {
+    const LEN: usize = 123;
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        let src = array_ref![src, 0, Bet::LEN];
+        let (
+            is_initialized,
+            market,
+            user_usdt_account,
+            user_main_account,
+            user_risk,
+            user_payout,
+            user_market_side,
+            outcome,
+            line,
+        ) = array_refs![src, 1, 32, 32, 32, 8, 8, 1, 1, 8];
+        let is_initialized = match is_initialized {
+            [0] => false,
+            [1] => true,
+            _ => return Err(ProgramError::InvalidAccountData),
+        };
+        Ok(Bet {
+            is_initialized,
+            market: Pubkey::new_from_array(*market),
+            user_usdt_account: Pubkey::new_from_array(*user_usdt_account),
+            user_main_account: Pubkey::new_from_array(*user_main_account),
+            user_risk: u64::from_le_bytes(*user_risk),
+            user_payout: u64::from_le_bytes(*user_payout),
+            user_market_side: u8::from_le_bytes(*user_market_side),
+            outcome: u8::from_le_bytes(*outcome),
+            line: i64::from_le_bytes(*line),
+        })
+    }
+
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        let dst = array_mut_ref![dst, 0, Bet::LEN];
+        let (
+            is_initialized_dst,
+            market_dst,
+            user_usdt_account_dst,
+            user_main_account_dst,
+            user_risk_dst,
+            user_payout_dst,
+            user_market_side_dst,
+            outcome_dst,
+            line_dst,
+        ) = mut_array_refs![dst, 1, 32, 32, 32, 8, 8, 1, 1, 8];
+
+        let Bet {
+            is_initialized,
+            market,
+            user_usdt_account,
+            user_main_account,
+            user_risk,
+            user_payout,
+            user_market_side,
+            outcome,
+            line,
+        } = self;
+
+        is_initialized_dst[0] = *is_initialized as u8;
+        market_dst.copy_from_slice(market.as_ref());
+        user_usdt_account_dst.copy_from_slice(user_usdt_account.as_ref());
+        user_main_account_dst.copy_from_slice(user_main_account.as_ref());
+        *user_risk_dst = user_risk.to_le_bytes();
+        *user_payout_dst = user_payout.to_le_bytes();
+        *user_market_side_dst = user_market_side.to_le_bytes();
+        *outcome_dst = outcome.to_le_bytes();
+        *line_dst = line.to_le_bytes();
+    }
+}
+
+impl Position {
+    /// Adds a bet on `market_side` to the position.
+    pub fn add_bet(
+        &mut self,
+        market_side: u8,
+        risk: u64,
+        payout: u64,
+    ) -> Result<(), ExchangeError> {
+        let side = market_side as usize;
+        self.risk[side] = self.risk[side]
+            .checked_add(risk)
+            .ok_or(ExchangeError::AmountOverflow)?;
+        self.payout[side] = self.payout[side]
+            .checked_add(payout)
+            .ok_or(ExchangeError::AmountOverflow)?;
+        Ok(())
+    }
+
+    /// Total risk of the wallet's bets on the market.
+    pub fn total_risk(&self) -> Result<u64, ExchangeError> {
+        self.risk
+            .iter()
+            .try_fold(0u64, |total, risk| total.checked_add(*risk))
+            .ok_or(ExchangeError::AmountOverflow)
+    }
+}
+
+impl Pack for Position {
+    const LEN: usize = 113;
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        let src = array_ref![src, 0, Position::LEN];
+        let (
+            is_initialized,
+            market,
+            user_main_account,
+            risk_0,
+            risk_1,
+            risk_2,
+            payout_0,
+            payout_1,
+            payout_2,
+        ) = array_refs![src, 1, 32, 32, 8, 8, 8, 8, 8, 8];
+        let is_initialized = match is_initialized {
+            [0] => false,
+            [1] => true,
+            _ => return Err(ProgramError::InvalidAccountData),
+        };
+        Ok(Position {
+            is_initialized,
+            market: Pubkey::new_from_array(*market),
+            user_main_account: Pubkey::new_from_array(*user_main_account),
+            risk: [
+                u64::from_le_bytes(*risk_0),
+                u64::from_le_bytes(*risk_1),
+                u64::from_le_bytes(*risk_2),
+            ],
+            payout: [
+                u64::from_le_bytes(*payout_0),
+                u64::from_le_bytes(*payout_1),
+                u64::from_le_bytes(*payout_2),
+            ],
+        })
+    }
+
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        let dst = array_mut_ref![dst, 0, Position::LEN];
+        let (
+            is_initialized_dst,
+            market_dst,
+            user_main_account_dst,
+            risk_0_dst,
+            risk_1_dst,
+            risk_2_dst,
+            payout_0_dst,
+            payout_1_dst,
+            payout_2_dst,
+        ) = mut_array_refs![dst, 1, 32, 32, 8, 8, 8, 8, 8, 8];
+
+        let Position {
+            is_initialized,
+            market,
+            user_main_account,
+            risk,
+            payout,
+        } = self;
+
+        is_initialized_dst[0] = *is_initialized as u8;
+        market_dst.copy_from_slice(market.as_ref());
+        user_main_account_dst.copy_from_slice(user_main_account.as_ref());
+        *risk_0_dst = risk[0].to_le_bytes();
+        *risk_1_dst = risk[1].to_le_bytes();
+        *risk_2_dst = risk[2].to_le_bytes();
+        *payout_0_dst = payout[0].to_le_bytes();
+        *payout_1_dst = payout[1].to_le_bytes();
+        *payout_2_dst = payout[2].to_le_bytes();
+    }
+}
@@ -0,0 +1,321 @@
+use divvycommon::{
+    authority::{
+        validate_authority, validate_role, Multisig, Role, Roles, MAX_ROLE_MEMBERS, MAX_SIGNERS,
+    },
+    error::ExchangeError,
+};
+use solana_program::{
+    account_info::AccountInfo, program_error::ProgramError, program_pack::Pack, pubkey::Pubkey,
+};
+
+fn multisig(m: u8, signers: &[Pubkey]) -> Vec<u8> {
+    let mut multisig = Multisig {
+        m,
+        n: signers.len() as u8,
+        is_initialized: true,
+        signers: [Pubkey::default(); MAX_SIGNERS],
+    };
+    multisig.signers[..signers.len()].copy_from_slice(signers);
+    let mut data = vec![0; Multisig::LEN];
+    Multisig::pack(multisig, &mut data).unwrap();
+    data
+}
+
+#[test]
+fn test_multisig_pack_round_trip() {
+    let signers = [Pubkey::new_unique(), Pubkey::new_unique()];
+    let data = multisig(1, &signers);
+    let unpacked = Multisig::unpack(&data).unwrap();
+    assert_eq!(unpacked.m, 1);
+    assert_eq!(unpacked.n, 2);
+    assert_eq!(unpacked.signers[..2], signers);
+    assert_eq!(unpacked.signers[2], Pubkey::default());
+    assert!(Multisig::unpack(&vec![0; Multisig::LEN]).is_err());
+}
+
+#[test]
+fn test_wallet_authority() {
+    let program_id = Pubkey::new_unique();
+    let system_program = Pubkey::default();
+    let authority = Pubkey::new_unique();
+    let other = Pubkey::new_unique();
+    let (mut lamports, mut other_lamports) = (0, 0);
+    let (mut data, mut other_data) = (vec![], vec![]);
+    let authority_info = AccountInfo::new(
+        &authority,
+        true,
+        false,
+        &mut lamports,
+        &mut data,
+        &system_program,
+        false,
+        0,
+    );
+    let mut other_info = AccountInfo::new(
+        &other,
+        true,
+        false,
+        &mut other_lamports,
+        &mut other_data,
+        &system_program,
+        false,
+        0,
+    );
+
+    assert_eq!(
+        validate_authority(&program_id, &authority, &authority_info, &[]),
+        Ok(())
+    );
+    assert_eq!(
+        validate_authority(&program_id, &authority, &other_info, &[]),
+        Err(ExchangeError::NotValidAuthority.into())
+    );
+    other_info.is_signer = false;
+    assert_eq!(
+        validate_authority(&program_id, &other, &other_info, &[]),
+        Err(ProgramError::MissingRequiredSignature)
+    );
+}
+
+#[test]
+fn test_multisig_authority() {
+    let program_id = Pubkey::new_unique();
+    let system_program = Pubkey::default();
+    let authority = Pubkey::new_unique();
+    let keys = [
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+    ];
+    let outsider = Pubkey::new_unique();
+    let mut lamports = 0;
+    let mut data = multisig(2, &keys);
+    // The multisig account itself never signs
+    let authority_info = AccountInfo::new(
+        &authority,
+        false,
+        false,
+        &mut lamports,
+        &mut data,
+        &program_id,
+        false,
+        0,
+    );
+    let mut signer_lamports = [0; 4];
+    let mut signer_data: [Vec<u8>; 4] = Default::default();
+    let mut signers = keys
+        .iter()
+        .chain(std::iter::once(&outsider))
+        .zip(signer_lamports.iter_mut().zip(signer_data.iter_mut()))
+        .map(|(key, (lamports, data))| {
+            AccountInfo::new(key, true, false, lamports, data, &system_program, false, 0)
+        })
+        .collect::<Vec<_>>();
+
+    assert_eq!(
+        validate_authority(&program_id, &authority, &authority_info, &signers[..2]),
+        Ok(())
+    );
+    assert_eq!(
+        validate_authority(&program_id, &authority, &authority_info, &signers[1..3]),
+        Ok(())
+    );
+    // One signer is not enough, even when passed twice
+    assert_eq!(
+        validate_authority(
+            &program_id,
+            &authority,
+            &authority_info,
+            &[signers[0].clone(), signers[0].clone()]
+        ),
+        Err(ProgramError::MissingRequiredSignature)
+    );
+    // Keys outside the signer set do not count
+    assert_eq!(
+        validate_authority(
+            &program_id,
+            &authority,
+            &authority_info,
+            &[signers[0].clone(), signers[3].clone()]
+        ),
+        Err(ProgramError::MissingRequiredSignature)
+    );
+    // Listed signers must sign
+    signers[1].is_signer = false;
+    assert_eq!(
+        validate_authority(&program_id, &authority, &authority_info, &signers[..2]),
+        Err(ProgramError::MissingRequiredSignature)
+    );
+}
+
+#[test]
+fn test_multisig_listing_a_key_twice() {
+    let program_id = Pubkey::new_unique();
+    let system_program = Pubkey::default();
+    let authority = Pubkey::new_unique();
+    let key = Pubkey::new_unique();
+    let other = Pubkey::new_unique();
+    let mut lamports = 0;
+    let mut data = multisig(2, &[key, key, other]);
+    let authority_info = AccountInfo::new(
+        &authority,
+        false,
+        false,
+        &mut lamports,
+        &mut data,
+        &program_id,
+        false,
+        0,
+    );
+    let (mut signer_lamports, mut signer_data) = (0, vec![]);
+    let signer = AccountInfo::new(
+        &key,
+        true,
+        false,
+        &mut signer_lamports,
+        &mut signer_data,
+        &system_program,
+        false,
+        0,
+    );
+
+    // A key listed twice still counts as one signer
+    assert_eq!(
+        validate_authority(&program_id, &authority, &authority_info, &[signer.clone()]),
+        Err(ProgramError::MissingRequiredSignature)
+    );
+    assert_eq!(
+        validate_authority(
+            &program_id,
+            &authority,
+            &authority_info,
+            &[signer.clone(), signer]
+        ),
+        Err(ProgramError::MissingRequiredSignature)
+    );
+}
+
+#[test]
+fn test_grant_and_revoke_roles() {
+    let pauser = Pubkey::new_unique();
+    let mut roles = Roles::default();
+    assert!(!roles.has_role(Role::Pauser, &pauser));
+    assert!(!roles.has_role(Role::Pauser, &Pubkey::default()));
+
+    roles.grant(Role::Pauser, &pauser).unwrap();
+    assert!(roles.has_role(Role::Pauser, &pauser));
+    assert!(!roles.has_role(Role::Treasurer, &pauser));
+    assert_eq!(
+        roles.grant(Role::Pauser, &pauser),
+        Err(ExchangeError::RoleAlreadyGranted)
+    );
+    assert_eq!(
+        roles.grant(Role::Pauser, &Pubkey::default()),
+        Err(ExchangeError::InvalidInstruction)
+    );
+    for _ in 1..MAX_ROLE_MEMBERS {
+        roles.grant(Role::Pauser, &Pubkey::new_unique()).unwrap();
+    }
+    assert_eq!(
+        roles.grant(Role::Pauser, &Pubkey::new_unique()),
+        Err(ExchangeError::RoleFull)
+    );
+
+    let mut packed = [0; Roles::LEN];
+    roles.pack_into_slice(&mut packed);
+    assert_eq!(Roles::unpack_from_slice(&packed), roles);
+
+    roles.revoke(Role::Pauser, &pauser).unwrap();
+    assert!(!roles.has_role(Role::Pauser, &pauser));
+    assert_eq!(
+        roles.revoke(Role::Pauser, &pauser),
+        Err(ExchangeError::MissingRole)
+    );
+    assert_eq!(
+        roles.revoke(Role::Treasurer, &Pubkey::default()),
+        Err(ExchangeError::MissingRole)
+    );
+    // The freed slot takes a new member
+    roles.grant(Role::Pauser, &pauser).unwrap();
+}
+
+#[test]
+fn test_validate_role() {
+    let program_id = Pubkey::new_unique();
+    let system_program = Pubkey::default();
+    let authority = Pubkey::new_unique();
+    let pauser = Pubkey::new_unique();
+    let mut roles = Roles::default();
+    roles.grant(Role::Pauser, &pauser).unwrap();
+    let (mut authority_lamports, mut pauser_lamports) = (0, 0);
+    let (mut authority_data, mut pauser_data) = (vec![], vec![]);
+    let authority_info = AccountInfo::new(
+        &authority,
+        true,
+        false,
+        &mut authority_lamports,
+        &mut authority_data,
+        &system_program,
+        false,
+        0,
+    );
+    let mut pauser_info = AccountInfo::new(
+        &pauser,
+        true,
+        false,
+        &mut pauser_lamports,
+        &mut pauser_data,
+        &system_program,
+        false,
+        0,
+    );
+
+    // The authority holds every role
+    for role in [
+        Role::MarketCreator,
+        Role::Settler,
+        Role::Pauser,
+        Role::Treasurer,
+    ]
+    .iter()
+    {
+        assert_eq!(
+            validate_role(&program_id, &authority, &roles, *role, &authority_info, &[]),
+            Ok(())
+        );
+    }
+    assert_eq!(
+        validate_role(
+            &program_id,
+            &authority,
+            &roles,
+            Role::Pauser,
+            &pauser_info,
+            &[]
+        ),
+        Ok(())
+    );
+    assert_eq!(
+        validate_role(
+            &program_id,
+            &authority,
+            &roles,
+            Role::Treasurer,
+            &pauser_info,
+            &[]
+        ),
+        Err(ExchangeError::NotValidAuthority.into())
+    );
+    pauser_info.is_signer = false;
+    assert_eq!(
+        validate_role(
+            &program_id,
+            &authority,
+            &roles,
+            Role::Pauser,
+            &pauser_info,
+            &[]
+        ),
+        Err(ProgramError::MissingRequiredSignature)
+    );
+}
@@ -0,0 +1,65 @@
+use divvycommon::{
+    error::ExchangeError,
+    fees::{
+        check_fees, fee, split_house_profit, FeeSplit, DEFAULT_DIVVY_FOUNDATION_FEE_BPS,
+        DEFAULT_INSURANCE_FUND_FEE_BPS, MAX_DIVVY_FOUNDATION_FEE_BPS, MAX_INSURANCE_FUND_FEE_BPS,
+    },
+};
+
+#[test]
+fn test_fee() {
+    assert_eq!(fee(1_000_000, 100), 10_000);
+    assert_eq!(fee(1_000_000, 0), 0);
+    assert_eq!(fee(1_000_000, 10_000), 1_000_000);
+    // Rounded down
+    assert_eq!(fee(199, 50), 0);
+    assert_eq!(fee(u64::MAX, 10_000), u64::MAX);
+}
+
+#[test]
+fn test_default_split() {
+    assert_eq!(
+        split_house_profit(
+            159_090_910,
+            DEFAULT_INSURANCE_FUND_FEE_BPS,
+            DEFAULT_DIVVY_FOUNDATION_FEE_BPS
+        ),
+        Ok(FeeSplit {
+            insurance_fund_fee: 1_590_909,
+            divvy_foundation_fee: 7_954_545,
+            house_share: 149_545_456,
+        })
+    );
+}
+
+#[test]
+fn test_house_keeps_the_rounding() {
+    let split = split_house_profit(99, 100, 500).unwrap();
+    assert_eq!(split.insurance_fund_fee, 0);
+    assert_eq!(split.divvy_foundation_fee, 4);
+    assert_eq!(split.house_share, 95);
+
+    let split = split_house_profit(0, 100, 500).unwrap();
+    assert_eq!(split.house_share, 0);
+}
+
+#[test]
+fn test_fee_bounds() {
+    assert_eq!(
+        check_fees(MAX_INSURANCE_FUND_FEE_BPS, MAX_DIVVY_FOUNDATION_FEE_BPS),
+        Ok(())
+    );
+    assert_eq!(check_fees(0, 0), Ok(()));
+    assert_eq!(
+        check_fees(MAX_INSURANCE_FUND_FEE_BPS + 1, 0),
+        Err(ExchangeError::FeeTooHigh)
+    );
+    assert_eq!(
+        check_fees(0, MAX_DIVVY_FOUNDATION_FEE_BPS + 1),
+        Err(ExchangeError::FeeTooHigh)
+    );
+    assert_eq!(
+        split_house_profit(1_000, 10_000, 10_000),
+        Err(ExchangeError::FeeTooHigh)
+    );
+}
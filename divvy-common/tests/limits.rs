@@ -0,0 +1,166 @@
+use divvycommon::{error::ExchangeError, limits::ExposureLimits, state::Position};
+use solana_program::pubkey::Pubkey;
+
+#[test]
+fn test_no_limits_by_default() {
+    let limits = ExposureLimits::default();
+    assert_eq!(limits.check(), Ok(()));
+    assert_eq!(limits.check_bet_risk(1), Ok(()));
+    assert_eq!(limits.check_bet_risk(u64::MAX), Ok(()));
+    assert_eq!(limits.check_side_payout(u64::MAX), Ok(()));
+    assert_eq!(limits.check_market_exposure(u64::MAX, 0), Ok(()));
+    let whale = Position {
+        is_initialized: true,
+        market: Pubkey::new_unique(),
+        user_main_account: Pubkey::new_unique(),
+        risk: [u64::MAX, 0, 0],
+        payout: [u64::MAX, 0, 0],
+    };
+    assert_eq!(limits.check_position(&whale, 0), Ok(()));
+}
+
+#[test]
+fn test_bet_risk() {
+    let limits = ExposureLimits {
+        min_bet_risk: 10,
+        max_bet_risk: 1_000,
+        ..ExposureLimits::default()
+    };
+    assert_eq!(limits.check_bet_risk(9), Err(ExchangeError::BetRiskTooLow));
+    assert_eq!(limits.check_bet_risk(10), Ok(()));
+    assert_eq!(limits.check_bet_risk(1_000), Ok(()));
+    assert_eq!(
+        limits.check_bet_risk(1_001),
+        Err(ExchangeError::BetRiskTooHigh)
+    );
+
+    // A minimum alone leaves the risk uncapped
+    let limits = ExposureLimits {
+        min_bet_risk: 10,
+        ..ExposureLimits::default()
+    };
+    assert_eq!(limits.check(), Ok(()));
+    assert_eq!(limits.check_bet_risk(u64::MAX), Ok(()));
+}
+
+#[test]
+fn test_side_payout() {
+    let limits = ExposureLimits {
+        max_side_payout: 5_000,
+        ..ExposureLimits::default()
+    };
+    assert_eq!(limits.check_side_payout(5_000), Ok(()));
+    assert_eq!(
+        limits.check_side_payout(5_001),
+        Err(ExchangeError::SidePayoutLimitExceeded)
+    );
+}
+
+#[test]
+fn test_wallet_position() {
+    let limits = ExposureLimits {
+        max_wallet_risk: 1_000,
+        max_wallet_payout: 2_000,
+        ..ExposureLimits::default()
+    };
+    let mut position = Position {
+        is_initialized: true,
+        market: Pubkey::new_unique(),
+        user_main_account: Pubkey::new_unique(),
+        risk: [0; 3],
+        payout: [0; 3],
+    };
+    // Splitting the risk into many bets adds up to the same position
+    for _ in 0..4 {
+        position.add_bet(1, 100, 150).unwrap();
+    }
+    position.add_bet(2, 600, 1_800).unwrap();
+    assert_eq!(position.total_risk(), Ok(1_000));
+    assert_eq!(limits.check_position(&position, 2), Ok(()));
+    position.add_bet(0, 1, 1).unwrap();
+    assert_eq!(
+        limits.check_position(&position, 0),
+        Err(ExchangeError::WalletRiskLimitExceeded)
+    );
+
+    // The payout is capped per side
+    let limits = ExposureLimits {
+        max_wallet_payout: 2_000,
+        ..ExposureLimits::default()
+    };
+    position.add_bet(2, 100, 300).unwrap();
+    assert_eq!(limits.check_position(&position, 1), Ok(()));
+    assert_eq!(
+        limits.check_position(&position, 2),
+        Err(ExchangeError::WalletPayoutLimitExceeded)
+    );
+    assert_eq!(
+        position.add_bet(2, 1, u64::MAX),
+        Err(ExchangeError::AmountOverflow)
+    );
+}
+
+#[test]
+fn test_market_exposure() {
+    let limits = ExposureLimits {
+        max_market_exposure_bps: 1_000,
+        ..ExposureLimits::default()
+    };
+    assert_eq!(limits.check_market_exposure(100, 1_000), Ok(()));
+    assert_eq!(
+        limits.check_market_exposure(101, 1_000),
+        Err(ExchangeError::MarketExposureLimitExceeded)
+    );
+    // Rounded down
+    assert_eq!(
+        limits.check_market_exposure(1, 9),
+        Err(ExchangeError::MarketExposureLimitExceeded)
+    );
+    assert_eq!(limits.check_market_exposure(0, 0), Ok(()));
+    assert_eq!(
+        limits.check_market_exposure(u64::MAX / 10, u64::MAX),
+        Ok(())
+    );
+}
+
+#[test]
+fn test_invalid_limits() {
+    let whole_pool = ExposureLimits {
+        max_market_exposure_bps: 10_000,
+        ..ExposureLimits::default()
+    };
+    assert_eq!(whole_pool.check(), Ok(()));
+    let above_pool = ExposureLimits {
+        max_market_exposure_bps: 10_001,
+        ..ExposureLimits::default()
+    };
+    assert_eq!(
+        above_pool.check(),
+        Err(ExchangeError::InvalidExposureLimits)
+    );
+    let min_above_max = ExposureLimits {
+        min_bet_risk: 11,
+        max_bet_risk: 10,
+        ..ExposureLimits::default()
+    };
+    assert_eq!(
+        min_above_max.check(),
+        Err(ExchangeError::InvalidExposureLimits)
+    );
+}
+
+#[test]
+fn test_pack_round_trip() {
+    let limits = ExposureLimits {
+        max_market_exposure_bps: 2_500,
+        max_side_payout: 1_000_000,
+        min_bet_risk: 1,
+        max_bet_risk: u64::MAX,
+        max_wallet_risk: 5_000_000,
+        max_wallet_payout: 7,
+    };
+    let mut packed = [0; ExposureLimits::LEN];
+    limits.pack_into_slice(&mut packed);
+    assert_eq!(&packed[..2], &2_500u16.to_le_bytes());
+    assert_eq!(ExposureLimits::unpack_from_slice(&packed), limits);
+}
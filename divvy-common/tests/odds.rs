@@ -0,0 +1,118 @@
+use divvycommon::odds::{
+    american_from_feed, american_to_decimal, implied_probability, payout_from_american,
+    payout_from_decimal, payout_from_implied_probability,
+};
+use fixed::types::U64F64;
+
+const RISK: u64 = 100_000_000;
+
+// American odds, decimal odds and implied probability as printed by bookmakers,
+// with the payout on 100 USDT risked.
+const BOOKMAKER_TABLE: [(i64, f64, f64, u64); 14] = [
+    (-1000, 1.10, 0.9091, 10_000_000),
+    (-500, 1.20, 0.8333, 20_000_000),
+    (-300, 1.3333, 0.75, 33_333_333),
+    (-250, 1.40, 0.7143, 40_000_000),
+    (-200, 1.50, 0.6667, 50_000_000),
+    (-150, 1.6667, 0.60, 66_666_666),
+    (-120, 1.8333, 0.5455, 83_333_333),
+    (-110, 1.9091, 0.5238, 90_909_090),
+    (-100, 2.00, 0.50, 100_000_000),
+    (100, 2.00, 0.50, 100_000_000),
+    (150, 2.50, 0.40, 150_000_000),
+    (250, 3.50, 0.2857, 250_000_000),
+    (500, 6.00, 0.1667, 500_000_000),
+    (1000, 11.00, 0.0909, 1_000_000_000),
+];
+
+fn assert_close(actual: U64F64, expected: f64) {
+    let actual = actual.to_num::<f64>();
+    assert!(
+        (actual - expected).abs() < 0.0001,
+        "{} is not close to {}",
+        actual,
+        expected
+    );
+}
+
+#[test]
+fn test_bookmaker_table() {
+    for &(american, decimal, probability, payout) in BOOKMAKER_TABLE.iter() {
+        assert_eq!(payout_from_american(american, RISK), Some(payout));
+        assert_close(american_to_decimal(american).unwrap(), decimal);
+        assert_close(implied_probability(american).unwrap(), probability);
+    }
+}
+
+#[test]
+fn test_invalid_american_odds() {
+    for &odds in [0, 1, 99, -1, -99, i64::MIN].iter() {
+        assert_eq!(payout_from_american(odds, RISK), None);
+        assert_eq!(american_to_decimal(odds), None);
+        assert_eq!(implied_probability(odds), None);
+    }
+}
+
+#[test]
+fn test_invalid_decimal_odds_and_probability() {
+    assert_eq!(payout_from_decimal(U64F64::from_num(0.99), RISK), None);
+    assert_eq!(
+        payout_from_implied_probability(U64F64::from_num(0), RISK),
+        None
+    );
+    assert_eq!(
+        payout_from_implied_probability(U64F64::from_num(1.01), RISK),
+        None
+    );
+    assert_eq!(payout_from_decimal(U64F64::from_num(1), RISK), Some(0));
+    assert_eq!(
+        payout_from_implied_probability(U64F64::from_num(1), RISK),
+        Some(0)
+    );
+}
+
+#[test]
+fn test_payout_overflow() {
+    assert_eq!(payout_from_american(200, u64::MAX), None);
+    assert_eq!(payout_from_american(-200, u64::MAX), Some(u64::MAX / 2));
+    assert_eq!(payout_from_decimal(U64F64::from_num(3), u64::MAX), None);
+}
+
+#[test]
+fn test_rounding_favors_house() {
+    for abs_odds in 100..=2000u64 {
+        for &odds in [abs_odds as i64, -(abs_odds as i64)].iter() {
+            for &risk in [1, 7, 999_999, RISK, 123_456_789_012].iter() {
+                // Exact payout is numerator / denominator
+                let (numerator, denominator) = if odds > 0 {
+                    (risk as u128 * abs_odds as u128, 100u128)
+                } else {
+                    (risk as u128 * 100, abs_odds as u128)
+                };
+                let payout = payout_from_american(odds, risk).unwrap();
+                assert_eq!(payout as u128, numerator / denominator);
+
+                let from_decimal =
+                    payout_from_decimal(american_to_decimal(odds).unwrap(), risk).unwrap();
+                let from_probability =
+                    payout_from_implied_probability(implied_probability(odds).unwrap(), risk)
+                        .unwrap();
+                assert!(from_decimal <= payout);
+                assert!(from_probability <= payout);
+                assert!(payout - from_decimal <= 1);
+                assert!(payout - from_probability <= 1);
+            }
+        }
+    }
+}
+
+#[test]
+fn test_american_from_feed() {
+    assert_eq!(american_from_feed(150.0), Some(150));
+    assert_eq!(american_from_feed(-110.0), Some(-110));
+    assert_eq!(american_from_feed(150.9), Some(150));
+    assert_eq!(american_from_feed(-110.2), Some(-111));
+    assert_eq!(american_from_feed(f64::NAN), None);
+    assert_eq!(american_from_feed(f64::INFINITY), None);
+    assert_eq!(american_from_feed(1e19), None);
+}
@@ -0,0 +1,72 @@
+use divvycommon::{
+    error::ExchangeError,
+    oracle::{read_pyth_price, OracleRound, PYTH_PRICE_LEN},
+};
+
+const NOW: i64 = 1_625_000_000;
+
+fn pyth_price(price: i64, expo: i32, timestamp: i64, num_qt: u32, status: u32) -> Vec<u8> {
+    let mut data = vec![0; PYTH_PRICE_LEN];
+    data[0..4].copy_from_slice(&0xa1b2c3d4u32.to_le_bytes());
+    data[4..8].copy_from_slice(&2u32.to_le_bytes());
+    data[8..12].copy_from_slice(&3u32.to_le_bytes());
+    data[20..24].copy_from_slice(&expo.to_le_bytes());
+    data[28..32].copy_from_slice(&num_qt.to_le_bytes());
+    data[96..104].copy_from_slice(&timestamp.to_le_bytes());
+    data[208..216].copy_from_slice(&price.to_le_bytes());
+    data[224..228].copy_from_slice(&status.to_le_bytes());
+    data
+}
+
+#[test]
+fn test_round_staleness() {
+    let round = OracleRound {
+        value: -110.0,
+        timestamp: NOW - 600,
+        responses: 1,
+    };
+    assert_eq!(round.check(NOW, 600, 0), Ok(-110.0));
+    assert_eq!(round.check(NOW, 599, 0), Err(ExchangeError::StaleFeed));
+    // Zero disables the staleness check
+    assert_eq!(round.check(NOW, 0, 0), Ok(-110.0));
+    // Rounds stamped ahead of the validator clock are not stale
+    assert_eq!(round.check(NOW - 3_600, 1, 0), Ok(-110.0));
+}
+
+#[test]
+fn test_round_min_responses() {
+    let round = OracleRound {
+        value: 1.0,
+        timestamp: NOW,
+        responses: 3,
+    };
+    assert_eq!(round.check(NOW, 600, 3), Ok(1.0));
+    assert_eq!(
+        round.check(NOW, 600, 4),
+        Err(ExchangeError::NotEnoughFeedResponses)
+    );
+}
+
+#[test]
+fn test_read_pyth_price() {
+    let round = read_pyth_price(&pyth_price(-11_000_000_000, -8, NOW, 7, 1)).unwrap();
+    assert_eq!(
+        round,
+        OracleRound {
+            value: -110.0,
+            timestamp: NOW,
+            responses: 7,
+        }
+    );
+    let round = read_pyth_price(&pyth_price(25, 1, NOW, 1, 1)).unwrap();
+    assert_eq!(round.value, 250.0);
+
+    // Not trading
+    assert!(read_pyth_price(&pyth_price(100, 0, NOW, 1, 2)).is_err());
+    // Not a price account
+    let mut data = pyth_price(100, 0, NOW, 1, 1);
+    data[8..12].copy_from_slice(&2u32.to_le_bytes());
+    assert!(read_pyth_price(&data).is_err());
+    // Truncated
+    assert!(read_pyth_price(&pyth_price(100, 0, NOW, 1, 1)[..PYTH_PRICE_LEN - 1]).is_err());
+}
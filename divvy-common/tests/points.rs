@@ -0,0 +1,139 @@
+use divvycommon::{
+    calculate_locked_liquidity, calculate_points_bettor_balance,
+    oracle::OracleSource,
+    points::{grade, points_from_feed, score_from_feed, Grade},
+    state::{BetType, Market, MarketSide, MoneylineMarketOutcome, LABEL_LEN, LEAGUE_LEN},
+};
+use solana_program::pubkey::Pubkey;
+
+/// A points market with `(risk, payout, line)` on its two sides.
+fn points_market(bet_type: BetType, sides: [(u64, u64, i64); 2]) -> Market {
+    let side = |(risk, payout, line): (u64, u64, i64)| MarketSide {
+        odds_feed_account: None,
+        points_feed_account: None,
+        payout,
+        risk,
+        line,
+        label: [0; LABEL_LEN],
+    };
+    Market {
+        is_initialized: true,
+        market_sides: [side(sides[0]), side(sides[1]), side((0, 0, 0))],
+        locked_liquidity: 0,
+        result_feed: Pubkey::default(),
+        result: MoneylineMarketOutcome::NotYetCommenced,
+        bettor_balance: sides[0].0 + sides[1].0,
+        pending_bets: 0,
+        bet_type,
+        oracle_source: OracleSource::SwitchboardV1,
+        max_staleness: 0,
+        min_responses: 0,
+        home_score: 0,
+        away_score: 0,
+        event_id: 0,
+        league: [0; LEAGUE_LEN],
+        start_time: 0,
+        suspended: false,
+    }
+}
+
+#[test]
+fn test_points_from_feed() {
+    assert_eq!(points_from_feed(-3.5), Some(-35));
+    assert_eq!(points_from_feed(45.5), Some(455));
+    assert_eq!(points_from_feed(3.0), Some(30));
+    // Feed noise below a tenth of a point is rounded away
+    assert_eq!(points_from_feed(-3.4999999), Some(-35));
+    assert_eq!(points_from_feed(f64::NAN), None);
+    assert_eq!(points_from_feed(1e9), None);
+}
+
+#[test]
+fn test_score_from_feed() {
+    assert_eq!(score_from_feed(24_017.0), Some((24, 17)));
+    assert_eq!(score_from_feed(0.0), Some((0, 0)));
+    assert_eq!(score_from_feed(105_099.0), Some((105, 99)));
+    assert_eq!(score_from_feed(-1.0), None);
+    assert_eq!(score_from_feed(24_017.5), None);
+    assert_eq!(score_from_feed(1e6), None);
+    assert_eq!(score_from_feed(f64::INFINITY), None);
+}
+
+#[test]
+fn test_spread_grading() {
+    // Home -3.5 and away +3.5
+    assert_eq!(grade(BetType::Spread, 0, -35, 24, 20), Grade::Win);
+    assert_eq!(grade(BetType::Spread, 0, -35, 24, 21), Grade::Loss);
+    assert_eq!(grade(BetType::Spread, 1, 35, 24, 20), Grade::Loss);
+    assert_eq!(grade(BetType::Spread, 1, 35, 24, 21), Grade::Win);
+    // Whole point lines push on the number
+    assert_eq!(grade(BetType::Spread, 0, -30, 24, 21), Grade::Push);
+    assert_eq!(grade(BetType::Spread, 1, 30, 24, 21), Grade::Push);
+    assert_eq!(grade(BetType::Spread, 1, 30, 20, 21), Grade::Win);
+    // Home underdog +7
+    assert_eq!(grade(BetType::Spread, 0, 70, 14, 20), Grade::Win);
+    assert_eq!(grade(BetType::Spread, 0, 70, 14, 21), Grade::Push);
+    assert_eq!(grade(BetType::Spread, 0, 70, 14, 22), Grade::Loss);
+}
+
+#[test]
+fn test_total_grading() {
+    // Over and under 45.5
+    assert_eq!(grade(BetType::Total, 0, 455, 24, 22), Grade::Win);
+    assert_eq!(grade(BetType::Total, 0, 455, 24, 21), Grade::Loss);
+    assert_eq!(grade(BetType::Total, 1, 455, 24, 22), Grade::Loss);
+    assert_eq!(grade(BetType::Total, 1, 455, 24, 21), Grade::Win);
+    // Over and under 45
+    assert_eq!(grade(BetType::Total, 0, 450, 24, 21), Grade::Push);
+    assert_eq!(grade(BetType::Total, 1, 450, 24, 21), Grade::Push);
+}
+
+#[test]
+fn test_grade_balance() {
+    assert_eq!(Grade::Win.balance(110, 100), Some(210));
+    assert_eq!(Grade::Push.balance(110, 100), Some(110));
+    assert_eq!(Grade::Loss.balance(110, 100), Some(0));
+    assert_eq!(Grade::Win.balance(u64::MAX, 1), None);
+}
+
+#[test]
+fn test_points_locked_liquidity() {
+    // Bets on one side only lock their payout
+    let market = points_market(BetType::Spread, [(110, 100, -35), (0, 0, 0)]);
+    assert_eq!(calculate_locked_liquidity(&market), Ok(100));
+
+    // Opposite sides of the same line cover each other
+    let market = points_market(BetType::Spread, [(220, 200, -35), (110, 100, 35)]);
+    assert_eq!(calculate_locked_liquidity(&market), Ok(90));
+    let market = points_market(BetType::Total, [(110, 100, 455), (110, 100, 455)]);
+    assert_eq!(calculate_locked_liquidity(&market), Ok(0));
+
+    // Home -3.5 and away +7.5 both win when home wins by 4 to 7
+    let market = points_market(BetType::Spread, [(110, 100, -35), (110, 100, 75)]);
+    assert_eq!(calculate_locked_liquidity(&market), Ok(200));
+    // Over 44.5 and under 47.5 both win on 45 to 47 points
+    let market = points_market(BetType::Total, [(110, 100, 445), (110, 100, 475)]);
+    assert_eq!(calculate_locked_liquidity(&market), Ok(200));
+
+    // A push returns the risk without a payout
+    let market = points_market(BetType::Spread, [(110, 100, -30), (110, 100, 30)]);
+    assert_eq!(calculate_locked_liquidity(&market), Ok(0));
+    let market = points_market(BetType::Spread, [(110, 100, -30), (110, 100, 40)]);
+    assert_eq!(calculate_locked_liquidity(&market), Ok(100));
+}
+
+#[test]
+fn test_points_bettor_balance() {
+    let market = points_market(BetType::Spread, [(110, 100, -35), (100, 100, 35)]);
+    assert_eq!(calculate_points_bettor_balance(&market, 24, 20), Ok(210));
+    assert_eq!(calculate_points_bettor_balance(&market, 24, 21), Ok(200));
+
+    // Both sides push on a whole point line
+    let market = points_market(BetType::Spread, [(110, 100, -30), (100, 100, 30)]);
+    assert_eq!(calculate_points_bettor_balance(&market, 24, 21), Ok(210));
+
+    let market = points_market(BetType::Total, [(110, 100, 450), (100, 100, 450)]);
+    assert_eq!(calculate_points_bettor_balance(&market, 24, 22), Ok(210));
+    assert_eq!(calculate_points_bettor_balance(&market, 24, 21), Ok(210));
+    assert_eq!(calculate_points_bettor_balance(&market, 20, 21), Ok(200));
+}
@@ -0,0 +1,101 @@
+use divvycommon::{
+    error::ExchangeError,
+    oracle::OracleSource,
+    pack_text,
+    state::{BetType, Market, MarketSide, MoneylineMarketOutcome, Position, LABEL_LEN, LEAGUE_LEN},
+    unpack_text,
+};
+use solana_program::{program_pack::Pack, pubkey::Pubkey};
+
+#[test]
+fn test_text_round_trip() {
+    let label: [u8; LABEL_LEN] = pack_text("Boston Celtics").unwrap();
+    assert_eq!(unpack_text(&label), Some("Boston Celtics"));
+    let league: [u8; LEAGUE_LEN] = pack_text("NBA").unwrap();
+    assert_eq!(&league[..4], b"NBA\0");
+    assert_eq!(unpack_text(&league), Some("NBA"));
+
+    let empty: [u8; LEAGUE_LEN] = pack_text("").unwrap();
+    assert_eq!(unpack_text(&empty), Some(""));
+    let full: [u8; LEAGUE_LEN] = pack_text("EREDIVIS").unwrap();
+    assert_eq!(unpack_text(&full), Some("EREDIVIS"));
+    assert_eq!(
+        pack_text::<[u8; LEAGUE_LEN]>("EREDIVISIE"),
+        Err(ExchangeError::InvalidInstruction)
+    );
+    assert_eq!(unpack_text(&[0xff, 0xfe, 0]), None);
+}
+
+#[test]
+fn test_market_keeps_event_metadata() {
+    let side = |label| MarketSide {
+        odds_feed_account: Some(Pubkey::new_unique()),
+        points_feed_account: None,
+        payout: 0,
+        risk: 0,
+        line: 0,
+        label: pack_text(label).unwrap(),
+    };
+    let market = Market {
+        is_initialized: true,
+        market_sides: [side("Arsenal"), side("Chelsea"), side("Draw")],
+        locked_liquidity: 0,
+        result_feed: Pubkey::new_unique(),
+        result: MoneylineMarketOutcome::NotYetCommenced,
+        bettor_balance: 0,
+        pending_bets: 0,
+        bet_type: BetType::MoneyLine,
+        oracle_source: OracleSource::Manual,
+        max_staleness: 0,
+        min_responses: 0,
+        home_score: 0,
+        away_score: 0,
+        event_id: 31_337,
+        league: pack_text("EPL").unwrap(),
+        start_time: 1_660_000_000,
+        suspended: false,
+    };
+    let mut data = vec![0; Market::LEN];
+    Market::pack(market, &mut data).unwrap();
+
+    let unpacked = Market::unpack(&data).unwrap();
+    assert_eq!(unpacked.event_id, 31_337);
+    assert_eq!(unpack_text(&unpacked.league), Some("EPL"));
+    assert_eq!(unpacked.start_time, 1_660_000_000);
+    let labels: Vec<_> = unpacked
+        .market_sides
+        .iter()
+        .map(|side| unpack_text(&side.label).unwrap())
+        .collect();
+    assert_eq!(labels, ["Arsenal", "Chelsea", "Draw"]);
+
+    // Betting closes the cutoff before the start time
+    assert_eq!(unpacked.bet_cutoff(0), 1_660_000_000);
+    assert_eq!(unpacked.bet_cutoff(600), 1_659_999_400);
+    assert_eq!(unpacked.bet_cutoff(u64::MAX), 1_660_000_000 - i64::MAX);
+}
+
+#[test]
+fn test_position_round_trip() {
+    let market = Pubkey::new_unique();
+    let user = Pubkey::new_unique();
+    let mut position = Position {
+        is_initialized: true,
+        market,
+        user_main_account: user,
+        risk: [0; 3],
+        payout: [0; 3],
+    };
+    position.add_bet(0, 110, 100).unwrap();
+    position.add_bet(2, 50, 150).unwrap();
+    position.add_bet(0, 220, 200).unwrap();
+    let mut data = vec![0; Position::LEN];
+    Position::pack(position, &mut data).unwrap();
+
+    let unpacked = Position::unpack(&data).unwrap();
+    assert_eq!(unpacked.market, market);
+    assert_eq!(unpacked.user_main_account, user);
+    assert_eq!(unpacked.risk, [330, 0, 50]);
+    assert_eq!(unpacked.payout, [300, 0, 150]);
+    assert_eq!(unpacked.total_risk(), Ok(380));
+}
@@ -10,6 +10,7 @@ export const payerAccount = Keypair.fromSecretKey(Uint8Array.from(JSON.parse(fs.
 const bool = (property = "bool") => {
     return blob(1, property);
 };
+const BETTING_PROGRAM_ID = new PublicKey("2u9dGXkuj5iTm6B9MRn1Exx55uEYX1ZmM3xda5S3f1oY")
 const bet_usdt_account = new PublicKey("E8uax62mJk8s8idaLh8zXRKEsD2VVEAtL8SUEdet9Wbd")
 const usdt = new PublicKey("7cnY6yuFXzTLEsnXn4FkgvmXq4FyuUakQDQqHJkbQvYG")
 const ht_mint = new PublicKey("CNz7u64CYdE7ZZeYasuaeCbEWx2wz6Lg8WH3Xaa25X1A")
@@ -25,6 +26,17 @@ export const STATE_ACCOUNT_DATA_LAYOUT = struct([
     blob(32, "bettingUsdt"),
     blob(32, "poolUsdt"),
     bool("frozenPool"),
+    blob(32, "authority"),
+    blob(33, "pendingAuthority"),
+    blob(512, "roles"),
+    blob(32, "queueHt"),
+    uint64("queuedHt"),
+    uint64("queuedLiquidity"),
+    uint64("queueHead"),
+    uint64("queueTail"),
+    uint64("committedLiquidity"),
+    blob(32, "bettingProgram"),
+    blob(8, "realizedPnl"),
 ]);
 
 const INIT_PROGRAM_LAYOUT = struct([
@@ -72,6 +84,8 @@ const main = async () => {
 
     const pool_usdt_account = await createTokenAccount(payerAccount, usdt, pda, connection)
     console.log("HP USDT ACCOUNT:", pool_usdt_account.toString());
+    const queue_ht_account = await createTokenAccount(payerAccount, ht_mint, pda, connection)
+    console.log("HP WITHDRAWAL QUEUE HT ACCOUNT:", queue_ht_account.toString());
     const dataBuffer = Buffer.alloc(INIT_PROGRAM_LAYOUT.span);
     INIT_PROGRAM_LAYOUT.encode(data, dataBuffer);
     const initProgramInstruction = new TransactionInstruction({
@@ -81,6 +95,8 @@ const main = async () => {
             { pubkey: ht_mint, isSigner: false, isWritable: true },
             { pubkey: bet_usdt_account, isSigner: false, isWritable: true },
             { pubkey: pool_usdt_account, isSigner: false, isWritable: true },
+            { pubkey: queue_ht_account, isSigner: false, isWritable: false },
+            { pubkey: BETTING_PROGRAM_ID, isSigner: false, isWritable: false },
         ],
         programId: DIVVY_PROGRAM_ID,
         data: dataBuffer,
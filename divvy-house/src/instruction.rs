@@ -1,31 +1,176 @@
-use solana_program::program_error::ProgramError;
-use std::{convert::TryInto};
+use divvycommon::pda::{find_ledger_address, find_withdrawal_address};
+use solana_program::{
+    instruction::{AccountMeta, Instruction},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    system_program,
+};
+use std::{convert::TryInto, mem::size_of};
 
 use crate::{
     error::ExchangeError::{self, InvalidInstruction},
+    state::Role,
 };
 
+#[derive(Clone, Debug, PartialEq)]
 pub enum HouseInstruction {
+    /// Deposits USDT into the house pool and mints house tokens to the user.
+    ///
+    ///   0. `[signer]` The user depositing USDT.
+    ///   1. `[writable]` The house token mint.
+    ///   2. `[]` The token program.
+    ///   3. `[writable]` The user's house token account.
+    ///   4. `[]` The house program PDA, mint authority of the house token.
+    ///   5. `[writable]` The user's USDT account.
+    ///   6. `[writable]` The house pool USDT account.
+    ///   7. `[]` The house pool state account.
     Deposit {
         /// The amount party A expects to receive of token Y
         usdt_amount: u64,
         bump_seed: u8,
     },
+    /// Burns house tokens and pays the user their share of the house pool.
+    ///
+    ///   0. `[signer]` The user withdrawing.
+    ///   1. `[writable]` The house token mint.
+    ///   2. `[]` The token program.
+    ///   3. `[writable]` The user's house token account.
+    ///   4. `[]` The house program PDA, owner of the house pool USDT account.
+    ///   5. `[writable]` The user's USDT account.
+    ///   6. `[writable]` The house pool USDT account.
+    ///   7. `[]` The house pool state account.
     Withdraw {
         /// the amount the taker expects to be paid in the other token, as a u64 because that's the max possible supply of a token
         ht_amount: u64,
         bump_seed: u8,
     },
-    Ownership {
-        bump_seed: u8,
-    },
-    Freeze {
-        freeze_pool: bool,
+    /// Initializes the house pool state account.
+    ///
+    ///   0. `[signer]` The authority.
+    ///   1. `[writable]` The rent exempt house pool state account.
+    ///   2. `[]` The house token mint.
+    ///   3. `[]` The betting pool USDT account.
+    ///   4. `[]` The house pool USDT account.
+    ///   5. `[]` The withdrawal queue house token account, owned by the house program PDA.
+    ///   6. `[]` The betting program, whose PDA owns the betting pool USDT account.
+    Ownership { bump_seed: u8 },
+    /// Freezes or unfreezes the house pool.
+    ///
+    ///   0. `[signer]` The authority or a pauser, or their multisig account.
+    ///   1. `[writable]` The house pool state account.
+    ///   2. ..2+M `[signer]` The multisig signers, if the signer is a multisig.
+    Freeze { freeze_pool: bool },
+    /// Moves a market's locked liquidity from the house pool to the betting
+    /// pool. Called by the betting program when a market commences, signed by
+    /// the PDA of the betting program stored in the house pool state.
+    ///
+    ///   0. `[]` The token program.
+    ///   1. `[]` The house program PDA, owner of the house pool USDT account.
+    ///   2. `[signer]` The betting program PDA.
+    ///   3. `[writable]` The betting pool USDT account.
+    ///   4. `[writable]` The house pool USDT account.
+    ///   5. `[writable]` The house pool state account.
+    ///   6. `[writable, signer]` The account commencing the market, pays the ledger rent.
+    ///   7. `[]` The market, owned by the betting program.
+    ///   8. `[writable]` The market's ledger account, at `find_ledger_address` of the pool and the market.
+    ///   9. `[]` The system program.
+    TransferLockedLiquidity { usdt_amount: u64, bump_seed: u8 },
+    /// Proposes a new authority for the house pool. It takes over once it
+    /// accepts.
+    ///
+    ///   0. `[signer]` The authority, or its multisig account.
+    ///   1. `[writable]` The house pool state account.
+    ///   2. ..2+M `[signer]` The multisig signers, if the authority is a multisig.
+    ProposeAuthority { new_authority: Pubkey },
+    /// Makes the proposed authority the authority of the house pool.
+    ///
+    ///   0. `[signer]` The proposed authority, or its multisig account.
+    ///   1. `[writable]` The house pool state account.
+    ///   2. ..2+M `[signer]` The multisig signers, if the authority is a multisig.
+    AcceptAuthority,
+    /// Initializes a multisig account that can act as the authority.
+    ///
+    ///   0. `[writable]` The rent exempt multisig account, owned by the program.
+    ///   1. ..1+N `[]` The signers, 1 to 11 of them.
+    InitMultisig {
+        /// The number of signers required.
+        m: u8,
     },
-    TransferLockedLiquidity {
-        usdt_amount: u64,
-        bump_seed: u8,
-    }
+    /// Grants a role to a key, which may be a multisig. Only pausers
+    /// have an instruction in the house program.
+    ///
+    ///   0. `[signer]` The authority, or its multisig account.
+    ///   1. `[writable]` The house pool state account.
+    ///   2. ..2+M `[signer]` The multisig signers, if the authority is a multisig.
+    GrantRole { role: Role, member: Pubkey },
+    /// Revokes a role from a key.
+    ///
+    ///   0. `[signer]` The authority, or its multisig account.
+    ///   1. `[writable]` The house pool state account.
+    ///   2. ..2+M `[signer]` The multisig signers, if the authority is a multisig.
+    RevokeRole { role: Role, member: Pubkey },
+    /// Escrows house tokens at the tail of the withdrawal queue, for when the
+    /// house pool can not pay them out yet. The USDT they are worth at the
+    /// share price now is held back from new bets and withdrawals, but they
+    /// are paid at the share price when the request is filled.
+    ///
+    ///   0. `[writable, signer]` The user withdrawing, pays the request rent.
+    ///   1. `[writable]` The withdrawal request account, at `find_withdrawal_address` of the queue tail.
+    ///   2. `[writable]` The user's house token account.
+    ///   3. `[writable]` The withdrawal queue house token account.
+    ///   4. `[]` The house token mint.
+    ///   5. `[]` The house pool USDT account.
+    ///   6. `[writable]` The house pool state account.
+    ///   7. `[]` The token program.
+    ///   8. `[]` The system program.
+    RequestWithdrawal { ht_amount: u64 },
+    /// Fills the request at the head of the withdrawal queue at the share
+    /// price once the house pool holds its USDT, burning its house tokens and
+    /// closing it. Anyone may call it.
+    ///
+    ///   0. `[writable]` The withdrawal request account, at `find_withdrawal_address` of the queue head.
+    ///   1. `[writable]` The requester's main account, receives the request rent.
+    ///   2. `[writable]` A USDT account of the requester.
+    ///   3. `[writable]` The withdrawal queue house token account.
+    ///   4. `[writable]` The house token mint.
+    ///   5. `[writable]` The house pool USDT account.
+    ///   6. `[writable]` The house pool state account.
+    ///   7. `[]` The house program PDA, owner of the house pool USDT and withdrawal queue accounts.
+    ///   8. `[]` The token program.
+    FillWithdrawal { bump_seed: u8 },
+    /// Returns what is left of the liquidity lent to a market, with the house
+    /// share of its profit or less its loss, from the betting pool to the
+    /// house pool and records it in the market's ledger. Called by the
+    /// betting program when a commenced market settles or is voided, signed
+    /// by its PDA like `TransferLockedLiquidity`. Once the market settled,
+    /// what it kept for bets graded below their side's line is returned as
+    /// they settle, adding to the market's profit.
+    ///
+    ///   0. `[signer]` The betting program PDA, owner of the betting pool USDT account.
+    ///   1. `[writable]` The house pool state account.
+    ///   2. `[writable]` The market's ledger account.
+    ///   3. `[writable]` The betting pool USDT account.
+    ///   4. `[writable]` The house pool USDT account.
+    ///   5. `[]` The token program.
+    ReturnLiquidity { usdt_amount: u64 },
+    /// Logs the house tokens a `Deposit` of `usdt_amount` would mint now,
+    /// with the pool value and house token supply the share price is taken
+    /// from. Fails like the deposit would. Changes nothing, meant to be
+    /// simulated.
+    ///
+    ///   0. `[]` The house token mint.
+    ///   1. `[]` The house pool USDT account.
+    ///   2. `[]` The house pool state account.
+    QuoteDeposit { usdt_amount: u64 },
+    /// Logs the USDT a `Withdraw` of `ht_amount` would pay now, with the
+    /// pool value and house token supply the share price is taken from.
+    /// Fails like the withdrawal would. Changes nothing, meant to be
+    /// simulated.
+    ///
+    ///   0. `[]` The house token mint.
+    ///   1. `[]` The house pool USDT account.
+    ///   2. `[]` The house pool state account.
+    QuoteWithdraw { ht_amount: u64 },
 }
 
 impl HouseInstruction {
@@ -40,26 +185,141 @@ impl HouseInstruction {
                 ht_amount: Self::unpack_amount(rest)?,
                 bump_seed: Self::unpack_last(rest)?,
             },
-            
+
             2 => Self::Ownership {
                 bump_seed: Self::unpack_last(rest)?,
             },
             3 => {
-                let (freeze_pool, rest) = rest
+                let (freeze_pool, _rest) = rest
                     .split_first()
                     .ok_or(ExchangeError::InvalidInstruction)?;
                 Self::Freeze {
                     freeze_pool: *freeze_pool != 0,
                 }
-            },
-            4 => Self::TransferLockedLiquidity{
+            }
+            4 => Self::TransferLockedLiquidity {
                 usdt_amount: Self::unpack_amount(rest)?,
                 bump_seed: Self::unpack_last(rest)?,
             },
+            5 => Self::ProposeAuthority {
+                new_authority: Self::unpack_pubkey(rest)?,
+            },
+            6 => Self::AcceptAuthority,
+            7 => {
+                let (m, _rest) = rest.split_first().ok_or(InvalidInstruction)?;
+                Self::InitMultisig { m: *m }
+            }
+            8 => {
+                let (role, rest) = rest.split_first().ok_or(InvalidInstruction)?;
+                Self::GrantRole {
+                    role: Role::unpack(role)?,
+                    member: Self::unpack_pubkey(rest)?,
+                }
+            }
+            9 => {
+                let (role, rest) = rest.split_first().ok_or(InvalidInstruction)?;
+                Self::RevokeRole {
+                    role: Role::unpack(role)?,
+                    member: Self::unpack_pubkey(rest)?,
+                }
+            }
+            10 => Self::RequestWithdrawal {
+                ht_amount: Self::unpack_amount(rest)?,
+            },
+            11 => Self::FillWithdrawal {
+                bump_seed: Self::unpack_last(rest)?,
+            },
+            12 => Self::ReturnLiquidity {
+                usdt_amount: Self::unpack_amount(rest)?,
+            },
+            13 => Self::QuoteDeposit {
+                usdt_amount: Self::unpack_amount(rest)?,
+            },
+            14 => Self::QuoteWithdraw {
+                ht_amount: Self::unpack_amount(rest)?,
+            },
             _ => return Err(InvalidInstruction.into()),
         })
     }
 
+    pub fn pack(&self) -> Vec<u8> {
+        let mut buf = Vec::with_capacity(size_of::<Self>());
+        match self {
+            Self::Deposit {
+                usdt_amount,
+                bump_seed,
+            } => {
+                buf.push(0);
+                buf.extend_from_slice(&usdt_amount.to_le_bytes());
+                buf.push(*bump_seed);
+            }
+            Self::Withdraw {
+                ht_amount,
+                bump_seed,
+            } => {
+                buf.push(1);
+                buf.extend_from_slice(&ht_amount.to_le_bytes());
+                buf.push(*bump_seed);
+            }
+            Self::Ownership { bump_seed } => {
+                buf.push(2);
+                buf.push(*bump_seed);
+            }
+            Self::Freeze { freeze_pool } => {
+                buf.push(3);
+                buf.push(*freeze_pool as u8);
+            }
+            Self::TransferLockedLiquidity {
+                usdt_amount,
+                bump_seed,
+            } => {
+                buf.push(4);
+                buf.extend_from_slice(&usdt_amount.to_le_bytes());
+                buf.push(*bump_seed);
+            }
+            Self::ProposeAuthority { new_authority } => {
+                buf.push(5);
+                buf.extend_from_slice(new_authority.as_ref());
+            }
+            Self::AcceptAuthority => buf.push(6),
+            Self::InitMultisig { m } => {
+                buf.push(7);
+                buf.push(*m);
+            }
+            Self::GrantRole { role, member } => {
+                buf.push(8);
+                buf.push(role.pack());
+                buf.extend_from_slice(member.as_ref());
+            }
+            Self::RevokeRole { role, member } => {
+                buf.push(9);
+                buf.push(role.pack());
+                buf.extend_from_slice(member.as_ref());
+            }
+            Self::RequestWithdrawal { ht_amount } => {
+                buf.push(10);
+                buf.extend_from_slice(&ht_amount.to_le_bytes());
+            }
+            Self::FillWithdrawal { bump_seed } => {
+                buf.push(11);
+                buf.push(*bump_seed);
+            }
+            Self::ReturnLiquidity { usdt_amount } => {
+                buf.push(12);
+                buf.extend_from_slice(&usdt_amount.to_le_bytes());
+            }
+            Self::QuoteDeposit { usdt_amount } => {
+                buf.push(13);
+                buf.extend_from_slice(&usdt_amount.to_le_bytes());
+            }
+            Self::QuoteWithdraw { ht_amount } => {
+                buf.push(14);
+                buf.extend_from_slice(&ht_amount.to_le_bytes());
+            }
+        };
+        buf
+    }
+
     // Todo: delete these 4 methods and use split_first, like in spl-token/instruction.rs
     fn unpack_last(input: &[u8]) -> Result<u8, ProgramError> {
         let (last, _rest) = input.split_last().ok_or(InvalidInstruction)?;
@@ -73,5 +333,461 @@ impl HouseInstruction {
             .ok_or(InvalidInstruction)?;
         Ok(amount)
     }
-   
+    fn unpack_pubkey(input: &[u8]) -> Result<Pubkey, ProgramError> {
+        let key = input.get(..32).map(Pubkey::new).ok_or(InvalidInstruction)?;
+        Ok(key)
+    }
+}
+
+/// Creates a `Deposit` instruction.
+pub fn deposit(
+    program_id: &Pubkey,
+    user: &Pubkey,
+    ht_mint: &Pubkey,
+    user_ht_account: &Pubkey,
+    pda: &Pubkey,
+    user_usdt_account: &Pubkey,
+    pool_usdt_account: &Pubkey,
+    pool_state: &Pubkey,
+    usdt_amount: u64,
+    bump_seed: u8,
+) -> Result<Instruction, ProgramError> {
+    let data = HouseInstruction::Deposit {
+        usdt_amount,
+        bump_seed,
+    }
+    .pack();
+
+    let accounts = vec![
+        AccountMeta::new_readonly(*user, true),
+        AccountMeta::new(*ht_mint, false),
+        AccountMeta::new_readonly(spl_token::id(), false),
+        AccountMeta::new(*user_ht_account, false),
+        AccountMeta::new_readonly(*pda, false),
+        AccountMeta::new(*user_usdt_account, false),
+        AccountMeta::new(*pool_usdt_account, false),
+        AccountMeta::new_readonly(*pool_state, false),
+    ];
+
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data,
+    })
+}
+
+/// Creates a `Withdraw` instruction.
+pub fn withdraw(
+    program_id: &Pubkey,
+    user: &Pubkey,
+    ht_mint: &Pubkey,
+    user_ht_account: &Pubkey,
+    pda: &Pubkey,
+    user_usdt_account: &Pubkey,
+    pool_usdt_account: &Pubkey,
+    pool_state: &Pubkey,
+    ht_amount: u64,
+    bump_seed: u8,
+) -> Result<Instruction, ProgramError> {
+    let data = HouseInstruction::Withdraw {
+        ht_amount,
+        bump_seed,
+    }
+    .pack();
+
+    let accounts = vec![
+        AccountMeta::new_readonly(*user, true),
+        AccountMeta::new(*ht_mint, false),
+        AccountMeta::new_readonly(spl_token::id(), false),
+        AccountMeta::new(*user_ht_account, false),
+        AccountMeta::new_readonly(*pda, false),
+        AccountMeta::new(*user_usdt_account, false),
+        AccountMeta::new(*pool_usdt_account, false),
+        AccountMeta::new_readonly(*pool_state, false),
+    ];
+
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data,
+    })
+}
+
+/// Creates a `QuoteDeposit` instruction.
+pub fn quote_deposit(
+    program_id: &Pubkey,
+    ht_mint: &Pubkey,
+    pool_usdt_account: &Pubkey,
+    pool_state: &Pubkey,
+    usdt_amount: u64,
+) -> Result<Instruction, ProgramError> {
+    let data = HouseInstruction::QuoteDeposit { usdt_amount }.pack();
+
+    let accounts = vec![
+        AccountMeta::new_readonly(*ht_mint, false),
+        AccountMeta::new_readonly(*pool_usdt_account, false),
+        AccountMeta::new_readonly(*pool_state, false),
+    ];
+
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data,
+    })
+}
+
+/// Creates a `QuoteWithdraw` instruction.
+pub fn quote_withdraw(
+    program_id: &Pubkey,
+    ht_mint: &Pubkey,
+    pool_usdt_account: &Pubkey,
+    pool_state: &Pubkey,
+    ht_amount: u64,
+) -> Result<Instruction, ProgramError> {
+    let data = HouseInstruction::QuoteWithdraw { ht_amount }.pack();
+
+    let accounts = vec![
+        AccountMeta::new_readonly(*ht_mint, false),
+        AccountMeta::new_readonly(*pool_usdt_account, false),
+        AccountMeta::new_readonly(*pool_state, false),
+    ];
+
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data,
+    })
+}
+
+/// Creates an `Ownership` instruction.
+pub fn ownership(
+    program_id: &Pubkey,
+    initializer: &Pubkey,
+    pool_state: &Pubkey,
+    ht_mint: &Pubkey,
+    betting_usdt_account: &Pubkey,
+    pool_usdt_account: &Pubkey,
+    queue_ht_account: &Pubkey,
+    betting_program_id: &Pubkey,
+    bump_seed: u8,
+) -> Result<Instruction, ProgramError> {
+    let data = HouseInstruction::Ownership { bump_seed }.pack();
+
+    let accounts = vec![
+        AccountMeta::new_readonly(*initializer, true),
+        AccountMeta::new(*pool_state, false),
+        AccountMeta::new_readonly(*ht_mint, false),
+        AccountMeta::new_readonly(*betting_usdt_account, false),
+        AccountMeta::new_readonly(*pool_usdt_account, false),
+        AccountMeta::new_readonly(*queue_ht_account, false),
+        AccountMeta::new_readonly(*betting_program_id, false),
+    ];
+
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data,
+    })
+}
+
+/// Creates a `Freeze` instruction. `signers` are the multisig signers, empty
+/// if the signer is a wallet.
+pub fn freeze(
+    program_id: &Pubkey,
+    initializer: &Pubkey,
+    pool_state: &Pubkey,
+    freeze_pool: bool,
+    signers: &[&Pubkey],
+) -> Result<Instruction, ProgramError> {
+    let data = HouseInstruction::Freeze { freeze_pool }.pack();
+
+    let mut accounts = vec![
+        AccountMeta::new_readonly(*initializer, signers.is_empty()),
+        AccountMeta::new(*pool_state, false),
+    ];
+    for signer in signers.iter() {
+        accounts.push(AccountMeta::new_readonly(**signer, true));
+    }
+
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data,
+    })
+}
+
+/// Creates a `TransferLockedLiquidity` instruction for the ledger of
+/// `market`. The betting program PDA must sign it through `invoke_signed`.
+pub fn transfer_locked_liquidity(
+    program_id: &Pubkey,
+    pda: &Pubkey,
+    bet_pda: &Pubkey,
+    betting_usdt_account: &Pubkey,
+    pool_usdt_account: &Pubkey,
+    pool_state: &Pubkey,
+    payer: &Pubkey,
+    market: &Pubkey,
+    usdt_amount: u64,
+    bump_seed: u8,
+) -> Result<Instruction, ProgramError> {
+    let data = HouseInstruction::TransferLockedLiquidity {
+        usdt_amount,
+        bump_seed,
+    }
+    .pack();
+    let (ledger, _) = find_ledger_address(program_id, pool_state, market);
+
+    let accounts = vec![
+        AccountMeta::new_readonly(spl_token::id(), false),
+        AccountMeta::new_readonly(*pda, false),
+        AccountMeta::new_readonly(*bet_pda, true),
+        AccountMeta::new(*betting_usdt_account, false),
+        AccountMeta::new(*pool_usdt_account, false),
+        AccountMeta::new(*pool_state, false),
+        AccountMeta::new(*payer, true),
+        AccountMeta::new_readonly(*market, false),
+        AccountMeta::new(ledger, false),
+        AccountMeta::new_readonly(system_program::id(), false),
+    ];
+
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data,
+    })
+}
+
+/// Creates a `ReturnLiquidity` instruction for the ledger of `market`.
+pub fn return_liquidity(
+    program_id: &Pubkey,
+    bet_pda: &Pubkey,
+    pool_state: &Pubkey,
+    market: &Pubkey,
+    betting_usdt_account: &Pubkey,
+    pool_usdt_account: &Pubkey,
+    usdt_amount: u64,
+) -> Result<Instruction, ProgramError> {
+    let data = HouseInstruction::ReturnLiquidity { usdt_amount }.pack();
+    let (ledger, _) = find_ledger_address(program_id, pool_state, market);
+
+    let accounts = vec![
+        AccountMeta::new_readonly(*bet_pda, true),
+        AccountMeta::new(*pool_state, false),
+        AccountMeta::new(ledger, false),
+        AccountMeta::new(*betting_usdt_account, false),
+        AccountMeta::new(*pool_usdt_account, false),
+        AccountMeta::new_readonly(spl_token::id(), false),
+    ];
+
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data,
+    })
+}
+
+/// Creates a `ProposeAuthority` instruction. `signers` are the multisig
+/// signers, empty if the authority is a wallet.
+pub fn propose_authority(
+    program_id: &Pubkey,
+    authority: &Pubkey,
+    pool_state: &Pubkey,
+    new_authority: &Pubkey,
+    signers: &[&Pubkey],
+) -> Result<Instruction, ProgramError> {
+    let data = HouseInstruction::ProposeAuthority {
+        new_authority: *new_authority,
+    }
+    .pack();
+
+    let mut accounts = vec![
+        AccountMeta::new_readonly(*authority, signers.is_empty()),
+        AccountMeta::new(*pool_state, false),
+    ];
+    for signer in signers.iter() {
+        accounts.push(AccountMeta::new_readonly(**signer, true));
+    }
+
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data,
+    })
+}
+
+/// Creates an `AcceptAuthority` instruction. `signers` are the multisig
+/// signers, empty if the proposed authority is a wallet.
+pub fn accept_authority(
+    program_id: &Pubkey,
+    pending_authority: &Pubkey,
+    pool_state: &Pubkey,
+    signers: &[&Pubkey],
+) -> Result<Instruction, ProgramError> {
+    let data = HouseInstruction::AcceptAuthority.pack();
+
+    let mut accounts = vec![
+        AccountMeta::new_readonly(*pending_authority, signers.is_empty()),
+        AccountMeta::new(*pool_state, false),
+    ];
+    for signer in signers.iter() {
+        accounts.push(AccountMeta::new_readonly(**signer, true));
+    }
+
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data,
+    })
+}
+
+/// Creates an `InitMultisig` instruction.
+pub fn init_multisig(
+    program_id: &Pubkey,
+    multisig: &Pubkey,
+    signers: &[&Pubkey],
+    m: u8,
+) -> Result<Instruction, ProgramError> {
+    let data = HouseInstruction::InitMultisig { m }.pack();
+
+    let mut accounts = vec![AccountMeta::new(*multisig, false)];
+    for signer in signers.iter() {
+        accounts.push(AccountMeta::new_readonly(**signer, false));
+    }
+
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data,
+    })
+}
+
+/// Creates a `GrantRole` instruction. `signers` are the multisig signers,
+/// empty if the authority is a wallet.
+pub fn grant_role(
+    program_id: &Pubkey,
+    authority: &Pubkey,
+    pool_state: &Pubkey,
+    role: Role,
+    member: &Pubkey,
+    signers: &[&Pubkey],
+) -> Result<Instruction, ProgramError> {
+    let data = HouseInstruction::GrantRole {
+        role,
+        member: *member,
+    }
+    .pack();
+
+    let mut accounts = Vec::with_capacity(2 + signers.len());
+    accounts.push(AccountMeta::new_readonly(*authority, signers.is_empty()));
+    accounts.push(AccountMeta::new(*pool_state, false));
+    for signer in signers.iter() {
+        accounts.push(AccountMeta::new_readonly(**signer, true));
+    }
+
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data,
+    })
+}
+
+/// Creates a `RevokeRole` instruction. `signers` are the multisig signers,
+/// empty if the authority is a wallet.
+pub fn revoke_role(
+    program_id: &Pubkey,
+    authority: &Pubkey,
+    pool_state: &Pubkey,
+    role: Role,
+    member: &Pubkey,
+    signers: &[&Pubkey],
+) -> Result<Instruction, ProgramError> {
+    let data = HouseInstruction::RevokeRole {
+        role,
+        member: *member,
+    }
+    .pack();
+
+    let mut accounts = Vec::with_capacity(2 + signers.len());
+    accounts.push(AccountMeta::new_readonly(*authority, signers.is_empty()));
+    accounts.push(AccountMeta::new(*pool_state, false));
+    for signer in signers.iter() {
+        accounts.push(AccountMeta::new_readonly(**signer, true));
+    }
+
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data,
+    })
+}
+
+/// Creates a `RequestWithdrawal` instruction for the request at `sequence`,
+/// the tail of the queue.
+pub fn request_withdrawal(
+    program_id: &Pubkey,
+    user: &Pubkey,
+    user_ht_account: &Pubkey,
+    queue_ht_account: &Pubkey,
+    ht_mint: &Pubkey,
+    pool_usdt_account: &Pubkey,
+    pool_state: &Pubkey,
+    sequence: u64,
+    ht_amount: u64,
+) -> Result<Instruction, ProgramError> {
+    let data = HouseInstruction::RequestWithdrawal { ht_amount }.pack();
+    let (request, _) = find_withdrawal_address(program_id, pool_state, sequence);
+
+    let accounts = vec![
+        AccountMeta::new(*user, true),
+        AccountMeta::new(request, false),
+        AccountMeta::new(*user_ht_account, false),
+        AccountMeta::new(*queue_ht_account, false),
+        AccountMeta::new_readonly(*ht_mint, false),
+        AccountMeta::new_readonly(*pool_usdt_account, false),
+        AccountMeta::new(*pool_state, false),
+        AccountMeta::new_readonly(spl_token::id(), false),
+        AccountMeta::new_readonly(system_program::id(), false),
+    ];
+
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data,
+    })
+}
+
+/// Creates a `FillWithdrawal` instruction for the request at `sequence`,
+/// the head of the queue.
+pub fn fill_withdrawal(
+    program_id: &Pubkey,
+    user: &Pubkey,
+    user_usdt_account: &Pubkey,
+    queue_ht_account: &Pubkey,
+    ht_mint: &Pubkey,
+    pool_usdt_account: &Pubkey,
+    pool_state: &Pubkey,
+    pda: &Pubkey,
+    sequence: u64,
+    bump_seed: u8,
+) -> Result<Instruction, ProgramError> {
+    let data = HouseInstruction::FillWithdrawal { bump_seed }.pack();
+    let (request, _) = find_withdrawal_address(program_id, pool_state, sequence);
+
+    let accounts = vec![
+        AccountMeta::new(request, false),
+        AccountMeta::new(*user, false),
+        AccountMeta::new(*user_usdt_account, false),
+        AccountMeta::new(*queue_ht_account, false),
+        AccountMeta::new(*ht_mint, false),
+        AccountMeta::new(*pool_usdt_account, false),
+        AccountMeta::new(*pool_state, false),
+        AccountMeta::new_readonly(*pda, false),
+        AccountMeta::new_readonly(spl_token::id(), false),
+    ];
+
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data,
+    })
 }
@@ -1,5 +1,7 @@
 use error::ExchangeError;
-use spl_token::state::Account as TokenAccount;
+use fixed::types::U64F64;
+use std::convert::TryInto;
+use spl_token::state::{Account as TokenAccount, Mint as TokenMint};
 use state::{HpLiquidity};
 
 pub mod error;
@@ -12,12 +14,90 @@ pub mod state;
 pub mod entrypoint;
 
 
-//TODO fix this
 fn calculate_available_liquidity(
     pool_usdt_state: &TokenAccount,
     pool_state: &HpLiquidity,
 ) -> Result<u64, ExchangeError> {
+    //The committed liquidity is not in the pool and the USDT held for the withdrawal queue is not available
     let available_liquidity = pool_usdt_state
-        .amount;
+        .amount
+        .saturating_sub(pool_state.queued_liquidity);
     return Ok(available_liquidity);
-}
\ No newline at end of file
+}
+
+/// The pool value and house token supply the share price is taken from.
+pub fn calculate_shares(
+    pool_usdt_state: &TokenAccount,
+    ht_mint_state: &TokenMint,
+    pool_state: &HpLiquidity,
+) -> Result<(u64, u64), ExchangeError> {
+    //The pool value and house tokens the share price is taken from, with the
+    //liquidity lent to live markets. Queued house tokens are not burned until
+    //their request is filled, so they share the profit and loss until then
+    let pool_value = pool_usdt_state
+        .amount
+        .checked_add(pool_state.committed_liquidity)
+        .ok_or(ExchangeError::AmountOverflow)?;
+    let ht_supply = ht_mint_state.supply;
+    return Ok((pool_value, ht_supply));
+}
+
+/// The USDT a house token is worth, the pool value over the house token supply.
+pub fn calculate_share_price(
+    pool_value: u64,
+    ht_supply: u64,
+) -> Result<U64F64, ExchangeError> {
+    let share_price = U64F64::from_num(pool_value)
+        .checked_div(U64F64::from_num(ht_supply))
+        .ok_or(ExchangeError::AmountOverflow)?;
+    return Ok(share_price);
+}
+
+/// The house tokens minted for depositing `usdt_amount`, one per USDT into an empty pool.
+pub fn calculate_ht_amount(
+    usdt_amount: u64,
+    pool_value: u64,
+    ht_supply: u64,
+) -> Result<u64, ExchangeError> {
+    let ht_amount = match pool_value {
+        0 => usdt_amount,
+        _ => (U64F64::from_num(ht_supply)
+            .checked_div(U64F64::from_num(pool_value))
+            .ok_or(ExchangeError::AmountOverflow)?
+            .checked_mul(U64F64::from_num(usdt_amount))
+            .ok_or(ExchangeError::AmountOverflow)?)
+        .to_num(),
+    };
+    return Ok(ht_amount);
+}
+
+/// The USDT paid for burning `ht_amount` house tokens.
+pub fn calculate_usdt_amount(
+    ht_amount: u64,
+    pool_value: u64,
+    ht_supply: u64,
+) -> Result<u64, ExchangeError> {
+    let usdt_amount = (calculate_share_price(pool_value, ht_supply)?
+        .checked_mul(U64F64::from_num(ht_amount))
+        .ok_or(ExchangeError::AmountOverflow)?)
+    .to_num();
+    return Ok(usdt_amount);
+}
+
+/// The part of the USDT held for the withdrawal queue released by filling a request for `ht_amount`.
+pub fn calculate_released_liquidity(
+    ht_amount: u64,
+    queued_ht: u64,
+    queued_liquidity: u64,
+) -> Result<u64, ExchangeError> {
+    //A queue holding no house tokens holds no USDT to release
+    if queued_ht == 0 {
+        return Ok(0);
+    }
+    let released_liquidity = (queued_liquidity as u128)
+        .checked_mul(ht_amount as u128)
+        .and_then(|amount| amount.checked_div(queued_ht as u128))
+        .and_then(|amount| amount.try_into().ok())
+        .ok_or(ExchangeError::AmountOverflow)?;
+    return Ok(released_liquidity);
+}
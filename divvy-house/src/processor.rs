@@ -16,15 +16,22 @@ use spl_token::{
     state::Mint as TokenMint,
 };
 
+use divvycommon::authority::{init_multisig, validate_authority, validate_role, Role, Roles};
+use divvycommon::pda::{create_pda_account, find_ledger_address, find_withdrawal_address, LEDGER_SEED, WITHDRAWAL_SEED};
+
 use crate::{
     calculate_available_liquidity,
+    calculate_ht_amount,
+    calculate_released_liquidity,
+    calculate_shares,
+    calculate_usdt_amount,
     error::ExchangeError,
     instruction::HouseInstruction,
     schema::{authority, token_program_id},
-    state::{HpLiquidity},
+    state::{HpLiquidity, MarketLedger, WithdrawalRequest},
 };
 
-use fixed::types::U64F64;
+use std::convert::TryInto;
 
 pub struct Processor;
 impl Processor {
@@ -65,6 +72,46 @@ impl Processor {
                 msg!("Divvy - Transfer locked liquidity");
                 Self::transfer_usdt_on_market_commence(accounts,usdt_amount, bump_seed, program_id)
             }
+            HouseInstruction::ProposeAuthority { new_authority } => {
+                msg!("Divvy - Propose authority");
+                Self::process_propose_authority(accounts, program_id, new_authority)
+            }
+            HouseInstruction::AcceptAuthority => {
+                msg!("Divvy - Accept authority");
+                Self::process_accept_authority(accounts, program_id)
+            }
+            HouseInstruction::InitMultisig { m } => {
+                msg!("Divvy - Init multisig");
+                Self::process_init_multisig(accounts, program_id, m)
+            }
+            HouseInstruction::GrantRole { role, member } => {
+                msg!("Divvy - Grant role");
+                Self::process_grant_role(accounts, program_id, role, member)
+            }
+            HouseInstruction::RevokeRole { role, member } => {
+                msg!("Divvy - Revoke role");
+                Self::process_revoke_role(accounts, program_id, role, member)
+            }
+            HouseInstruction::RequestWithdrawal { ht_amount } => {
+                msg!("Divvy - Request withdrawal");
+                Self::process_request_withdrawal(accounts, ht_amount, program_id)
+            }
+            HouseInstruction::FillWithdrawal { bump_seed } => {
+                msg!("Divvy - Fill withdrawal");
+                Self::process_fill_withdrawal(accounts, bump_seed, program_id)
+            }
+            HouseInstruction::ReturnLiquidity { usdt_amount } => {
+                msg!("Divvy - Return liquidity");
+                Self::process_return_liquidity(accounts, usdt_amount, program_id)
+            }
+            HouseInstruction::QuoteDeposit { usdt_amount } => {
+                msg!("Divvy - Quote deposit");
+                Self::process_quote_deposit(accounts, usdt_amount, program_id)
+            }
+            HouseInstruction::QuoteWithdraw { ht_amount } => {
+                msg!("Divvy - Quote withdraw");
+                Self::process_quote_withdraw(accounts, ht_amount, program_id)
+            }
 
         }
     }
@@ -73,7 +120,7 @@ impl Processor {
         accounts: &[AccountInfo],
         usdt_amount: u64,
         bump_seed: u8,
-        _program_id: &Pubkey,
+        program_id: &Pubkey,
     ) -> ProgramResult {
         let accounts_iter = &mut accounts.iter();
 
@@ -87,7 +134,7 @@ impl Processor {
         let pool_state_account = next_account_info(accounts_iter)?;
 
         msg!("- Unpacking pool state");
-        let pool_state = HpLiquidity::unpack(&pool_state_account.data.borrow())?;
+        let pool_state = Self::unpack_pool_state(program_id, pool_state_account)?;
         msg!("- Unpacking ht mint");
         let ht_mint_state = TokenMint::unpack(&ht_mint_account.data.borrow())?;
         msg!("- Unpacking usdt pool");
@@ -110,34 +157,17 @@ impl Processor {
         msg!(0, 0, 0, 0, ht_mint_state.supply);
         msg!("- House pool balance");
         msg!(0, 0, 0, 0, pool_usdt_state.amount);
-
-        // TODO fix this
-        // msg!("- House pool locked liquidity");
-        // msg!(0, 0, 0, 0, pool_state.locked_liquidity);
-        // msg!("- House pool live liquidity");
-        // msg!(0, 0, 0, 0, pool_state.locked_liquidity);
-
-        // TODO fix this
-        // if pool_state.live_liquidity > 0 {
-        //     return Err(ExchangeError::GamesAreLive.into());
-        // }
+        msg!("- House pool queued liquidity");
+        msg!(0, 0, 0, 0, pool_state.queued_liquidity);
+        msg!("- House pool committed liquidity");
+        msg!(0, 0, 0, 0, pool_state.committed_liquidity);
 
         if pool_state.frozen_pool {
             return Err(ExchangeError::PoolFrozen.into());
         }
 
-        let ht_amount = match pool_usdt_state.amount {
-            0 => usdt_amount,
-            _ => (U64F64::from_num(ht_mint_state.supply)
-                .checked_div(U64F64::from_num(
-                    pool_usdt_state
-                        .amount
-                ))
-                .ok_or(ExchangeError::AmountOverflow)?
-                .checked_mul(U64F64::from_num(usdt_amount))
-                .ok_or(ExchangeError::AmountOverflow)?)
-            .to_num(),
-        };
+        let (pool_value, ht_supply) = calculate_shares(&pool_usdt_state, &ht_mint_state, &pool_state)?;
+        let ht_amount = calculate_ht_amount(usdt_amount, pool_value, ht_supply)?;
 
         msg!("- HT amount received");
         msg!(0, 0, 0, 0, ht_amount);
@@ -189,7 +219,7 @@ impl Processor {
         accounts: &[AccountInfo],
         ht_amount: u64,
         bump_seed: u8,
-        _program_id: &Pubkey,
+        program_id: &Pubkey,
     ) -> ProgramResult {
         let accounts_iter = &mut accounts.iter();
 
@@ -202,7 +232,7 @@ impl Processor {
         let pool_usdt_account = next_account_info(accounts_iter)?;
         let pool_state_account = next_account_info(accounts_iter)?;
 
-        let pool_state = HpLiquidity::unpack(&pool_state_account.data.borrow())?;
+        let pool_state = Self::unpack_pool_state(program_id, pool_state_account)?;
         let ht_mint_state = TokenMint::unpack(&ht_mint_account.data.borrow())?;
         let pool_usdt_state = TokenAccount::unpack(&pool_usdt_account.data.borrow())?;
 
@@ -223,28 +253,16 @@ impl Processor {
         msg!(0, 0, 0, 0, ht_mint_state.supply);
         msg!("- House pool balance");
         msg!(0, 0, 0, 0, pool_usdt_state.amount);
-        //TODO fix this
-        // msg!("- House pool locked liquidity");
-        // msg!(0, 0, 0, 0, pool_state.locked_liquidity);
-        // msg!("- House pool live liquidity");
-        // msg!(0, 0, 0, 0, pool_state.locked_liquidity);
-
-        // if pool_state.live_liquidity > 0 {
-        //     return Err(ExchangeError::GamesAreLive.into());
-        // }
+        msg!("- House pool queued liquidity");
+        msg!(0, 0, 0, 0, pool_state.queued_liquidity);
+        msg!("- House pool committed liquidity");
+        msg!(0, 0, 0, 0, pool_state.committed_liquidity);
         if pool_state.frozen_pool {
             return Err(ExchangeError::PoolFrozen.into());
         }
 
-        let usdt_amount: u64 = (U64F64::from_num(
-            pool_usdt_state
-                .amount
-        )
-        .checked_div(U64F64::from_num(ht_mint_state.supply))
-        .ok_or(ExchangeError::AmountOverflow)?
-        .checked_mul(U64F64::from_num(ht_amount))
-        .ok_or(ExchangeError::AmountOverflow)?)
-        .to_num();
+        let (pool_value, ht_supply) = calculate_shares(&pool_usdt_state, &ht_mint_state, &pool_state)?;
+        let usdt_amount = calculate_usdt_amount(ht_amount, pool_value, ht_supply)?;
         let available_liquidity = calculate_available_liquidity(&pool_usdt_state, &pool_state)?;
 
         msg!("- House pool available liquidity");
@@ -299,12 +317,327 @@ impl Processor {
         Ok(())
     }
 
+    fn process_quote_deposit(
+        accounts: &[AccountInfo],
+        usdt_amount: u64,
+        program_id: &Pubkey,
+    ) -> ProgramResult {
+        let (pool_state, ht_mint_state, pool_usdt_state) = Self::unpack_quote_accounts(accounts, program_id)?;
+        if pool_state.frozen_pool {
+            return Err(ExchangeError::PoolFrozen.into());
+        }
+
+        //Same share price math as the deposit
+        let (pool_value, ht_supply) = calculate_shares(&pool_usdt_state, &ht_mint_state, &pool_state)?;
+        let ht_amount = calculate_ht_amount(usdt_amount, pool_value, ht_supply)?;
+
+        msg!("- House pool value and HT supply");
+        msg!(0, 0, 0, pool_value, ht_supply);
+        msg!("- USDT amount deposited and HT amount received");
+        msg!(0, 0, 0, usdt_amount, ht_amount);
+        Ok(())
+    }
+
+    fn process_quote_withdraw(
+        accounts: &[AccountInfo],
+        ht_amount: u64,
+        program_id: &Pubkey,
+    ) -> ProgramResult {
+        let (pool_state, ht_mint_state, pool_usdt_state) = Self::unpack_quote_accounts(accounts, program_id)?;
+        if pool_state.frozen_pool {
+            return Err(ExchangeError::PoolFrozen.into());
+        }
+
+        //Same share price math as the withdrawal
+        let (pool_value, ht_supply) = calculate_shares(&pool_usdt_state, &ht_mint_state, &pool_state)?;
+        let usdt_amount = calculate_usdt_amount(ht_amount, pool_value, ht_supply)?;
+        let available_liquidity = calculate_available_liquidity(&pool_usdt_state, &pool_state)?;
+
+        msg!("- House pool value and HT supply");
+        msg!(0, 0, 0, pool_value, ht_supply);
+        msg!("- House pool available liquidity");
+        msg!(0, 0, 0, 0, available_liquidity);
+        msg!("- HT amount burned and USDT amount received");
+        msg!(0, 0, 0, ht_amount, usdt_amount);
+
+        if usdt_amount > available_liquidity {
+            return Err(ExchangeError::NotEnoughAvailableLiquidityForWithdrawal.into());
+        }
+        Ok(())
+    }
+
+    fn unpack_quote_accounts(
+        accounts: &[AccountInfo],
+        program_id: &Pubkey,
+    ) -> Result<(HpLiquidity, TokenMint, TokenAccount), ProgramError> {
+        let accounts_iter = &mut accounts.iter();
+
+        let ht_mint_account = next_account_info(accounts_iter)?;
+        let pool_usdt_account = next_account_info(accounts_iter)?;
+        let pool_state_account = next_account_info(accounts_iter)?;
+
+        let pool_state = Self::unpack_pool_state(program_id, pool_state_account)?;
+        // Checking house token ownership
+        if *ht_mint_account.key != pool_state.ht_mint {
+            return Err(ExchangeError::InvalidHtMintAccount.into());
+        }
+        if *pool_usdt_account.key != pool_state.pool_usdt {
+            return Err(ExchangeError::InvalidPoolUsdtAccount.into());
+        }
+        let ht_mint_state = TokenMint::unpack(&ht_mint_account.data.borrow())?;
+        let pool_usdt_state = TokenAccount::unpack(&pool_usdt_account.data.borrow())?;
+        Ok((pool_state, ht_mint_state, pool_usdt_state))
+    }
+
+    fn process_request_withdrawal(
+        accounts: &[AccountInfo],
+        ht_amount: u64,
+        program_id: &Pubkey,
+    ) -> ProgramResult {
+        let accounts_iter = &mut accounts.iter();
+
+        let user_account = next_account_info(accounts_iter)?;
+        let request_account = next_account_info(accounts_iter)?;
+        let user_ht_account = next_account_info(accounts_iter)?;
+        let queue_ht_account = next_account_info(accounts_iter)?;
+        let ht_mint_account = next_account_info(accounts_iter)?;
+        let pool_usdt_account = next_account_info(accounts_iter)?;
+        let pool_state_account = next_account_info(accounts_iter)?;
+        let token_program = next_account_info(accounts_iter)?;
+        let system_program = next_account_info(accounts_iter)?;
+
+        let mut pool_state = Self::unpack_pool_state(program_id, pool_state_account)?;
+        let ht_mint_state = TokenMint::unpack(&ht_mint_account.data.borrow())?;
+        let pool_usdt_state = TokenAccount::unpack(&pool_usdt_account.data.borrow())?;
+
+        // Checking house token ownership
+        if *ht_mint_account.key != pool_state.ht_mint {
+            return Err(ExchangeError::InvalidHtMintAccount.into());
+        }
+        if *pool_usdt_account.key != pool_state.pool_usdt {
+            return Err(ExchangeError::InvalidPoolUsdtAccount.into());
+        }
+        if *queue_ht_account.key != pool_state.queue_ht {
+            return Err(ExchangeError::InvalidQueueHtAccount.into());
+        }
+        if *token_program.key != token_program_id::ID {
+            return Err(ExchangeError::InvalidInstruction.into());
+        }
+        if pool_state.frozen_pool {
+            return Err(ExchangeError::PoolFrozen.into());
+        }
+        //A request for no HT would hold the head of the queue
+        if ht_amount == 0 {
+            return Err(ExchangeError::WithdrawalAmountZero.into());
+        }
+
+        //Checking the request account is the tail of the queue
+        let sequence = pool_state.queue_tail;
+        let (request_address, request_bump_seed) = find_withdrawal_address(program_id, pool_state_account.key, sequence);
+        if *request_account.key != request_address {
+            return Err(ExchangeError::InvalidWithdrawalRequest.into());
+        }
+
+        let (pool_value, ht_supply) = calculate_shares(&pool_usdt_state, &ht_mint_state, &pool_state)?;
+        let usdt_amount = calculate_usdt_amount(ht_amount, pool_value, ht_supply)?;
+
+        msg!("- HT amount queued");
+        msg!(0, 0, 0, 0, ht_amount);
+        msg!("- USDT amount held for the request");
+        msg!(0, 0, 0, 0, usdt_amount);
+        msg!("- Withdrawal request sequence");
+        msg!(0, 0, 0, 0, sequence);
+
+        msg!("Escrowing HT");
+        let transfer_instruction = transfer(
+            &token_program.key,
+            &user_ht_account.key,
+            &queue_ht_account.key,
+            &user_account.key,
+            &[&user_account.key],
+            ht_amount,
+        )?;
+        invoke(
+            &transfer_instruction,
+            &[
+                user_ht_account.clone(),
+                queue_ht_account.clone(),
+                user_account.clone(),
+                token_program.clone(),
+            ],
+        )?;
+
+        create_pda_account(
+            program_id,
+            user_account,
+            request_account,
+            system_program,
+            WithdrawalRequest::LEN,
+            &[WITHDRAWAL_SEED, pool_state_account.key.as_ref(), &sequence.to_le_bytes(), &[request_bump_seed]],
+        )?;
+        WithdrawalRequest::pack(
+            WithdrawalRequest {
+                is_initialized: true,
+                user: *user_account.key,
+                ht_amount,
+                sequence,
+            },
+            &mut request_account.data.borrow_mut(),
+        )?;
+
+        pool_state.queued_ht = pool_state
+            .queued_ht
+            .checked_add(ht_amount)
+            .ok_or(ExchangeError::AmountOverflow)?;
+        pool_state.queued_liquidity = pool_state
+            .queued_liquidity
+            .checked_add(usdt_amount)
+            .ok_or(ExchangeError::AmountOverflow)?;
+        pool_state.queue_tail = sequence
+            .checked_add(1)
+            .ok_or(ExchangeError::AmountOverflow)?;
+        HpLiquidity::pack(pool_state, &mut pool_state_account.data.borrow_mut())?;
+
+        Ok(())
+    }
+
+    fn process_fill_withdrawal(
+        accounts: &[AccountInfo],
+        bump_seed: u8,
+        program_id: &Pubkey,
+    ) -> ProgramResult {
+        let accounts_iter = &mut accounts.iter();
+
+        let request_account = next_account_info(accounts_iter)?;
+        let user_account = next_account_info(accounts_iter)?;
+        let user_usdt_account = next_account_info(accounts_iter)?;
+        let queue_ht_account = next_account_info(accounts_iter)?;
+        let ht_mint_account = next_account_info(accounts_iter)?;
+        let pool_usdt_account = next_account_info(accounts_iter)?;
+        let pool_state_account = next_account_info(accounts_iter)?;
+        let pda_account = next_account_info(accounts_iter)?;
+        let token_program = next_account_info(accounts_iter)?;
+
+        let mut pool_state = Self::unpack_pool_state(program_id, pool_state_account)?;
+        let ht_mint_state = TokenMint::unpack(&ht_mint_account.data.borrow())?;
+        let pool_usdt_state = TokenAccount::unpack(&pool_usdt_account.data.borrow())?;
+        let user_usdt_state = TokenAccount::unpack(&user_usdt_account.data.borrow())?;
+
+        // Checking house token ownership
+        if *ht_mint_account.key != pool_state.ht_mint {
+            return Err(ExchangeError::InvalidHtMintAccount.into());
+        }
+        if *pool_usdt_account.key != pool_state.pool_usdt {
+            return Err(ExchangeError::InvalidPoolUsdtAccount.into());
+        }
+        if *queue_ht_account.key != pool_state.queue_ht {
+            return Err(ExchangeError::InvalidQueueHtAccount.into());
+        }
+        if *token_program.key != token_program_id::ID {
+            return Err(ExchangeError::InvalidInstruction.into());
+        }
+        if pool_state.frozen_pool {
+            return Err(ExchangeError::PoolFrozen.into());
+        }
+
+        //Checking the request account is the head of the queue
+        let (request_address, _) = find_withdrawal_address(program_id, pool_state_account.key, pool_state.queue_head);
+        if *request_account.key != request_address {
+            return Err(ExchangeError::InvalidWithdrawalRequest.into());
+        }
+        let request = WithdrawalRequest::unpack(&request_account.data.borrow())?;
+        //Checking the USDT goes to the requester, who may have closed the account they queued from
+        if *user_account.key != request.user
+            || user_usdt_state.owner != request.user
+            || user_usdt_state.mint != pool_usdt_state.mint
+        {
+            return Err(ExchangeError::InvalidWithdrawalRequest.into());
+        }
+
+        //The request is paid at the share price now, and releases its part of the USDT held for the queue
+        let (pool_value, ht_supply) = calculate_shares(&pool_usdt_state, &ht_mint_state, &pool_state)?;
+        let usdt_amount = calculate_usdt_amount(request.ht_amount, pool_value, ht_supply)?;
+        let released_liquidity = calculate_released_liquidity(request.ht_amount, pool_state.queued_ht, pool_state.queued_liquidity)?;
+
+        msg!("- HT amount burned");
+        msg!(0, 0, 0, 0, request.ht_amount);
+        msg!("- USDT amount received");
+        msg!(0, 0, 0, 0, usdt_amount);
+        msg!("- House pool balance");
+        msg!(0, 0, 0, 0, pool_usdt_state.amount);
+
+        //Liquidity lent to live markets comes back as they settle
+        if usdt_amount > pool_usdt_state.amount {
+            return Err(ExchangeError::NotEnoughAvailableLiquidityForWithdrawal.into());
+        }
+
+        msg!("Burning HT");
+        let burn_tx = burn(
+            &token_program.key,
+            &queue_ht_account.key,
+            &ht_mint_account.key,
+            &pda_account.key,
+            &[&pda_account.key],
+            request.ht_amount,
+        )?;
+        invoke_signed(
+            &burn_tx,
+            &[
+                token_program.clone(),
+                queue_ht_account.clone(),
+                ht_mint_account.clone(),
+                pda_account.clone(),
+            ],
+            &[&[b"divvyhouse", &[bump_seed]]],
+        )?;
+
+        msg!("Transfering USDT to the user");
+        let transfer_instruction = transfer(
+            &token_program.key,
+            &pool_usdt_account.key,
+            &user_usdt_account.key,
+            &pda_account.key,
+            &[&pda_account.key],
+            usdt_amount,
+        )?;
+        invoke_signed(
+            &transfer_instruction,
+            &[
+                pool_usdt_account.clone(),
+                user_usdt_account.clone(),
+                pda_account.clone(),
+                token_program.clone(),
+            ],
+            &[&[b"divvyhouse", &[bump_seed]]],
+        )?;
+
+        pool_state.queued_ht = pool_state
+            .queued_ht
+            .checked_sub(request.ht_amount)
+            .ok_or(ExchangeError::AmountOverflow)?;
+        pool_state.queued_liquidity = pool_state
+            .queued_liquidity
+            .checked_sub(released_liquidity)
+            .ok_or(ExchangeError::AmountOverflow)?;
+        pool_state.queue_head = pool_state
+            .queue_head
+            .checked_add(1)
+            .ok_or(ExchangeError::AmountOverflow)?;
+        HpLiquidity::pack(pool_state, &mut pool_state_account.data.borrow_mut())?;
+
+        //Return the request rent to the requester
+        let balance = request_account.lamports();
+        **request_account.try_borrow_mut_lamports()? -= balance;
+        **user_account.try_borrow_mut_lamports()? += balance;
+
+        Ok(())
+    }
 
     pub fn transfer_usdt_on_market_commence(
         accounts: &[AccountInfo],
         usdt_amount: u64,
         bump_seed: u8,
-        _program_id: &Pubkey,
+        program_id: &Pubkey,
     ) -> ProgramResult {
         let accounts_iter = &mut accounts.iter();
         let token_program = next_account_info(accounts_iter)?;
@@ -313,15 +646,70 @@ impl Processor {
         let betting_usdt_account = next_account_info(accounts_iter)?;
         let pool_usdt_account = next_account_info(accounts_iter)?;
         let pool_state_account = next_account_info(accounts_iter)?;
+        let payer_account = next_account_info(accounts_iter)?;
+        let market_account = next_account_info(accounts_iter)?;
+        let ledger_account = next_account_info(accounts_iter)?;
+        let system_program = next_account_info(accounts_iter)?;
 
-        if !bet_pda_account.is_signer {
-            return Err(ProgramError::MissingRequiredSignature);
+        let mut pool_state = Self::unpack_pool_state(program_id, pool_state_account)?;
+        Self::check_betting_pda(&pool_state, bet_pda_account)?;
+        //Checking the market is one of the betting program's
+        if *market_account.owner != pool_state.betting_program {
+            return Err(ExchangeError::InvalidMarketAccount.into());
+        }
+        let (ledger_address, ledger_bump_seed) = find_ledger_address(program_id, pool_state_account.key, market_account.key);
+        if *ledger_account.key != ledger_address {
+            return Err(ExchangeError::InvalidLedgerAccount.into());
+        }
+        //Checking the liquidity moves from the house pool to the betting pool
+        if *pool_usdt_account.key != pool_state.pool_usdt {
+            return Err(ExchangeError::InvalidPoolUsdtAccount.into());
+        }
+        if *betting_usdt_account.key != pool_state.betting_usdt {
+            return Err(ExchangeError::InvalidBettingPoolUsdtAccount.into());
+        }
+        if *token_program.key != token_program_id::ID {
+            return Err(ExchangeError::InvalidInstruction.into());
+        }
+
+        //Checking the pool holds the liquidity, without the USDT held for the withdrawal queue
+        let pool_usdt_state = TokenAccount::unpack(&pool_usdt_account.data.borrow())?;
+        let available_liquidity = calculate_available_liquidity(&pool_usdt_state, &pool_state)?;
+        msg!("- House pool available liquidity");
+        msg!(0, 0, 0, 0, available_liquidity);
+        if usdt_amount > available_liquidity {
+            return Err(ExchangeError::NotEnoughAvailableLiquidityForBet.into());
         }
-        // let pool_usdt_state = TokenAccount::unpack(&pool_usdt_account.data.borrow())?;
-        // let pool_state = HpLiquidity::unpack(&pool_state_account.data.borrow())?;
 
-        //TODO check if locked liquidity is greater than the usdt balance 
-        //TODO check if betting_usdt_account is actually the betting_account in state
+        //Adding the liquidity lent to the market to the committed liquidity
+        pool_state.committed_liquidity = pool_state
+            .committed_liquidity
+            .checked_add(usdt_amount)
+            .ok_or(ExchangeError::AmountOverflow)?;
+        msg!("- House pool committed liquidity");
+        msg!(0, 0, 0, 0, pool_state.committed_liquidity);
+        HpLiquidity::pack(pool_state, &mut pool_state_account.data.borrow_mut())?;
+
+        //Opening the market's ledger, a market commences once
+        create_pda_account(
+            program_id,
+            payer_account,
+            ledger_account,
+            system_program,
+            MarketLedger::LEN,
+            &[LEDGER_SEED, pool_state_account.key.as_ref(), market_account.key.as_ref(), &[ledger_bump_seed]],
+        )?;
+        MarketLedger::pack(
+            MarketLedger {
+                is_initialized: true,
+                market: *market_account.key,
+                lent: usdt_amount,
+                returned: 0,
+                realized_pnl: 0,
+                settled: false,
+            },
+            &mut ledger_account.data.borrow_mut(),
+        )?;
 
         msg!("transferring locked liquidity usdt on market commence");
         let transfer_instruction = transfer(
@@ -345,7 +733,128 @@ impl Processor {
 
         Ok(())
     }
-    
+
+    pub fn process_return_liquidity(
+        accounts: &[AccountInfo],
+        usdt_amount: u64,
+        program_id: &Pubkey,
+    ) -> ProgramResult {
+        let accounts_iter = &mut accounts.iter();
+        let bet_pda_account = next_account_info(accounts_iter)?;
+        let pool_state_account = next_account_info(accounts_iter)?;
+        let ledger_account = next_account_info(accounts_iter)?;
+        let betting_usdt_account = next_account_info(accounts_iter)?;
+        let pool_usdt_account = next_account_info(accounts_iter)?;
+        let token_program = next_account_info(accounts_iter)?;
+
+        let mut pool_state = Self::unpack_pool_state(program_id, pool_state_account)?;
+        Self::check_betting_pda(&pool_state, bet_pda_account)?;
+        //Checking the liquidity moves from the betting pool back to the house pool
+        if *betting_usdt_account.key != pool_state.betting_usdt {
+            return Err(ExchangeError::InvalidBettingPoolUsdtAccount.into());
+        }
+        if *pool_usdt_account.key != pool_state.pool_usdt {
+            return Err(ExchangeError::InvalidPoolUsdtAccount.into());
+        }
+        if *token_program.key != token_program_id::ID {
+            return Err(ExchangeError::InvalidInstruction.into());
+        }
+        //Checking the ledger is this pool's
+        if ledger_account.owner != program_id {
+            return Err(ExchangeError::InvalidLedgerAccount.into());
+        }
+        let mut ledger = MarketLedger::unpack(&ledger_account.data.borrow())?;
+        let (ledger_address, _ledger_bump_seed) = find_ledger_address(program_id, pool_state_account.key, &ledger.market);
+        if *ledger_account.key != ledger_address {
+            return Err(ExchangeError::InvalidLedgerAccount.into());
+        }
+
+        if usdt_amount > 0 {
+            //The betting program PDA signed the call, so it authorizes the transfer
+            msg!("Transfering returned liquidity to house pool");
+            let transfer_instruction = transfer(
+                &token_program.key,
+                &betting_usdt_account.key,
+                &pool_usdt_account.key,
+                &bet_pda_account.key,
+                &[&bet_pda_account.key],
+                usdt_amount,
+            )?;
+            invoke(
+                &transfer_instruction,
+                &[
+                    betting_usdt_account.clone(),
+                    pool_usdt_account.clone(),
+                    bet_pda_account.clone(),
+                    token_program.clone(),
+                ],
+            )?;
+        }
+
+        let realized_pnl: i64 = if ledger.settled {
+            //Released as bets graded below their side's line settle, after the loan was closed
+            usdt_amount
+                .try_into()
+                .map_err(|_| ExchangeError::AmountOverflow)?
+        } else {
+            //Closing the market's loan and realizing its profit or loss
+            pool_state.committed_liquidity = pool_state
+                .committed_liquidity
+                .checked_sub(ledger.lent)
+                .ok_or(ExchangeError::AmountOverflow)?;
+            (usdt_amount as i128 - ledger.lent as i128)
+                .try_into()
+                .map_err(|_| ExchangeError::AmountOverflow)?
+        };
+        ledger.returned = ledger
+            .returned
+            .checked_add(usdt_amount)
+            .ok_or(ExchangeError::AmountOverflow)?;
+        ledger.realized_pnl = ledger
+            .realized_pnl
+            .checked_add(realized_pnl)
+            .ok_or(ExchangeError::AmountOverflow)?;
+        ledger.settled = true;
+        pool_state.realized_pnl = pool_state
+            .realized_pnl
+            .checked_add(realized_pnl)
+            .ok_or(ExchangeError::AmountOverflow)?;
+
+        msg!("- Market liquidity lent and returned");
+        msg!(0, 0, 0, ledger.lent, ledger.returned);
+        msg!("- House pool committed liquidity");
+        msg!(0, 0, 0, 0, pool_state.committed_liquidity);
+
+        MarketLedger::pack(ledger, &mut ledger_account.data.borrow_mut())?;
+        HpLiquidity::pack(pool_state, &mut pool_state_account.data.borrow_mut())?;
+
+        Ok(())
+    }
+
+    fn unpack_pool_state(
+        program_id: &Pubkey,
+        pool_state_account: &AccountInfo,
+    ) -> Result<HpLiquidity, ProgramError> {
+        if pool_state_account.owner != program_id {
+            return Err(ExchangeError::InvalidHousePoolStateAccount.into());
+        }
+        HpLiquidity::unpack(&pool_state_account.data.borrow())
+    }
+
+    //Only the PDA of the pool's betting program may move its liquidity
+    fn check_betting_pda(
+        pool_state: &HpLiquidity,
+        bet_pda_account: &AccountInfo,
+    ) -> ProgramResult {
+        if !bet_pda_account.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+        let (bet_pda, _bet_bump_seed) = Pubkey::find_program_address(&[b"divvybetting"], &pool_state.betting_program);
+        if *bet_pda_account.key != bet_pda {
+            return Err(ExchangeError::InvalidBettingPda.into());
+        }
+        Ok(())
+    }
 
     pub fn process_ownership(
         accounts: &[AccountInfo],
@@ -359,13 +868,15 @@ impl Processor {
         let ht_mint_account = next_account_info(accounts_iter)?;
         let betting_usdt_account = next_account_info(accounts_iter)?;
         let pool_usdt_account = next_account_info(accounts_iter)?;
+        let queue_ht_account = next_account_info(accounts_iter)?;
+        let betting_program = next_account_info(accounts_iter)?;
         msg!("Unpack HP State account");
         let mut pool_state = HpLiquidity::unpack_unchecked(&pool_state_account.data.borrow())?;
         msg!("Check HP State Init");
-        // Doesn't help, should change
-        // if pool_state.is_initialized {
-        //     return Err(ExchangeError::HpLiquidityAlreadyInitialized.into());
-        // }
+        // The authority is in the pool state now, so the bootstrap key must not reset it
+        if pool_state.is_initialized {
+            return Err(ExchangeError::HpLiquidityAlreadyInitialized.into());
+        }
         msg!("Check Rent Exemption");
         if !Rent::get()?.is_exempt(
             **pool_state_account.lamports.borrow(),
@@ -376,8 +887,20 @@ impl Processor {
         // Unpack token accounts to verify their length
         msg!("Check token account accounts length");
         TokenMint::unpack(&ht_mint_account.data.borrow())?;
-        TokenAccount::unpack(&betting_usdt_account.data.borrow())?;
+        let betting_usdt_state = TokenAccount::unpack(&betting_usdt_account.data.borrow())?;
         TokenAccount::unpack(&pool_usdt_account.data.borrow())?;
+        let queue_ht_state = TokenAccount::unpack(&queue_ht_account.data.borrow())?;
+        if queue_ht_state.mint != *ht_mint_account.key {
+            return Err(ExchangeError::InvalidQueueHtAccount.into());
+        }
+        //Checking the betting program and that it owns the betting pool USDT account through its PDA
+        if !betting_program.executable {
+            return Err(ExchangeError::InvalidBettingProgramAccount.into());
+        }
+        let (bet_pda, _bet_bump_seed) = Pubkey::find_program_address(&[b"divvybetting"], betting_program.key);
+        if betting_usdt_state.owner != bet_pda {
+            return Err(ExchangeError::InvalidBettingPoolUsdtAccount.into());
+        }
 
         msg!("Check authority");
         if initializer.key != &authority::ID {
@@ -391,6 +914,17 @@ impl Processor {
             betting_usdt: *betting_usdt_account.key,
             pool_usdt: *pool_usdt_account.key,
             frozen_pool: false,
+            authority: *initializer.key,
+            pending_authority: None,
+            roles: Roles::default(),
+            queue_ht: *queue_ht_account.key,
+            queued_ht: 0,
+            queued_liquidity: 0,
+            queue_head: 0,
+            queue_tail: 0,
+            committed_liquidity: 0,
+            betting_program: *betting_program.key,
+            realized_pnl: 0,
         };
         HpLiquidity::pack(pool_state, &mut pool_state_account.data.borrow_mut())?;
         Ok(())
@@ -398,21 +932,15 @@ impl Processor {
 
     pub fn process_freeze(
         accounts: &[AccountInfo],
-        _program_id: &Pubkey,
+        program_id: &Pubkey,
         freeze_pool: bool,
     ) -> ProgramResult {
         let accounts_iter = &mut accounts.iter();
         let initializer = next_account_info(accounts_iter)?;
         let pool_state_account = next_account_info(accounts_iter)?;
 
-        if !initializer.is_signer {
-            return Err(ProgramError::MissingRequiredSignature);
-        }
-        if initializer.key != &authority::ID {
-            return Err(ExchangeError::NotValidAuthority.into());
-        }
-
         let mut pool_state = HpLiquidity::unpack(&pool_state_account.data.borrow())?;
+        validate_role(program_id, &pool_state.authority, &pool_state.roles, Role::Pauser, initializer, accounts_iter.as_slice())?;
 
         if freeze_pool && !pool_state.frozen_pool {
             msg!("Freezing pool");
@@ -426,4 +954,100 @@ impl Processor {
 
         Ok(())
     }
+
+    pub fn process_propose_authority(
+        accounts: &[AccountInfo],
+        program_id: &Pubkey,
+        new_authority: Pubkey,
+    ) -> ProgramResult {
+        let accounts_iter = &mut accounts.iter();
+        let authority_account = next_account_info(accounts_iter)?;
+        let pool_state_account = next_account_info(accounts_iter)?;
+
+        let mut pool_state = HpLiquidity::unpack(&pool_state_account.data.borrow())?;
+        validate_authority(program_id, &pool_state.authority, authority_account, accounts_iter.as_slice())?;
+
+        msg!("Proposing new authority");
+        pool_state.pending_authority = Some(new_authority);
+
+        HpLiquidity::pack(pool_state, &mut pool_state_account.data.borrow_mut())?;
+
+        Ok(())
+    }
+
+    pub fn process_accept_authority(
+        accounts: &[AccountInfo],
+        program_id: &Pubkey,
+    ) -> ProgramResult {
+        let accounts_iter = &mut accounts.iter();
+        let pending_authority_account = next_account_info(accounts_iter)?;
+        let pool_state_account = next_account_info(accounts_iter)?;
+
+        let mut pool_state = HpLiquidity::unpack(&pool_state_account.data.borrow())?;
+        let pending_authority = pool_state.pending_authority.ok_or(ExchangeError::NoPendingAuthority)?;
+        validate_authority(program_id, &pending_authority, pending_authority_account, accounts_iter.as_slice())?;
+
+        msg!("Accepting authority");
+        pool_state.authority = pending_authority;
+        pool_state.pending_authority = None;
+
+        HpLiquidity::pack(pool_state, &mut pool_state_account.data.borrow_mut())?;
+
+        Ok(())
+    }
+
+    pub fn process_init_multisig(
+        accounts: &[AccountInfo],
+        program_id: &Pubkey,
+        m: u8,
+    ) -> ProgramResult {
+        let accounts_iter = &mut accounts.iter();
+        let multisig_account = next_account_info(accounts_iter)?;
+
+        init_multisig(program_id, multisig_account, accounts_iter.as_slice(), m)
+    }
+
+    pub fn process_grant_role(
+        accounts: &[AccountInfo],
+        program_id: &Pubkey,
+        role: Role,
+        member: Pubkey,
+    ) -> ProgramResult {
+        let accounts_iter = &mut accounts.iter();
+        let authority_account = next_account_info(accounts_iter)?;
+        let pool_state_account = next_account_info(accounts_iter)?;
+
+        let mut pool_state = HpLiquidity::unpack(&pool_state_account.data.borrow())?;
+        validate_authority(program_id, &pool_state.authority, authority_account, accounts_iter.as_slice())?;
+
+        msg!("Granting role");
+        msg!(0, 0, 0, 0, role.pack() as u64);
+        pool_state.roles.grant(role, &member)?;
+
+        HpLiquidity::pack(pool_state, &mut pool_state_account.data.borrow_mut())?;
+
+        Ok(())
+    }
+
+    pub fn process_revoke_role(
+        accounts: &[AccountInfo],
+        program_id: &Pubkey,
+        role: Role,
+        member: Pubkey,
+    ) -> ProgramResult {
+        let accounts_iter = &mut accounts.iter();
+        let authority_account = next_account_info(accounts_iter)?;
+        let pool_state_account = next_account_info(accounts_iter)?;
+
+        let mut pool_state = HpLiquidity::unpack(&pool_state_account.data.borrow())?;
+        validate_authority(program_id, &pool_state.authority, authority_account, accounts_iter.as_slice())?;
+
+        msg!("Revoking role");
+        msg!(0, 0, 0, 0, role.pack() as u64);
+        pool_state.roles.revoke(role, &member)?;
+
+        HpLiquidity::pack(pool_state, &mut pool_state_account.data.borrow_mut())?;
+
+        Ok(())
+    }
 }
@@ -6,6 +6,10 @@ use solana_program::{
 
 use arrayref::{array_mut_ref, array_ref, array_refs, mut_array_refs};
 
+use divvycommon::{pack_pubkey_option, unpack_pubkey_option};
+
+pub use divvycommon::authority::{Multisig, Role, Roles};
+
 
 pub struct HpLiquidity {
     pub is_initialized: bool,
@@ -13,6 +17,35 @@ pub struct HpLiquidity {
     pub betting_usdt: Pubkey,
     pub pool_usdt: Pubkey,
     pub frozen_pool: bool,
+    /// The key or multisig allowed to administer the pool.
+    pub authority: Pubkey,
+    /// The authority proposed to take over, until it accepts.
+    pub pending_authority: Option<Pubkey>,
+    /// The keys holding each admin role.
+    pub roles: Roles,
+    /// The house token account, owned by the house program PDA, escrowing
+    /// the house tokens queued for withdrawal.
+    pub queue_ht: Pubkey,
+    /// House tokens escrowed in the withdrawal queue.
+    pub queued_ht: u64,
+    /// USDT held back for the withdrawal queue, at the share price each
+    /// request was queued at. It takes no new exposure and can not be
+    /// withdrawn, but still counts toward the share price until the
+    /// requests are filled.
+    pub queued_liquidity: u64,
+    /// Sequence of the oldest unfilled withdrawal request.
+    pub queue_head: u64,
+    /// Sequence of the next withdrawal request.
+    pub queue_tail: u64,
+    /// USDT lent to the betting program for live markets and not yet
+    /// released by their settlement. It counts toward the share price but
+    /// can not be withdrawn.
+    pub committed_liquidity: u64,
+    /// The betting program allowed to borrow and release the pool's liquidity
+    /// through its PDA.
+    pub betting_program: Pubkey,
+    /// Profit or loss of the settled markets, after fees.
+    pub realized_pnl: i64,
 }
 
 
@@ -26,7 +59,7 @@ impl IsInitialized for HpLiquidity {
 impl Sealed for HpLiquidity {}
 
 impl Pack for HpLiquidity {
-    const LEN: usize = 98;
+    const LEN: usize = 787;
     fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
         let src = array_ref![src, 0, HpLiquidity::LEN];
         let (
@@ -35,7 +68,18 @@ impl Pack for HpLiquidity {
             betting_usdt,
             pool_usdt,
             frozen_pool,
-        ) = array_refs![src,1, 32, 32, 32, 1];
+            authority,
+            pending_authority,
+            roles,
+            queue_ht,
+            queued_ht,
+            queued_liquidity,
+            queue_head,
+            queue_tail,
+            committed_liquidity,
+            betting_program,
+            realized_pnl,
+        ) = array_refs![src,1, 32, 32, 32, 1, 32, 33, 512, 32, 8, 8, 8, 8, 8, 32, 8];
 
         Ok(HpLiquidity {
             is_initialized: is_initialized[0] != 0,
@@ -43,6 +87,17 @@ impl Pack for HpLiquidity {
             betting_usdt: Pubkey::new_from_array(*betting_usdt),
             pool_usdt: Pubkey::new_from_array(*pool_usdt),
             frozen_pool: frozen_pool[0] != 0,
+            authority: Pubkey::new_from_array(*authority),
+            pending_authority: unpack_pubkey_option(pending_authority)?.0,
+            roles: Roles::unpack_from_slice(roles),
+            queue_ht: Pubkey::new_from_array(*queue_ht),
+            queued_ht: u64::from_le_bytes(*queued_ht),
+            queued_liquidity: u64::from_le_bytes(*queued_liquidity),
+            queue_head: u64::from_le_bytes(*queue_head),
+            queue_tail: u64::from_le_bytes(*queue_tail),
+            committed_liquidity: u64::from_le_bytes(*committed_liquidity),
+            betting_program: Pubkey::new_from_array(*betting_program),
+            realized_pnl: i64::from_le_bytes(*realized_pnl),
         })
     }
 
@@ -54,7 +109,18 @@ impl Pack for HpLiquidity {
             betting_usdt_dst,
             pool_usdt_dst,
             frozen_pool_dst,
-        ) = mut_array_refs![dst, 1, 32, 32, 32, 1];
+            authority_dst,
+            pending_authority_dst,
+            roles_dst,
+            queue_ht_dst,
+            queued_ht_dst,
+            queued_liquidity_dst,
+            queue_head_dst,
+            queue_tail_dst,
+            committed_liquidity_dst,
+            betting_program_dst,
+            realized_pnl_dst,
+        ) = mut_array_refs![dst, 1, 32, 32, 32, 1, 32, 33, 512, 32, 8, 8, 8, 8, 8, 32, 8];
 
         let HpLiquidity {
             is_initialized,
@@ -62,11 +128,130 @@ impl Pack for HpLiquidity {
             betting_usdt,
             pool_usdt,
             frozen_pool,
+            authority,
+            pending_authority,
+            roles,
+            queue_ht,
+            queued_ht,
+            queued_liquidity,
+            queue_head,
+            queue_tail,
+            committed_liquidity,
+            betting_program,
+            realized_pnl,
         } = self;
         is_initialized_dst[0] = *is_initialized as u8;
         ht_mint_dst.copy_from_slice(ht_mint.as_ref());
         betting_usdt_dst.copy_from_slice(betting_usdt.as_ref());
         pool_usdt_dst.copy_from_slice(pool_usdt.as_ref());
         frozen_pool_dst[0] = *frozen_pool as u8;
+        authority_dst.copy_from_slice(authority.as_ref());
+        pack_pubkey_option(pending_authority, pending_authority_dst);
+        roles.pack_into_slice(roles_dst);
+        queue_ht_dst.copy_from_slice(queue_ht.as_ref());
+        *queued_ht_dst = queued_ht.to_le_bytes();
+        *queued_liquidity_dst = queued_liquidity.to_le_bytes();
+        *queue_head_dst = queue_head.to_le_bytes();
+        *queue_tail_dst = queue_tail.to_le_bytes();
+        *committed_liquidity_dst = committed_liquidity.to_le_bytes();
+        betting_program_dst.copy_from_slice(betting_program.as_ref());
+        *realized_pnl_dst = realized_pnl.to_le_bytes();
+    }
+}
+
+/// A house token holder's place in the withdrawal queue, at
+/// `find_withdrawal_address` of the pool and its sequence.
+pub struct WithdrawalRequest {
+    pub is_initialized: bool,
+    /// The holder, paid the USDT and the request rent once it is filled.
+    pub user: Pubkey,
+    /// House tokens escrowed, burned and paid at the share price once the
+    /// request is filled.
+    pub ht_amount: u64,
+    pub sequence: u64,
+}
+
+impl IsInitialized for WithdrawalRequest {
+    fn is_initialized(&self) -> bool {
+        self.is_initialized
+    }
+}
+
+impl Sealed for WithdrawalRequest {}
+
+impl Pack for WithdrawalRequest {
+    const LEN: usize = 49;
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        let src = array_ref![src, 0, WithdrawalRequest::LEN];
+        let (is_initialized, user, ht_amount, sequence) = array_refs![src, 1, 32, 8, 8];
+
+        Ok(WithdrawalRequest {
+            is_initialized: is_initialized[0] != 0,
+            user: Pubkey::new_from_array(*user),
+            ht_amount: u64::from_le_bytes(*ht_amount),
+            sequence: u64::from_le_bytes(*sequence),
+        })
+    }
+
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        let dst = array_mut_ref![dst, 0, WithdrawalRequest::LEN];
+        let (is_initialized_dst, user_dst, ht_amount_dst, sequence_dst) = mut_array_refs![dst, 1, 32, 8, 8];
+
+        is_initialized_dst[0] = self.is_initialized as u8;
+        user_dst.copy_from_slice(self.user.as_ref());
+        *ht_amount_dst = self.ht_amount.to_le_bytes();
+        *sequence_dst = self.sequence.to_le_bytes();
+    }
+}
+
+/// The liquidity the house pool lent to a market, at `find_ledger_address`
+/// of the pool and the market. Kept once the market settles as the record
+/// of its profit or loss.
+pub struct MarketLedger {
+    pub is_initialized: bool,
+    pub market: Pubkey,
+    /// USDT lent to the market when it commenced.
+    pub lent: u64,
+    /// USDT returned when the market settled or was voided.
+    pub returned: u64,
+    /// What was returned less what was lent, once settled.
+    pub realized_pnl: i64,
+    pub settled: bool,
+}
+
+impl IsInitialized for MarketLedger {
+    fn is_initialized(&self) -> bool {
+        self.is_initialized
+    }
+}
+
+impl Sealed for MarketLedger {}
+
+impl Pack for MarketLedger {
+    const LEN: usize = 58;
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        let src = array_ref![src, 0, MarketLedger::LEN];
+        let (is_initialized, market, lent, returned, realized_pnl, settled) = array_refs![src, 1, 32, 8, 8, 8, 1];
+
+        Ok(MarketLedger {
+            is_initialized: is_initialized[0] != 0,
+            market: Pubkey::new_from_array(*market),
+            lent: u64::from_le_bytes(*lent),
+            returned: u64::from_le_bytes(*returned),
+            realized_pnl: i64::from_le_bytes(*realized_pnl),
+            settled: settled[0] != 0,
+        })
+    }
+
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        let dst = array_mut_ref![dst, 0, MarketLedger::LEN];
+        let (is_initialized_dst, market_dst, lent_dst, returned_dst, realized_pnl_dst, settled_dst) = mut_array_refs![dst, 1, 32, 8, 8, 8, 1];
+
+        is_initialized_dst[0] = self.is_initialized as u8;
+        market_dst.copy_from_slice(self.market.as_ref());
+        *lent_dst = self.lent.to_le_bytes();
+        *returned_dst = self.returned.to_le_bytes();
+        *realized_pnl_dst = self.realized_pnl.to_le_bytes();
+        settled_dst[0] = self.settled as u8;
     }
 }
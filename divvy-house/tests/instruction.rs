@@ -0,0 +1,395 @@
+use divvycommon::pda::{find_ledger_address, find_withdrawal_address};
+use divvyhouse::{
+    instruction::{self, HouseInstruction},
+    state::Role,
+};
+use solana_program::{pubkey::Pubkey, system_program};
+
+fn assert_round_trip(instruction: HouseInstruction) {
+    let packed = instruction.pack();
+    let unpacked = HouseInstruction::unpack(&packed).unwrap();
+    assert_eq!(unpacked, instruction);
+}
+
+#[test]
+fn test_pack_unpack_round_trip() {
+    assert_round_trip(HouseInstruction::Deposit {
+        usdt_amount: 1_000_000,
+        bump_seed: 254,
+    });
+    assert_round_trip(HouseInstruction::Withdraw {
+        ht_amount: u64::MAX,
+        bump_seed: 0,
+    });
+    assert_round_trip(HouseInstruction::Ownership { bump_seed: 252 });
+    assert_round_trip(HouseInstruction::Freeze { freeze_pool: true });
+    assert_round_trip(HouseInstruction::Freeze { freeze_pool: false });
+    assert_round_trip(HouseInstruction::TransferLockedLiquidity {
+        usdt_amount: 42,
+        bump_seed: 251,
+    });
+    assert_round_trip(HouseInstruction::ProposeAuthority {
+        new_authority: Pubkey::new_unique(),
+    });
+    assert_round_trip(HouseInstruction::AcceptAuthority);
+    assert_round_trip(HouseInstruction::InitMultisig { m: 11 });
+    assert_round_trip(HouseInstruction::GrantRole {
+        role: Role::MarketCreator,
+        member: Pubkey::new_unique(),
+    });
+    assert_round_trip(HouseInstruction::RevokeRole {
+        role: Role::Treasurer,
+        member: Pubkey::new_unique(),
+    });
+    assert_round_trip(HouseInstruction::RequestWithdrawal {
+        ht_amount: 1_000_000,
+    });
+    assert_round_trip(HouseInstruction::FillWithdrawal { bump_seed: 253 });
+    assert_round_trip(HouseInstruction::ReturnLiquidity {
+        usdt_amount: 450_000_000,
+    });
+    assert_round_trip(HouseInstruction::QuoteDeposit {
+        usdt_amount: 100_000_000,
+    });
+    assert_round_trip(HouseInstruction::QuoteWithdraw {
+        ht_amount: u64::MAX,
+    });
+}
+
+#[test]
+fn test_unpack_invalid() {
+    assert!(HouseInstruction::unpack(&[]).is_err());
+    assert!(HouseInstruction::unpack(&[5]).is_err());
+    assert!(HouseInstruction::unpack(&[0, 1, 0, 0]).is_err());
+    assert!(HouseInstruction::unpack(&[3]).is_err());
+    assert!(HouseInstruction::unpack(&[7]).is_err());
+    assert!(HouseInstruction::unpack(&[8]).is_err());
+    let mut grant_unknown_role = vec![8, 4];
+    grant_unknown_role.extend_from_slice(Pubkey::new_unique().as_ref());
+    assert!(HouseInstruction::unpack(&grant_unknown_role).is_err());
+    assert!(HouseInstruction::unpack(&[10, 1, 0]).is_err());
+    assert!(HouseInstruction::unpack(&[11]).is_err());
+    assert!(HouseInstruction::unpack(&[12, 1]).is_err());
+    assert!(HouseInstruction::unpack(&[13]).is_err());
+    assert!(HouseInstruction::unpack(&[14, 1, 0]).is_err());
+    assert!(HouseInstruction::unpack(&[15]).is_err());
+}
+
+#[test]
+fn test_transfer_locked_liquidity_builder() {
+    let program_id = Pubkey::new_unique();
+    let pda = Pubkey::new_unique();
+    let bet_pda = Pubkey::new_unique();
+    let betting_usdt = Pubkey::new_unique();
+    let pool_usdt = Pubkey::new_unique();
+    let pool_state = Pubkey::new_unique();
+    let payer = Pubkey::new_unique();
+    let market = Pubkey::new_unique();
+    let (ledger, _) = find_ledger_address(&program_id, &pool_state, &market);
+    let ix = instruction::transfer_locked_liquidity(
+        &program_id,
+        &pda,
+        &bet_pda,
+        &betting_usdt,
+        &pool_usdt,
+        &pool_state,
+        &payer,
+        &market,
+        500,
+        250,
+    )
+    .unwrap();
+
+    assert_eq!(ix.program_id, program_id);
+    assert_eq!(
+        HouseInstruction::unpack(&ix.data).unwrap(),
+        HouseInstruction::TransferLockedLiquidity {
+            usdt_amount: 500,
+            bump_seed: 250,
+        }
+    );
+    let keys: Vec<Pubkey> = ix.accounts.iter().map(|meta| meta.pubkey).collect();
+    assert_eq!(
+        keys,
+        vec![
+            spl_token::id(),
+            pda,
+            bet_pda,
+            betting_usdt,
+            pool_usdt,
+            pool_state,
+            payer,
+            market,
+            ledger,
+            system_program::id()
+        ]
+    );
+    assert!(ix.accounts[2].is_signer);
+    assert!(!ix.accounts[2].is_writable);
+    assert!(ix.accounts[6].is_signer);
+    assert!(ix.accounts[8].is_writable);
+}
+
+#[test]
+fn test_return_liquidity_builder() {
+    let program_id = Pubkey::new_unique();
+    let bet_pda = Pubkey::new_unique();
+    let pool_state = Pubkey::new_unique();
+    let market = Pubkey::new_unique();
+    let betting_usdt = Pubkey::new_unique();
+    let pool_usdt = Pubkey::new_unique();
+    let (ledger, _) = find_ledger_address(&program_id, &pool_state, &market);
+
+    let ix = instruction::return_liquidity(
+        &program_id,
+        &bet_pda,
+        &pool_state,
+        &market,
+        &betting_usdt,
+        &pool_usdt,
+        450,
+    )
+    .unwrap();
+    assert_eq!(
+        HouseInstruction::unpack(&ix.data).unwrap(),
+        HouseInstruction::ReturnLiquidity { usdt_amount: 450 }
+    );
+    let keys: Vec<Pubkey> = ix.accounts.iter().map(|meta| meta.pubkey).collect();
+    assert_eq!(
+        keys,
+        vec![
+            bet_pda,
+            pool_state,
+            ledger,
+            betting_usdt,
+            pool_usdt,
+            spl_token::id()
+        ]
+    );
+    assert!(ix.accounts[0].is_signer);
+    assert!(!ix.accounts[0].is_writable);
+    assert!(ix.accounts.iter().skip(1).all(|meta| !meta.is_signer));
+}
+
+#[test]
+fn test_quote_builders() {
+    let program_id = Pubkey::new_unique();
+    let ht_mint = Pubkey::new_unique();
+    let pool_usdt = Pubkey::new_unique();
+    let pool_state = Pubkey::new_unique();
+
+    let deposit =
+        instruction::quote_deposit(&program_id, &ht_mint, &pool_usdt, &pool_state, 100).unwrap();
+    assert_eq!(
+        HouseInstruction::unpack(&deposit.data).unwrap(),
+        HouseInstruction::QuoteDeposit { usdt_amount: 100 }
+    );
+    let withdraw =
+        instruction::quote_withdraw(&program_id, &ht_mint, &pool_usdt, &pool_state, 50).unwrap();
+    assert_eq!(
+        HouseInstruction::unpack(&withdraw.data).unwrap(),
+        HouseInstruction::QuoteWithdraw { ht_amount: 50 }
+    );
+    // Quotes only read the pool
+    for ix in [deposit, withdraw].iter() {
+        let keys: Vec<Pubkey> = ix.accounts.iter().map(|meta| meta.pubkey).collect();
+        assert_eq!(keys, vec![ht_mint, pool_usdt, pool_state]);
+        assert!(ix
+            .accounts
+            .iter()
+            .all(|meta| !meta.is_signer && !meta.is_writable));
+    }
+}
+
+#[test]
+fn test_withdrawal_queue_builders() {
+    let program_id = Pubkey::new_unique();
+    let user = Pubkey::new_unique();
+    let pool_state = Pubkey::new_unique();
+    let key = Pubkey::new_unique();
+    let (tail, _) = find_withdrawal_address(&program_id, &pool_state, 4);
+
+    let ix = instruction::request_withdrawal(
+        &program_id,
+        &user,
+        &key,
+        &key,
+        &key,
+        &key,
+        &pool_state,
+        4,
+        500,
+    )
+    .unwrap();
+    assert_eq!(
+        HouseInstruction::unpack(&ix.data).unwrap(),
+        HouseInstruction::RequestWithdrawal { ht_amount: 500 }
+    );
+    assert_eq!(ix.accounts.len(), 9);
+    assert!(ix.accounts[0].is_signer);
+    assert!(ix.accounts[0].is_writable);
+    assert_eq!(ix.accounts[1].pubkey, tail);
+    assert_eq!(ix.accounts[6].pubkey, pool_state);
+    assert_eq!(ix.accounts[8].pubkey, system_program::id());
+
+    // Filling needs no signer, so any keeper may crank the queue
+    let ix = instruction::fill_withdrawal(
+        &program_id,
+        &user,
+        &key,
+        &key,
+        &key,
+        &key,
+        &pool_state,
+        &key,
+        4,
+        252,
+    )
+    .unwrap();
+    assert_eq!(
+        HouseInstruction::unpack(&ix.data).unwrap(),
+        HouseInstruction::FillWithdrawal { bump_seed: 252 }
+    );
+    assert_eq!(ix.accounts.len(), 9);
+    assert!(ix.accounts.iter().all(|meta| !meta.is_signer));
+    assert_eq!(ix.accounts[0].pubkey, tail);
+    assert_eq!(ix.accounts[1].pubkey, user);
+}
+
+#[test]
+fn test_builders_pack_their_variant() {
+    let program_id = Pubkey::new_unique();
+    let key = Pubkey::new_unique();
+
+    let ix =
+        instruction::deposit(&program_id, &key, &key, &key, &key, &key, &key, &key, 5, 7).unwrap();
+    assert_eq!(ix.accounts.len(), 8);
+    assert!(ix.accounts[0].is_signer);
+    assert_eq!(
+        HouseInstruction::unpack(&ix.data).unwrap(),
+        HouseInstruction::Deposit {
+            usdt_amount: 5,
+            bump_seed: 7,
+        }
+    );
+
+    let ix =
+        instruction::withdraw(&program_id, &key, &key, &key, &key, &key, &key, &key, 5, 7).unwrap();
+    assert_eq!(ix.accounts.len(), 8);
+    assert_eq!(
+        HouseInstruction::unpack(&ix.data).unwrap(),
+        HouseInstruction::Withdraw {
+            ht_amount: 5,
+            bump_seed: 7,
+        }
+    );
+
+    let ix =
+        instruction::ownership(&program_id, &key, &key, &key, &key, &key, &key, &key, 3).unwrap();
+    assert_eq!(ix.accounts.len(), 7);
+    assert_eq!(
+        HouseInstruction::unpack(&ix.data).unwrap(),
+        HouseInstruction::Ownership { bump_seed: 3 }
+    );
+
+    let ix = instruction::freeze(&program_id, &key, &key, true, &[]).unwrap();
+    assert_eq!(ix.accounts.len(), 2);
+    assert_eq!(
+        HouseInstruction::unpack(&ix.data).unwrap(),
+        HouseInstruction::Freeze { freeze_pool: true }
+    );
+}
+
+#[test]
+fn test_authority_builders() {
+    let program_id = Pubkey::new_unique();
+    let authority = Pubkey::new_unique();
+    let pool_state = Pubkey::new_unique();
+    let new_authority = Pubkey::new_unique();
+    let signer = Pubkey::new_unique();
+
+    let ix = instruction::propose_authority(
+        &program_id,
+        &authority,
+        &pool_state,
+        &new_authority,
+        &[&signer],
+    )
+    .unwrap();
+    assert_eq!(ix.accounts.len(), 3);
+    assert!(!ix.accounts[0].is_signer);
+    assert!(ix.accounts[1].is_writable);
+    assert!(ix.accounts[2].is_signer);
+    assert_eq!(
+        HouseInstruction::unpack(&ix.data).unwrap(),
+        HouseInstruction::ProposeAuthority { new_authority }
+    );
+
+    let ix = instruction::accept_authority(&program_id, &new_authority, &pool_state, &[]).unwrap();
+    assert_eq!(ix.accounts.len(), 2);
+    assert!(ix.accounts[0].is_signer);
+    assert_eq!(
+        HouseInstruction::unpack(&ix.data).unwrap(),
+        HouseInstruction::AcceptAuthority
+    );
+
+    let ix = instruction::freeze(&program_id, &authority, &pool_state, true, &[&signer]).unwrap();
+    assert_eq!(ix.accounts.len(), 3);
+    assert!(!ix.accounts[0].is_signer);
+    assert_eq!(ix.accounts[2].pubkey, signer);
+    assert!(ix.accounts[2].is_signer);
+
+    let ix = instruction::init_multisig(&program_id, &authority, &[&signer], 1).unwrap();
+    assert_eq!(ix.accounts.len(), 2);
+    assert_eq!(
+        HouseInstruction::unpack(&ix.data).unwrap(),
+        HouseInstruction::InitMultisig { m: 1 }
+    );
+}
+
+#[test]
+fn test_role_builders() {
+    let program_id = Pubkey::new_unique();
+    let authority = Pubkey::new_unique();
+    let pool_state = Pubkey::new_unique();
+    let member = Pubkey::new_unique();
+
+    let ix = instruction::grant_role(
+        &program_id,
+        &authority,
+        &pool_state,
+        Role::Pauser,
+        &member,
+        &[],
+    )
+    .unwrap();
+    assert_eq!(ix.accounts.len(), 2);
+    assert!(ix.accounts[0].is_signer);
+    assert!(ix.accounts[1].is_writable);
+    assert_eq!(
+        HouseInstruction::unpack(&ix.data).unwrap(),
+        HouseInstruction::GrantRole {
+            role: Role::Pauser,
+            member
+        }
+    );
+
+    let ix = instruction::revoke_role(
+        &program_id,
+        &authority,
+        &pool_state,
+        Role::Settler,
+        &member,
+        &[&member],
+    )
+    .unwrap();
+    assert_eq!(ix.accounts.len(), 3);
+    assert!(!ix.accounts[0].is_signer);
+    assert_eq!(
+        HouseInstruction::unpack(&ix.data).unwrap(),
+        HouseInstruction::RevokeRole {
+            role: Role::Settler,
+            member
+        }
+    );
+}
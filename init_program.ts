@@ -30,7 +30,20 @@ export const STATE_ACCOUNT_DATA_LAYOUT = struct([
     blob(32, "insuranceFundUsdt"),
     blob(32, "divvyFoundationProceedsUsdt"),
     bool("frozenPool"),
-    bool("frozenBetting")
+    bool("frozenBetting"),
+    blob(2, "insuranceFundFeeBps"),
+    blob(2, "divvyFoundationFeeBps"),
+    blob(32, "authority"),
+    blob(33, "pendingAuthority"),
+    blob(512, "roles"),
+    uint64("betCutoffSecs"),
+    uint64("crankRewardLamports"),
+    blob(2, "maxMarketExposureBps"),
+    uint64("maxSidePayout"),
+    uint64("minBetRisk"),
+    uint64("maxBetRisk"),
+    uint64("maxWalletRisk"),
+    uint64("maxWalletPayout")
 ]);
 
 const INIT_PROGRAM_LAYOUT = struct([
@@ -1,44 +1,266 @@
-use solana_program::program_error::ProgramError;
-use std::convert::TryInto;
+use divvycommon::{
+    pack_text,
+    pda::{find_bet_address, find_market_address, find_position_address},
+    unpack_text,
+};
+use solana_program::{
+    instruction::{AccountMeta, Instruction},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    system_program,
+};
+use std::{
+    convert::{TryFrom, TryInto},
+    mem::size_of,
+};
 
 use crate::{
     error::ExchangeError::{self, InvalidInstruction},
-    state::BetType,
+    state::{BetType, ExposureLimits, OracleSource, Role, LABEL_LEN, LEAGUE_LEN},
 };
 
+#[derive(Clone, Debug, PartialEq)]
 pub enum ExchangeInstruction {
+    /// Deposits USDT into the house pool and mints house tokens to the user.
+    ///
+    ///   0. `[signer]` The user depositing USDT.
+    ///   1. `[writable]` The house token mint.
+    ///   2. `[]` The token program.
+    ///   3. `[writable]` The user's house token account.
+    ///   4. `[]` The program PDA, mint authority of the house token.
+    ///   5. `[writable]` The user's USDT account.
+    ///   6. `[writable]` The house pool USDT account.
+    ///   7. `[]` The house pool state account.
     Deposit {
         /// The amount party A expects to receive of token Y
         usdt_amount: u64,
         bump_seed: u8,
     },
+    /// Burns house tokens and pays the user their share of the house pool.
+    ///
+    ///   0. `[signer]` The user withdrawing.
+    ///   1. `[writable]` The house token mint.
+    ///   2. `[]` The token program.
+    ///   3. `[writable]` The user's house token account.
+    ///   4. `[]` The program PDA, owner of the house pool USDT account.
+    ///   5. `[writable]` The user's USDT account.
+    ///   6. `[writable]` The house pool USDT account.
+    ///   7. `[]` The house pool state account.
     Withdraw {
         /// the amount the taker expects to be paid in the other token, as a u64 because that's the max possible supply of a token
         ht_amount: u64,
         bump_seed: u8,
     },
+    /// Places a bet on one side of a market.
+    ///
+    ///   0. `[signer, writable]` The bettor, pays the bet and position account rent.
+    ///   1. `[]` The odds feed of the chosen market side.
+    ///   2. `[writable]` The bet account, at `find_bet_address` of the market, bettor and nonce.
+    ///   3. `[writable]` The position account, at `find_position_address` of the market and bettor.
+    ///   4. `[writable]` The market state account.
+    ///   5. `[writable]` The house pool state account.
+    ///   6. `[writable]` The house pool USDT account.
+    ///   7. `[writable]` The bettor's USDT account.
+    ///   8. `[]` The token program.
+    ///   9. `[]` The system program.
+    ///   10. `[]` The points feed of the chosen market side, spread and total markets only.
     Initbet {
         risk: u64,
-        odds: u64,
+        /// The American odds quoted to the bettor.
+        odds: i64,
         market_side: u8,
+        /// How much worse than the quoted payout the feed payout may be, in
+        /// basis points of the quoted payout, at most 10_000.
+        odds_tolerance_bps: u16,
+        /// Tells the bettor's bets on the market apart, part of the bet address seeds.
+        nonce: u64,
     },
-    SettleBet {
-        bump_seed: u8,
-    },
+    /// Settles a single bet of a settled market, paying out winners and
+    /// refunding pushes and voided bets.
+    ///
+    ///   0. `[signer]` The account settling the bet.
+    ///   1. `[]` The token program.
+    ///   2. `[writable]` The market state account.
+    ///   3. `[writable]` The bet account.
+    ///   4. `[]` The program PDA, owner of the house pool USDT account.
+    ///   5. `[writable]` The house pool USDT account.
+    ///   6. `[writable]` The bettor's USDT account.
+    ///   7. `[writable]` The bettor's main account, receives the bet account rent.
+    ///   8. `[writable]` The house pool state account.
+    SettleBet { bump_seed: u8 },
+    /// Initializes a market. Moneyline markets take three odds feeds, spread
+    /// and total markets take two odds feeds followed by two points feeds.
+    ///
+    ///   0. `[signer]` The authority or a market creator, or their multisig account.
+    ///   1. `[writable]` The market account, at `find_market_address` of the event and bet type.
+    ///   2. `[]` The result feed.
+    ///   3. `[]` The house pool state account.
+    ///   4. `[signer, writable]` The payer of the market account rent.
+    ///   5. `[]` The system program.
+    ///   6. `[]` The odds feed of market side 0.
+    ///   7. `[]` The odds feed of market side 1.
+    ///   8. `[]` The odds feed of market side 2, or the points feed of market side 0.
+    ///   9. `[]` The points feed of market side 1, spread and total markets only.
+    ///   The multisig signers follow the feeds, if the signer is a multisig.
     InitMoneylineMarket {
+        /// The event the market is on, part of the market address seeds.
+        event_id: u64,
         bet_type: BetType,
+        /// The oracle all feeds of the market are read from.
+        oracle_source: OracleSource,
+        /// The oldest feed round, in seconds, bets and settlement accept.
+        /// Zero disables the check.
+        max_staleness: u64,
+        /// The fewest oracle responses a feed round needs.
+        min_responses: u32,
+        /// Code of the league or sport of the event, UTF-8 padded with zeros.
+        league: [u8; LEAGUE_LEN],
+        /// Unix timestamp the event is scheduled to start at.
+        start_time: i64,
+        /// What each market side stands for, UTF-8 padded with zeros.
+        side_labels: [[u8; LABEL_LEN]; 3],
     },
-    SettleMoneylineMarket {
-        bump_seed: u8,
-    },
-    Ownership {
-        bump_seed: u8,
-    },
+    /// Settles a market from its result feed and takes the protocol fees. A
+    /// result feed reading `VOID_RESULT` voids the market without fees.
+    ///
+    ///   0. `[signer]` The account settling the market.
+    ///   1. `[writable]` The market state account.
+    ///   2. `[writable]` The house pool state account.
+    ///   3. `[]` The result feed.
+    ///   4. `[]` The program PDA, owner of the house pool USDT account.
+    ///   5. `[writable]` The house pool USDT account.
+    ///   6. `[writable]` The insurance fund USDT account.
+    ///   7. `[writable]` The Divvy foundation proceeds USDT account.
+    ///   8. `[]` The token program.
+    SettleMoneylineMarket { bump_seed: u8 },
+    /// Initializes the house pool state account.
+    ///
+    ///   0. `[signer]` The authority.
+    ///   1. `[writable]` The uninitialized, rent exempt house pool state account.
+    ///   2. `[]` The house token mint.
+    ///   3. `[]` The house pool USDT account.
+    ///   4. `[]` The insurance fund USDT account.
+    ///   5. `[]` The Divvy foundation proceeds USDT account.
+    Ownership { bump_seed: u8 },
+    /// Moves a market's locked liquidity to live liquidity once the game starts.
+    /// Anyone may commence a market once betting on it has closed.
+    ///
+    ///   0. `[signer]` The authority or a settler, or their multisig account.
+    ///      Any keeper once betting on the market has closed.
+    ///   1. `[writable]` The market state account.
+    ///   2. `[writable]` The house pool state account.
+    ///   3. ..3+M `[signer]` The multisig signers, if the signer is a multisig.
     CommenceMarket,
+    /// Freezes or unfreezes the house pool and betting.
+    ///
+    ///   0. `[signer]` The authority or a pauser, or their multisig account.
+    ///   1. `[writable]` The house pool state account.
+    ///   2. ..2+M `[signer]` The multisig signers, if the signer is a multisig.
     Freeze {
         freeze_pool: bool,
         freeze_betting: bool,
     },
+    /// Writes a manual feed, initializing it on the first write.
+    ///
+    ///   0. `[signer]` The authority or a settler, or their multisig account.
+    ///   1. `[writable]` The rent exempt manual feed account, owned by the program.
+    ///   2. `[]` The house pool state account.
+    ///   3. ..3+M `[signer]` The multisig signers, if the signer is a multisig.
+    UpdateManualFeed { value: i64, expo: i32 },
+    /// Sets the fees taken from the house profit when a market settles.
+    ///
+    ///   0. `[signer]` The authority or a treasurer, or their multisig account.
+    ///   1. `[writable]` The house pool state account.
+    ///   2. ..2+M `[signer]` The multisig signers, if the signer is a multisig.
+    UpdateFees {
+        /// Share of the house profit paid to the insurance fund, in basis points.
+        insurance_fund_fee_bps: u16,
+        /// Share of the house profit paid to the Divvy foundation, in basis points.
+        divvy_foundation_fee_bps: u16,
+    },
+    /// Proposes a new authority for the pool. It takes over once it accepts.
+    ///
+    ///   0. `[signer]` The authority, or its multisig account.
+    ///   1. `[writable]` The house pool state account.
+    ///   2. ..2+M `[signer]` The multisig signers, if the authority is a multisig.
+    ProposeAuthority { new_authority: Pubkey },
+    /// Makes the proposed authority the authority of the pool.
+    ///
+    ///   0. `[signer]` The proposed authority, or its multisig account.
+    ///   1. `[writable]` The house pool state account.
+    ///   2. ..2+M `[signer]` The multisig signers, if the authority is a multisig.
+    AcceptAuthority,
+    /// Initializes a multisig account that can act as the authority.
+    ///
+    ///   0. `[writable]` The rent exempt multisig account, owned by the program.
+    ///   1. ..1+N `[]` The signers, 1 to 11 of them.
+    InitMultisig {
+        /// The number of signers required.
+        m: u8,
+    },
+    /// Grants a role to a key, which may be a multisig.
+    ///
+    ///   0. `[signer]` The authority, or its multisig account.
+    ///   1. `[writable]` The house pool state account.
+    ///   2. ..2+M `[signer]` The multisig signers, if the authority is a multisig.
+    GrantRole { role: Role, member: Pubkey },
+    /// Revokes a role from a key.
+    ///
+    ///   0. `[signer]` The authority, or its multisig account.
+    ///   1. `[writable]` The house pool state account.
+    ///   2. ..2+M `[signer]` The multisig signers, if the authority is a multisig.
+    RevokeRole { role: Role, member: Pubkey },
+    /// Sets how long before a market's start time betting on it closes.
+    ///
+    ///   0. `[signer]` The authority or a settler, or their multisig account.
+    ///   1. `[writable]` The house pool state account.
+    ///   2. ..2+M `[signer]` The multisig signers, if the signer is a multisig.
+    UpdateBetCutoff { bet_cutoff_secs: u64 },
+    /// Settles a batch of bets of a settled market, paying out winners and
+    /// closing every bet account. Anyone may call it, and the keeper gets the
+    /// crank reward out of the rent of each bet it closes.
+    ///
+    ///   0. `[writable]` The keeper, receives the crank rewards.
+    ///   1. `[]` The token program.
+    ///   2. `[writable]` The market state account.
+    ///   3. `[]` The program PDA, owner of the house pool USDT account.
+    ///   4. `[writable]` The house pool USDT account.
+    ///   5. `[writable]` The house pool state account.
+    ///   6. ..6+3N For each bet:
+    ///      `[writable]` The bet account.
+    ///      `[writable]` The bettor's USDT account.
+    ///      `[writable]` The bettor's main account, receives the rest of the bet account rent.
+    SettleBets { bump_seed: u8 },
+    /// Sets the lamports of a bet's rent paid to the keeper settling it with
+    /// `SettleBets`, at most the rent of a bet account.
+    ///
+    ///   0. `[signer]` The authority or a treasurer, or their multisig account.
+    ///   1. `[writable]` The house pool state account.
+    ///   2. ..2+M `[signer]` The multisig signers, if the signer is a multisig.
+    UpdateCrankReward { crank_reward_lamports: u64 },
+    /// Voids a market before or after it commences, releasing its locked
+    /// liquidity. Every bet on it is refunded its risk when settled.
+    ///
+    ///   0. `[signer]` The authority or a settler, or their multisig account.
+    ///   1. `[writable]` The market state account.
+    ///   2. `[writable]` The house pool state account.
+    ///   3. ..3+M `[signer]` The multisig signers, if the signer is a multisig.
+    VoidMarket,
+    /// Suspends or resumes a single market. A suspended market takes no bets
+    /// and can not be settled, whatever the freeze flags of the pool.
+    ///
+    ///   0. `[signer]` The authority or a pauser, or their multisig account.
+    ///   1. `[writable]` The market state account.
+    ///   2. `[]` The house pool state account.
+    ///   3. ..3+M `[signer]` The multisig signers, if the signer is a multisig.
+    SuspendMarket { suspended: bool },
+    /// Sets the caps on the exposure of the pool to a market, a side and a
+    /// bet. A zero cap is no cap.
+    ///
+    ///   0. `[signer]` The authority or a treasurer, or their multisig account.
+    ///   1. `[writable]` The house pool state account.
+    ///   2. ..2+M `[signer]` The multisig signers, if the signer is a multisig.
+    UpdateExposureLimits { exposure_limits: ExposureLimits },
 }
 
 impl ExchangeInstruction {
@@ -57,16 +279,34 @@ impl ExchangeInstruction {
                 risk: Self::unpack_amount(rest)?,
                 odds: Self::unpack_odds(rest)?,
                 market_side: Self::unpack_market_side(rest)?,
+                odds_tolerance_bps: Self::unpack_odds_tolerance_bps(rest)?,
+                nonce: Self::unpack_nonce(rest)?,
             },
             3 => Self::SettleBet {
                 bump_seed: Self::unpack_last(rest)?,
             },
             4 => {
-                let (bet_type, _rest) = rest
+                let event_id = Self::unpack_amount(rest)?;
+                let rest = rest.get(8..).ok_or(ExchangeError::InvalidInstruction)?;
+                let (bet_type, rest) = rest
+                    .split_first()
+                    .ok_or(ExchangeError::InvalidInstruction)?;
+                let (oracle_source, rest) = rest
                     .split_first()
                     .ok_or(ExchangeError::InvalidInstruction)?;
                 Self::InitMoneylineMarket {
+                    event_id,
                     bet_type: BetType::unpack(bet_type)?,
+                    oracle_source: OracleSource::unpack(oracle_source)?,
+                    max_staleness: Self::unpack_amount(rest)?,
+                    min_responses: Self::unpack_min_responses(rest)?,
+                    league: Self::unpack_text(rest.get(12..20))?,
+                    start_time: Self::unpack_start_time(rest)?,
+                    side_labels: [
+                        Self::unpack_text(rest.get(28..60))?,
+                        Self::unpack_text(rest.get(60..92))?,
+                        Self::unpack_text(rest.get(92..124))?,
+                    ],
                 }
             }
             5 => Self::SettleMoneylineMarket {
@@ -88,10 +328,196 @@ impl ExchangeInstruction {
                     freeze_betting: *freeze_betting != 0,
                 }
             }
+            13 => Self::UpdateManualFeed {
+                value: Self::unpack_value(rest)?,
+                expo: Self::unpack_expo(rest)?,
+            },
+            14 => Self::UpdateFees {
+                insurance_fund_fee_bps: Self::unpack_insurance_fund_fee_bps(rest)?,
+                divvy_foundation_fee_bps: Self::unpack_divvy_foundation_fee_bps(rest)?,
+            },
+            15 => Self::ProposeAuthority {
+                new_authority: Self::unpack_pubkey(rest)?,
+            },
+            16 => Self::AcceptAuthority,
+            17 => {
+                let (m, _rest) = rest.split_first().ok_or(InvalidInstruction)?;
+                Self::InitMultisig { m: *m }
+            }
+            18 => {
+                let (role, rest) = rest.split_first().ok_or(InvalidInstruction)?;
+                Self::GrantRole {
+                    role: Role::unpack(role)?,
+                    member: Self::unpack_pubkey(rest)?,
+                }
+            }
+            19 => {
+                let (role, rest) = rest.split_first().ok_or(InvalidInstruction)?;
+                Self::RevokeRole {
+                    role: Role::unpack(role)?,
+                    member: Self::unpack_pubkey(rest)?,
+                }
+            }
+            20 => Self::UpdateBetCutoff {
+                bet_cutoff_secs: Self::unpack_amount(rest)?,
+            },
+            21 => Self::SettleBets {
+                bump_seed: Self::unpack_last(rest)?,
+            },
+            22 => Self::UpdateCrankReward {
+                crank_reward_lamports: Self::unpack_amount(rest)?,
+            },
+            23 => Self::VoidMarket,
+            24 => {
+                let (suspended, _rest) = rest.split_first().ok_or(InvalidInstruction)?;
+                Self::SuspendMarket {
+                    suspended: *suspended != 0,
+                }
+            }
+            25 => Self::UpdateExposureLimits {
+                exposure_limits: Self::unpack_exposure_limits(rest)?,
+            },
             _ => return Err(InvalidInstruction.into()),
         })
     }
 
+    pub fn pack(&self) -> Vec<u8> {
+        let mut buf = Vec::with_capacity(size_of::<Self>());
+        match self {
+            Self::Deposit {
+                usdt_amount,
+                bump_seed,
+            } => {
+                buf.push(0);
+                buf.extend_from_slice(&usdt_amount.to_le_bytes());
+                buf.push(*bump_seed);
+            }
+            Self::Withdraw {
+                ht_amount,
+                bump_seed,
+            } => {
+                buf.push(1);
+                buf.extend_from_slice(&ht_amount.to_le_bytes());
+                buf.push(*bump_seed);
+            }
+            Self::Initbet {
+                risk,
+                odds,
+                market_side,
+                odds_tolerance_bps,
+                nonce,
+            } => {
+                buf.push(2);
+                buf.extend_from_slice(&risk.to_le_bytes());
+                buf.extend_from_slice(&odds.to_le_bytes());
+                buf.push(*market_side);
+                buf.extend_from_slice(&odds_tolerance_bps.to_le_bytes());
+                buf.extend_from_slice(&nonce.to_le_bytes());
+            }
+            Self::SettleBet { bump_seed } => {
+                buf.push(3);
+                buf.push(*bump_seed);
+            }
+            Self::InitMoneylineMarket {
+                event_id,
+                bet_type,
+                oracle_source,
+                max_staleness,
+                min_responses,
+                league,
+                start_time,
+                side_labels,
+            } => {
+                buf.push(4);
+                buf.extend_from_slice(&event_id.to_le_bytes());
+                buf.push(bet_type.pack());
+                buf.push(oracle_source.pack());
+                buf.extend_from_slice(&max_staleness.to_le_bytes());
+                buf.extend_from_slice(&min_responses.to_le_bytes());
+                buf.extend_from_slice(league);
+                buf.extend_from_slice(&start_time.to_le_bytes());
+                for label in side_labels.iter() {
+                    buf.extend_from_slice(label);
+                }
+            }
+            Self::SettleMoneylineMarket { bump_seed } => {
+                buf.push(5);
+                buf.push(*bump_seed);
+            }
+            Self::Ownership { bump_seed } => {
+                buf.push(10);
+                buf.push(*bump_seed);
+            }
+            Self::CommenceMarket => buf.push(11),
+            Self::Freeze {
+                freeze_pool,
+                freeze_betting,
+            } => {
+                buf.push(12);
+                buf.push(*freeze_pool as u8);
+                buf.push(*freeze_betting as u8);
+            }
+            Self::UpdateManualFeed { value, expo } => {
+                buf.push(13);
+                buf.extend_from_slice(&value.to_le_bytes());
+                buf.extend_from_slice(&expo.to_le_bytes());
+            }
+            Self::UpdateFees {
+                insurance_fund_fee_bps,
+                divvy_foundation_fee_bps,
+            } => {
+                buf.push(14);
+                buf.extend_from_slice(&insurance_fund_fee_bps.to_le_bytes());
+                buf.extend_from_slice(&divvy_foundation_fee_bps.to_le_bytes());
+            }
+            Self::ProposeAuthority { new_authority } => {
+                buf.push(15);
+                buf.extend_from_slice(new_authority.as_ref());
+            }
+            Self::AcceptAuthority => buf.push(16),
+            Self::InitMultisig { m } => {
+                buf.push(17);
+                buf.push(*m);
+            }
+            Self::GrantRole { role, member } => {
+                buf.push(18);
+                buf.push(role.pack());
+                buf.extend_from_slice(member.as_ref());
+            }
+            Self::RevokeRole { role, member } => {
+                buf.push(19);
+                buf.push(role.pack());
+                buf.extend_from_slice(member.as_ref());
+            }
+            Self::UpdateBetCutoff { bet_cutoff_secs } => {
+                buf.push(20);
+                buf.extend_from_slice(&bet_cutoff_secs.to_le_bytes());
+            }
+            Self::SettleBets { bump_seed } => {
+                buf.push(21);
+                buf.push(*bump_seed);
+            }
+            Self::UpdateCrankReward {
+                crank_reward_lamports,
+            } => {
+                buf.push(22);
+                buf.extend_from_slice(&crank_reward_lamports.to_le_bytes());
+            }
+            Self::VoidMarket => buf.push(23),
+            Self::SuspendMarket { suspended } => {
+                buf.push(24);
+                buf.push(*suspended as u8);
+            }
+            Self::UpdateExposureLimits { exposure_limits } => {
+                buf.push(25);
+                let mut packed = [0; ExposureLimits::LEN];
+                exposure_limits.pack_into_slice(&mut packed);
+                buf.extend_from_slice(&packed);
+            }
+        };
+        buf
+    }
+
     // Todo: delete these 4 methods and use split_first, like in spl-token/instruction.rs
     fn unpack_last(input: &[u8]) -> Result<u8, ProgramError> {
         let (last, _rest) = input.split_last().ok_or(InvalidInstruction)?;
@@ -105,11 +531,11 @@ impl ExchangeInstruction {
             .ok_or(InvalidInstruction)?;
         Ok(amount)
     }
-    fn unpack_odds(input: &[u8]) -> Result<u64, ProgramError> {
+    fn unpack_odds(input: &[u8]) -> Result<i64, ProgramError> {
         let odds = input
             .get(8..16)
             .and_then(|slice| slice.try_into().ok())
-            .map(u64::from_le_bytes)
+            .map(i64::from_le_bytes)
             .ok_or(InvalidInstruction)?;
         Ok(odds)
     }
@@ -118,7 +544,810 @@ impl ExchangeInstruction {
             .get(16..17)
             .and_then(|slice| slice.try_into().ok())
             .map(u8::from_le_bytes)
+            //A market has 3 sides
+            .filter(|market_side| *market_side < 3)
             .ok_or(InvalidInstruction)?;
         Ok(market_side)
     }
+    fn unpack_odds_tolerance_bps(input: &[u8]) -> Result<u16, ProgramError> {
+        let odds_tolerance_bps = input
+            .get(17..19)
+            .and_then(|slice| slice.try_into().ok())
+            .map(u16::from_le_bytes)
+            .ok_or(InvalidInstruction)?;
+        Ok(odds_tolerance_bps)
+    }
+    fn unpack_nonce(input: &[u8]) -> Result<u64, ProgramError> {
+        let nonce = input
+            .get(19..27)
+            .and_then(|slice| slice.try_into().ok())
+            .map(u64::from_le_bytes)
+            .ok_or(InvalidInstruction)?;
+        Ok(nonce)
+    }
+    fn unpack_value(input: &[u8]) -> Result<i64, ProgramError> {
+        let value = input
+            .get(..8)
+            .and_then(|slice| slice.try_into().ok())
+            .map(i64::from_le_bytes)
+            .ok_or(InvalidInstruction)?;
+        Ok(value)
+    }
+    fn unpack_min_responses(input: &[u8]) -> Result<u32, ProgramError> {
+        let min_responses = input
+            .get(8..12)
+            .and_then(|slice| slice.try_into().ok())
+            .map(u32::from_le_bytes)
+            .ok_or(InvalidInstruction)?;
+        Ok(min_responses)
+    }
+    fn unpack_expo(input: &[u8]) -> Result<i32, ProgramError> {
+        let expo = input
+            .get(8..12)
+            .and_then(|slice| slice.try_into().ok())
+            .map(i32::from_le_bytes)
+            .ok_or(InvalidInstruction)?;
+        Ok(expo)
+    }
+    fn unpack_insurance_fund_fee_bps(input: &[u8]) -> Result<u16, ProgramError> {
+        let insurance_fund_fee_bps = input
+            .get(..2)
+            .and_then(|slice| slice.try_into().ok())
+            .map(u16::from_le_bytes)
+            .ok_or(InvalidInstruction)?;
+        Ok(insurance_fund_fee_bps)
+    }
+    fn unpack_divvy_foundation_fee_bps(input: &[u8]) -> Result<u16, ProgramError> {
+        let divvy_foundation_fee_bps = input
+            .get(2..4)
+            .and_then(|slice| slice.try_into().ok())
+            .map(u16::from_le_bytes)
+            .ok_or(InvalidInstruction)?;
+        Ok(divvy_foundation_fee_bps)
+    }
+    fn unpack_start_time(input: &[u8]) -> Result<i64, ProgramError> {
+        let start_time = input
+            .get(20..28)
+            .and_then(|slice| slice.try_into().ok())
+            .map(i64::from_le_bytes)
+            .ok_or(InvalidInstruction)?;
+        Ok(start_time)
+    }
+    fn unpack_text<T: for<'a> TryFrom<&'a [u8]>>(input: Option<&[u8]>) -> Result<T, ProgramError> {
+        let text = input
+            .filter(|slice| unpack_text(slice).is_some())
+            .and_then(|slice| slice.try_into().ok())
+            .ok_or(InvalidInstruction)?;
+        Ok(text)
+    }
+    fn unpack_pubkey(input: &[u8]) -> Result<Pubkey, ProgramError> {
+        let key = input.get(..32).map(Pubkey::new).ok_or(InvalidInstruction)?;
+        Ok(key)
+    }
+    fn unpack_exposure_limits(input: &[u8]) -> Result<ExposureLimits, ProgramError> {
+        let exposure_limits = input
+            .get(..ExposureLimits::LEN)
+            .and_then(|slice| slice.try_into().ok())
+            .map(ExposureLimits::unpack_from_slice)
+            .ok_or(InvalidInstruction)?;
+        Ok(exposure_limits)
+    }
+}
+
+/// Creates a `Deposit` instruction.
+pub fn deposit(
+    program_id: &Pubkey,
+    user: &Pubkey,
+    ht_mint: &Pubkey,
+    user_ht_account: &Pubkey,
+    pda: &Pubkey,
+    user_usdt_account: &Pubkey,
+    pool_usdt_account: &Pubkey,
+    pool_state: &Pubkey,
+    usdt_amount: u64,
+    bump_seed: u8,
+) -> Result<Instruction, ProgramError> {
+    let data = ExchangeInstruction::Deposit {
+        usdt_amount,
+        bump_seed,
+    }
+    .pack();
+
+    let accounts = vec![
+        AccountMeta::new_readonly(*user, true),
+        AccountMeta::new(*ht_mint, false),
+        AccountMeta::new_readonly(spl_token::id(), false),
+        AccountMeta::new(*user_ht_account, false),
+        AccountMeta::new_readonly(*pda, false),
+        AccountMeta::new(*user_usdt_account, false),
+        AccountMeta::new(*pool_usdt_account, false),
+        AccountMeta::new_readonly(*pool_state, false),
+    ];
+
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data,
+    })
+}
+
+/// Creates a `Withdraw` instruction.
+pub fn withdraw(
+    program_id: &Pubkey,
+    user: &Pubkey,
+    ht_mint: &Pubkey,
+    user_ht_account: &Pubkey,
+    pda: &Pubkey,
+    user_usdt_account: &Pubkey,
+    pool_usdt_account: &Pubkey,
+    pool_state: &Pubkey,
+    ht_amount: u64,
+    bump_seed: u8,
+) -> Result<Instruction, ProgramError> {
+    let data = ExchangeInstruction::Withdraw {
+        ht_amount,
+        bump_seed,
+    }
+    .pack();
+
+    let accounts = vec![
+        AccountMeta::new_readonly(*user, true),
+        AccountMeta::new(*ht_mint, false),
+        AccountMeta::new_readonly(spl_token::id(), false),
+        AccountMeta::new(*user_ht_account, false),
+        AccountMeta::new_readonly(*pda, false),
+        AccountMeta::new(*user_usdt_account, false),
+        AccountMeta::new(*pool_usdt_account, false),
+        AccountMeta::new_readonly(*pool_state, false),
+    ];
+
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data,
+    })
+}
+
+/// Creates an `Initbet` instruction for the bet at `find_bet_address`. Spread
+/// and total bets also pass the `points_feed` of the chosen market side.
+pub fn init_bet(
+    program_id: &Pubkey,
+    initializer: &Pubkey,
+    feed: &Pubkey,
+    points_feed: Option<&Pubkey>,
+    market: &Pubkey,
+    pool_state: &Pubkey,
+    pool_usdt_account: &Pubkey,
+    user_usdt_account: &Pubkey,
+    risk: u64,
+    odds: i64,
+    market_side: u8,
+    odds_tolerance_bps: u16,
+    nonce: u64,
+) -> Result<Instruction, ProgramError> {
+    let data = ExchangeInstruction::Initbet {
+        risk,
+        odds,
+        market_side,
+        odds_tolerance_bps,
+        nonce,
+    }
+    .pack();
+    let (bet, _) = find_bet_address(program_id, market, initializer, nonce);
+    let (position, _) = find_position_address(program_id, market, initializer);
+
+    let mut accounts = vec![
+        AccountMeta::new(*initializer, true),
+        AccountMeta::new_readonly(*feed, false),
+        AccountMeta::new(bet, false),
+        AccountMeta::new(position, false),
+        AccountMeta::new(*market, false),
+        AccountMeta::new(*pool_state, false),
+        AccountMeta::new(*pool_usdt_account, false),
+        AccountMeta::new(*user_usdt_account, false),
+        AccountMeta::new_readonly(spl_token::id(), false),
+        AccountMeta::new_readonly(system_program::id(), false),
+    ];
+    if let Some(points_feed) = points_feed {
+        accounts.push(AccountMeta::new_readonly(*points_feed, false));
+    }
+
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data,
+    })
+}
+
+/// Creates a `SettleBet` instruction.
+pub fn settle_bet(
+    program_id: &Pubkey,
+    initializer: &Pubkey,
+    market: &Pubkey,
+    bet: &Pubkey,
+    pda: &Pubkey,
+    pool_usdt_account: &Pubkey,
+    user_usdt_account: &Pubkey,
+    user_main_account: &Pubkey,
+    pool_state: &Pubkey,
+    bump_seed: u8,
+) -> Result<Instruction, ProgramError> {
+    let data = ExchangeInstruction::SettleBet { bump_seed }.pack();
+
+    let accounts = vec![
+        AccountMeta::new_readonly(*initializer, true),
+        AccountMeta::new_readonly(spl_token::id(), false),
+        AccountMeta::new(*market, false),
+        AccountMeta::new(*bet, false),
+        AccountMeta::new_readonly(*pda, false),
+        AccountMeta::new(*pool_usdt_account, false),
+        AccountMeta::new(*user_usdt_account, false),
+        AccountMeta::new(*user_main_account, false),
+        AccountMeta::new(*pool_state, false),
+    ];
+
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data,
+    })
+}
+
+/// Creates a `SettleBets` instruction. `bets` are the bet accounts, each with
+/// the bettor's USDT and main accounts recorded in it.
+pub fn settle_bets(
+    program_id: &Pubkey,
+    keeper: &Pubkey,
+    market: &Pubkey,
+    pda: &Pubkey,
+    pool_usdt_account: &Pubkey,
+    pool_state: &Pubkey,
+    bets: &[(&Pubkey, &Pubkey, &Pubkey)],
+    bump_seed: u8,
+) -> Result<Instruction, ProgramError> {
+    let data = ExchangeInstruction::SettleBets { bump_seed }.pack();
+
+    let mut accounts = Vec::with_capacity(6 + 3 * bets.len());
+    accounts.push(AccountMeta::new(*keeper, false));
+    accounts.push(AccountMeta::new_readonly(spl_token::id(), false));
+    accounts.push(AccountMeta::new(*market, false));
+    accounts.push(AccountMeta::new_readonly(*pda, false));
+    accounts.push(AccountMeta::new(*pool_usdt_account, false));
+    accounts.push(AccountMeta::new(*pool_state, false));
+    for (bet, user_usdt_account, user_main_account) in bets.iter() {
+        accounts.push(AccountMeta::new(**bet, false));
+        accounts.push(AccountMeta::new(**user_usdt_account, false));
+        accounts.push(AccountMeta::new(**user_main_account, false));
+    }
+
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data,
+    })
+}
+
+/// Creates an `InitMoneylineMarket` instruction for the market at
+/// `find_market_address`. Moneyline markets expect three `odds_feeds` and no
+/// `points_feeds`, spread and total markets expect two of each, and a label
+/// for each of their sides. `signers` are the multisig signers, empty if the
+/// signer is a wallet.
+pub fn init_moneyline_market(
+    program_id: &Pubkey,
+    initializer: &Pubkey,
+    payer: &Pubkey,
+    result_feed: &Pubkey,
+    pool_state: &Pubkey,
+    odds_feeds: &[&Pubkey],
+    points_feeds: &[&Pubkey],
+    event_id: u64,
+    bet_type: BetType,
+    oracle_source: OracleSource,
+    max_staleness: u64,
+    min_responses: u32,
+    league: &str,
+    start_time: i64,
+    side_labels: &[&str],
+    signers: &[&Pubkey],
+) -> Result<Instruction, ProgramError> {
+    let expected_feeds = match bet_type {
+        BetType::MoneyLine => (3, 0),
+        BetType::Spread | BetType::Total => (2, 2),
+    };
+    if (odds_feeds.len(), points_feeds.len()) != expected_feeds
+        || side_labels.len() != odds_feeds.len()
+    {
+        return Err(ExchangeError::InvalidInstruction.into());
+    }
+    let mut packed_labels = [[0; LABEL_LEN]; 3];
+    for (packed, label) in packed_labels.iter_mut().zip(side_labels.iter()) {
+        *packed = pack_text(label)?;
+    }
+    let data = ExchangeInstruction::InitMoneylineMarket {
+        event_id,
+        bet_type,
+        oracle_source,
+        max_staleness,
+        min_responses,
+        league: pack_text(league)?,
+        start_time,
+        side_labels: packed_labels,
+    }
+    .pack();
+    let (market, _) = find_market_address(program_id, event_id, bet_type);
+
+    let mut accounts =
+        Vec::with_capacity(6 + odds_feeds.len() + points_feeds.len() + signers.len());
+    accounts.push(AccountMeta::new_readonly(*initializer, signers.is_empty()));
+    accounts.push(AccountMeta::new(market, false));
+    accounts.push(AccountMeta::new_readonly(*result_feed, false));
+    accounts.push(AccountMeta::new_readonly(*pool_state, false));
+    accounts.push(AccountMeta::new(*payer, true));
+    accounts.push(AccountMeta::new_readonly(system_program::id(), false));
+    for feed in odds_feeds.iter().chain(points_feeds.iter()) {
+        accounts.push(AccountMeta::new_readonly(**feed, false));
+    }
+    for signer in signers.iter() {
+        accounts.push(AccountMeta::new_readonly(**signer, true));
+    }
+
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data,
+    })
+}
+
+/// Creates a `SettleMoneylineMarket` instruction.
+pub fn settle_moneyline_market(
+    program_id: &Pubkey,
+    initializer: &Pubkey,
+    market: &Pubkey,
+    pool_state: &Pubkey,
+    result_feed: &Pubkey,
+    pda: &Pubkey,
+    pool_usdt_account: &Pubkey,
+    insurance_fund_usdt_account: &Pubkey,
+    divvy_foundation_proceeds_usdt_account: &Pubkey,
+    bump_seed: u8,
+) -> Result<Instruction, ProgramError> {
+    let data = ExchangeInstruction::SettleMoneylineMarket { bump_seed }.pack();
+
+    let accounts = vec![
+        AccountMeta::new_readonly(*initializer, true),
+        AccountMeta::new(*market, false),
+        AccountMeta::new(*pool_state, false),
+        AccountMeta::new_readonly(*result_feed, false),
+        AccountMeta::new_readonly(*pda, false),
+        AccountMeta::new(*pool_usdt_account, false),
+        AccountMeta::new(*insurance_fund_usdt_account, false),
+        AccountMeta::new(*divvy_foundation_proceeds_usdt_account, false),
+        AccountMeta::new_readonly(spl_token::id(), false),
+    ];
+
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data,
+    })
+}
+
+/// Creates an `Ownership` instruction.
+pub fn ownership(
+    program_id: &Pubkey,
+    initializer: &Pubkey,
+    pool_state: &Pubkey,
+    ht_mint: &Pubkey,
+    pool_usdt_account: &Pubkey,
+    insurance_fund_usdt_account: &Pubkey,
+    divvy_foundation_proceeds_usdt_account: &Pubkey,
+    bump_seed: u8,
+) -> Result<Instruction, ProgramError> {
+    let data = ExchangeInstruction::Ownership { bump_seed }.pack();
+
+    let accounts = vec![
+        AccountMeta::new_readonly(*initializer, true),
+        AccountMeta::new(*pool_state, false),
+        AccountMeta::new_readonly(*ht_mint, false),
+        AccountMeta::new_readonly(*pool_usdt_account, false),
+        AccountMeta::new_readonly(*insurance_fund_usdt_account, false),
+        AccountMeta::new_readonly(*divvy_foundation_proceeds_usdt_account, false),
+    ];
+
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data,
+    })
+}
+
+/// Creates a `CommenceMarket` instruction. `signers` are the multisig
+/// signers, empty if the signer is a wallet.
+pub fn commence_market(
+    program_id: &Pubkey,
+    initializer: &Pubkey,
+    market: &Pubkey,
+    pool_state: &Pubkey,
+    signers: &[&Pubkey],
+) -> Result<Instruction, ProgramError> {
+    let data = ExchangeInstruction::CommenceMarket.pack();
+
+    let mut accounts = vec![
+        AccountMeta::new_readonly(*initializer, signers.is_empty()),
+        AccountMeta::new(*market, false),
+        AccountMeta::new(*pool_state, false),
+    ];
+    for signer in signers.iter() {
+        accounts.push(AccountMeta::new_readonly(**signer, true));
+    }
+
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data,
+    })
+}
+
+/// Creates a `Freeze` instruction. `signers` are the multisig
+/// signers, empty if the signer is a wallet.
+pub fn freeze(
+    program_id: &Pubkey,
+    initializer: &Pubkey,
+    pool_state: &Pubkey,
+    freeze_pool: bool,
+    freeze_betting: bool,
+    signers: &[&Pubkey],
+) -> Result<Instruction, ProgramError> {
+    let data = ExchangeInstruction::Freeze {
+        freeze_pool,
+        freeze_betting,
+    }
+    .pack();
+
+    let mut accounts = vec![
+        AccountMeta::new_readonly(*initializer, signers.is_empty()),
+        AccountMeta::new(*pool_state, false),
+    ];
+    for signer in signers.iter() {
+        accounts.push(AccountMeta::new_readonly(**signer, true));
+    }
+
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data,
+    })
+}
+
+/// Creates an `UpdateManualFeed` instruction. `signers` are the multisig
+/// signers, empty if the signer is a wallet.
+pub fn update_manual_feed(
+    program_id: &Pubkey,
+    initializer: &Pubkey,
+    feed: &Pubkey,
+    pool_state: &Pubkey,
+    value: i64,
+    expo: i32,
+    signers: &[&Pubkey],
+) -> Result<Instruction, ProgramError> {
+    let data = ExchangeInstruction::UpdateManualFeed { value, expo }.pack();
+
+    let mut accounts = vec![
+        AccountMeta::new_readonly(*initializer, signers.is_empty()),
+        AccountMeta::new(*feed, false),
+        AccountMeta::new_readonly(*pool_state, false),
+    ];
+    for signer in signers.iter() {
+        accounts.push(AccountMeta::new_readonly(**signer, true));
+    }
+
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data,
+    })
+}
+
+/// Creates an `UpdateFees` instruction. `signers` are the multisig
+/// signers, empty if the signer is a wallet.
+pub fn update_fees(
+    program_id: &Pubkey,
+    initializer: &Pubkey,
+    pool_state: &Pubkey,
+    insurance_fund_fee_bps: u16,
+    divvy_foundation_fee_bps: u16,
+    signers: &[&Pubkey],
+) -> Result<Instruction, ProgramError> {
+    let data = ExchangeInstruction::UpdateFees {
+        insurance_fund_fee_bps,
+        divvy_foundation_fee_bps,
+    }
+    .pack();
+
+    let mut accounts = vec![
+        AccountMeta::new_readonly(*initializer, signers.is_empty()),
+        AccountMeta::new(*pool_state, false),
+    ];
+    for signer in signers.iter() {
+        accounts.push(AccountMeta::new_readonly(**signer, true));
+    }
+
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data,
+    })
+}
+
+/// Creates a `ProposeAuthority` instruction. `signers` are the multisig
+/// signers, empty if the authority is a wallet.
+pub fn propose_authority(
+    program_id: &Pubkey,
+    authority: &Pubkey,
+    pool_state: &Pubkey,
+    new_authority: &Pubkey,
+    signers: &[&Pubkey],
+) -> Result<Instruction, ProgramError> {
+    let data = ExchangeInstruction::ProposeAuthority {
+        new_authority: *new_authority,
+    }
+    .pack();
+
+    let mut accounts = Vec::with_capacity(2 + signers.len());
+    accounts.push(AccountMeta::new_readonly(*authority, signers.is_empty()));
+    accounts.push(AccountMeta::new(*pool_state, false));
+    for signer in signers.iter() {
+        accounts.push(AccountMeta::new_readonly(**signer, true));
+    }
+
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data,
+    })
+}
+
+/// Creates an `AcceptAuthority` instruction. `signers` are the multisig
+/// signers, empty if the proposed authority is a wallet.
+pub fn accept_authority(
+    program_id: &Pubkey,
+    pending_authority: &Pubkey,
+    pool_state: &Pubkey,
+    signers: &[&Pubkey],
+) -> Result<Instruction, ProgramError> {
+    let data = ExchangeInstruction::AcceptAuthority.pack();
+
+    let mut accounts = Vec::with_capacity(2 + signers.len());
+    accounts.push(AccountMeta::new_readonly(
+        *pending_authority,
+        signers.is_empty(),
+    ));
+    accounts.push(AccountMeta::new(*pool_state, false));
+    for signer in signers.iter() {
+        accounts.push(AccountMeta::new_readonly(**signer, true));
+    }
+
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data,
+    })
+}
+
+/// Creates an `InitMultisig` instruction.
+pub fn init_multisig(
+    program_id: &Pubkey,
+    multisig: &Pubkey,
+    signers: &[&Pubkey],
+    m: u8,
+) -> Result<Instruction, ProgramError> {
+    let data = ExchangeInstruction::InitMultisig { m }.pack();
+
+    let mut accounts = Vec::with_capacity(1 + signers.len());
+    accounts.push(AccountMeta::new(*multisig, false));
+    for signer in signers.iter() {
+        accounts.push(AccountMeta::new_readonly(**signer, false));
+    }
+
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data,
+    })
+}
+
+/// Creates a `GrantRole` instruction. `signers` are the multisig signers,
+/// empty if the authority is a wallet.
+pub fn grant_role(
+    program_id: &Pubkey,
+    authority: &Pubkey,
+    pool_state: &Pubkey,
+    role: Role,
+    member: &Pubkey,
+    signers: &[&Pubkey],
+) -> Result<Instruction, ProgramError> {
+    let data = ExchangeInstruction::GrantRole {
+        role,
+        member: *member,
+    }
+    .pack();
+
+    let mut accounts = Vec::with_capacity(2 + signers.len());
+    accounts.push(AccountMeta::new_readonly(*authority, signers.is_empty()));
+    accounts.push(AccountMeta::new(*pool_state, false));
+    for signer in signers.iter() {
+        accounts.push(AccountMeta::new_readonly(**signer, true));
+    }
+
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data,
+    })
+}
+
+/// Creates a `RevokeRole` instruction. `signers` are the multisig signers,
+/// empty if the authority is a wallet.
+pub fn revoke_role(
+    program_id: &Pubkey,
+    authority: &Pubkey,
+    pool_state: &Pubkey,
+    role: Role,
+    member: &Pubkey,
+    signers: &[&Pubkey],
+) -> Result<Instruction, ProgramError> {
+    let data = ExchangeInstruction::RevokeRole {
+        role,
+        member: *member,
+    }
+    .pack();
+
+    let mut accounts = Vec::with_capacity(2 + signers.len());
+    accounts.push(AccountMeta::new_readonly(*authority, signers.is_empty()));
+    accounts.push(AccountMeta::new(*pool_state, false));
+    for signer in signers.iter() {
+        accounts.push(AccountMeta::new_readonly(**signer, true));
+    }
+
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data,
+    })
+}
+
+/// Creates an `UpdateBetCutoff` instruction. `signers` are the multisig
+/// signers, empty if the signer is a wallet.
+pub fn update_bet_cutoff(
+    program_id: &Pubkey,
+    initializer: &Pubkey,
+    pool_state: &Pubkey,
+    bet_cutoff_secs: u64,
+    signers: &[&Pubkey],
+) -> Result<Instruction, ProgramError> {
+    let data = ExchangeInstruction::UpdateBetCutoff { bet_cutoff_secs }.pack();
+
+    let mut accounts = vec![
+        AccountMeta::new_readonly(*initializer, signers.is_empty()),
+        AccountMeta::new(*pool_state, false),
+    ];
+    for signer in signers.iter() {
+        accounts.push(AccountMeta::new_readonly(**signer, true));
+    }
+
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data,
+    })
+}
+
+/// Creates an `UpdateCrankReward` instruction. `signers` are the multisig
+/// signers, empty if the signer is a wallet.
+pub fn update_crank_reward(
+    program_id: &Pubkey,
+    initializer: &Pubkey,
+    pool_state: &Pubkey,
+    crank_reward_lamports: u64,
+    signers: &[&Pubkey],
+) -> Result<Instruction, ProgramError> {
+    let data = ExchangeInstruction::UpdateCrankReward {
+        crank_reward_lamports,
+    }
+    .pack();
+
+    let mut accounts = vec![
+        AccountMeta::new_readonly(*initializer, signers.is_empty()),
+        AccountMeta::new(*pool_state, false),
+    ];
+    for signer in signers.iter() {
+        accounts.push(AccountMeta::new_readonly(**signer, true));
+    }
+
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data,
+    })
+}
+
+/// Creates a `VoidMarket` instruction. `signers` are the multisig
+/// signers, empty if the signer is a wallet.
+pub fn void_market(
+    program_id: &Pubkey,
+    initializer: &Pubkey,
+    market: &Pubkey,
+    pool_state: &Pubkey,
+    signers: &[&Pubkey],
+) -> Result<Instruction, ProgramError> {
+    let data = ExchangeInstruction::VoidMarket.pack();
+
+    let mut accounts = vec![
+        AccountMeta::new_readonly(*initializer, signers.is_empty()),
+        AccountMeta::new(*market, false),
+        AccountMeta::new(*pool_state, false),
+    ];
+    for signer in signers.iter() {
+        accounts.push(AccountMeta::new_readonly(**signer, true));
+    }
+
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data,
+    })
+}
+
+/// Creates a `SuspendMarket` instruction. `signers` are the multisig
+/// signers, empty if the signer is a wallet.
+pub fn suspend_market(
+    program_id: &Pubkey,
+    initializer: &Pubkey,
+    market: &Pubkey,
+    pool_state: &Pubkey,
+    suspended: bool,
+    signers: &[&Pubkey],
+) -> Result<Instruction, ProgramError> {
+    let data = ExchangeInstruction::SuspendMarket { suspended }.pack();
+
+    let mut accounts = vec![
+        AccountMeta::new_readonly(*initializer, signers.is_empty()),
+        AccountMeta::new(*market, false),
+        AccountMeta::new_readonly(*pool_state, false),
+    ];
+    for signer in signers.iter() {
+        accounts.push(AccountMeta::new_readonly(**signer, true));
+    }
+
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data,
+    })
+}
+
+/// Creates an `UpdateExposureLimits` instruction. `signers` are the multisig
+/// signers, empty if the signer is a wallet.
+pub fn update_exposure_limits(
+    program_id: &Pubkey,
+    initializer: &Pubkey,
+    pool_state: &Pubkey,
+    exposure_limits: ExposureLimits,
+    signers: &[&Pubkey],
+) -> Result<Instruction, ProgramError> {
+    let data = ExchangeInstruction::UpdateExposureLimits { exposure_limits }.pack();
+
+    let mut accounts = vec![
+        AccountMeta::new_readonly(*initializer, signers.is_empty()),
+        AccountMeta::new(*pool_state, false),
+    ];
+    for signer in signers.iter() {
+        accounts.push(AccountMeta::new_readonly(**signer, true));
+    }
+
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data,
+    })
 }
@@ -0,0 +1,115 @@
+mod common;
+
+use common::{exchange_error, funded_divvy, now, program_test};
+use divvyexchange::{
+    error::ExchangeError,
+    instruction,
+    state::{BetType, ExposureLimits, HpLiquidity, Market, OracleSource, Roles},
+};
+use solana_program::{program_pack::Pack, pubkey::Pubkey};
+use solana_sdk::{
+    account::Account,
+    signature::{Keypair, Signer},
+};
+
+#[tokio::test]
+async fn test_forged_pool_state_rejected() {
+    let program_id = Pubkey::new_unique();
+    let attacker = Keypair::new();
+
+    // A pool state naming the attacker as authority, in an account the attacker owns
+    let forged_pool_state = Pubkey::new_unique();
+    let mut data = vec![0; HpLiquidity::LEN];
+    HpLiquidity::pack(
+        HpLiquidity {
+            is_initialized: true,
+            locked_liquidity: 0,
+            live_liquidity: 0,
+            bettor_balance: 0,
+            pending_bets: 0,
+            ht_mint: Pubkey::new_unique(),
+            pool_usdt: Pubkey::new_unique(),
+            insurance_fund_usdt: Pubkey::new_unique(),
+            divvy_foundation_proceeds_usdt: Pubkey::new_unique(),
+            frozen_pool: false,
+            frozen_betting: false,
+            insurance_fund_fee_bps: 0,
+            divvy_foundation_fee_bps: 0,
+            authority: attacker.pubkey(),
+            pending_authority: None,
+            roles: Roles::default(),
+            bet_cutoff_secs: 0,
+            crank_reward_lamports: 0,
+            exposure_limits: ExposureLimits::default(),
+        },
+        &mut data,
+    )
+    .unwrap();
+    let mut program_test = program_test(&program_id);
+    program_test.add_account(
+        forged_pool_state,
+        Account {
+            lamports: 1_000_000_000,
+            data,
+            owner: attacker.pubkey(),
+            executable: false,
+            rent_epoch: 0,
+        },
+    );
+    let mut divvy = funded_divvy(program_test, program_id).await;
+    let feed = divvy.create_manual_feed(-110, 0).await.unwrap().pubkey();
+    let market = divvy
+        .init_market(&feed, &[&feed, &feed, &feed], &[], BetType::MoneyLine)
+        .await
+        .unwrap();
+
+    let update_feed = instruction::update_manual_feed(
+        &program_id,
+        &attacker.pubkey(),
+        &feed,
+        &forged_pool_state,
+        10_000,
+        0,
+        &[],
+    )
+    .unwrap();
+    let suspend = instruction::suspend_market(
+        &program_id,
+        &attacker.pubkey(),
+        &market,
+        &forged_pool_state,
+        true,
+        &[],
+    )
+    .unwrap();
+    let init_market = instruction::init_moneyline_market(
+        &program_id,
+        &attacker.pubkey(),
+        &divvy.payer.pubkey(),
+        &feed,
+        &forged_pool_state,
+        &[&feed, &feed, &feed],
+        &[],
+        u64::MAX,
+        BetType::MoneyLine,
+        OracleSource::Manual,
+        0,
+        0,
+        "TEST",
+        now() + 86_400,
+        &["Home", "Away", "Draw"],
+        &[],
+    )
+    .unwrap();
+    for instruction in [update_feed, suspend, init_market].iter().cloned() {
+        assert_eq!(
+            divvy
+                .process(&[instruction], &[&attacker])
+                .await
+                .unwrap_err(),
+            exchange_error(ExchangeError::InvalidHousePoolStateAccount)
+        );
+    }
+    let market_state: Market = divvy.unpack(&market).await;
+    assert!(!market_state.suspended);
+}
@@ -0,0 +1,501 @@
+#![allow(dead_code)]
+
+use divvycommon::fees::{DEFAULT_DIVVY_FOUNDATION_FEE_BPS, DEFAULT_INSURANCE_FUND_FEE_BPS};
+use divvycommon::pda::{find_bet_address, find_market_address};
+use divvyexchange::{
+    error::ExchangeError,
+    instruction,
+    processor::Processor,
+    state::{BetType, ExposureLimits, HpLiquidity, ManualFeed, OracleSource, Roles},
+};
+use solana_program::{
+    instruction::{Instruction, InstructionError},
+    native_token::LAMPORTS_PER_SOL,
+    program_pack::{IsInitialized, Pack},
+    pubkey::Pubkey,
+    rent::Rent,
+    system_instruction,
+};
+use solana_program_test::{processor, BanksClient, ProgramTest};
+use solana_sdk::{
+    account::Account,
+    hash::Hash,
+    signature::{Keypair, Signer},
+    transaction::{Transaction, TransactionError},
+};
+use spl_token::state::{Account as TokenAccount, Mint};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+pub const USDT: u64 = 1_000_000;
+
+pub fn program_test(program_id: &Pubkey) -> ProgramTest {
+    ProgramTest::new("divvyexchange", *program_id, processor!(Processor::process))
+}
+
+/// The wall clock unix timestamp, which the test bank clock starts from.
+pub fn now() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_secs() as i64
+}
+
+/// The error a single instruction transaction fails with.
+pub fn exchange_error(error: ExchangeError) -> TransactionError {
+    TransactionError::InstructionError(0, InstructionError::Custom(error as u32))
+}
+
+/// A user with a funded USDT account and an empty house token account.
+pub struct User {
+    pub keypair: Keypair,
+    pub usdt_account: Keypair,
+    pub ht_account: Keypair,
+}
+
+/// The program booted with an initialized house pool.
+pub struct Divvy {
+    pub banks_client: BanksClient,
+    pub payer: Keypair,
+    pub recent_blockhash: Hash,
+    pub program_id: Pubkey,
+    pub authority: Keypair,
+    pub usdt_mint: Keypair,
+    pub pda: Pubkey,
+    pub bump_seed: u8,
+    pub pool_state: Keypair,
+    pub pool_usdt_account: Keypair,
+    pub ht_mint: Keypair,
+    pub insurance_fund_usdt_account: Keypair,
+    pub divvy_foundation_proceeds_usdt_account: Keypair,
+    /// Event id of the next market, so each test market gets its own address.
+    pub next_event_id: u64,
+    /// Nonce of the next bet.
+    pub next_nonce: u64,
+}
+
+impl Divvy {
+    /// `Ownership` only accepts the hard-coded program authority, so the pool
+    /// state is loaded already initialized with a test authority instead.
+    pub async fn start(mut program_test: ProgramTest, program_id: Pubkey) -> Self {
+        let (pda, bump_seed) = Pubkey::find_program_address(&[b"divvyexchange"], &program_id);
+        let authority = Keypair::new();
+        let pool_state = Keypair::new();
+        let pool_usdt_account = Keypair::new();
+        let ht_mint = Keypair::new();
+        let insurance_fund_usdt_account = Keypair::new();
+        let divvy_foundation_proceeds_usdt_account = Keypair::new();
+
+        let mut data = vec![0; HpLiquidity::LEN];
+        HpLiquidity {
+            is_initialized: true,
+            locked_liquidity: 0,
+            live_liquidity: 0,
+            bettor_balance: 0,
+            pending_bets: 0,
+            ht_mint: ht_mint.pubkey(),
+            pool_usdt: pool_usdt_account.pubkey(),
+            insurance_fund_usdt: insurance_fund_usdt_account.pubkey(),
+            divvy_foundation_proceeds_usdt: divvy_foundation_proceeds_usdt_account.pubkey(),
+            frozen_pool: false,
+            frozen_betting: false,
+            insurance_fund_fee_bps: DEFAULT_INSURANCE_FUND_FEE_BPS,
+            divvy_foundation_fee_bps: DEFAULT_DIVVY_FOUNDATION_FEE_BPS,
+            authority: authority.pubkey(),
+            pending_authority: None,
+            roles: Roles::default(),
+            bet_cutoff_secs: 0,
+            crank_reward_lamports: 0,
+            exposure_limits: ExposureLimits::default(),
+        }
+        .pack_into_slice(&mut data);
+        program_test.add_account(
+            pool_state.pubkey(),
+            Account {
+                lamports: Rent::default().minimum_balance(HpLiquidity::LEN),
+                data,
+                owner: program_id,
+                executable: false,
+                rent_epoch: 0,
+            },
+        );
+
+        let (banks_client, payer, recent_blockhash) = program_test.start().await;
+        let mut divvy = Divvy {
+            banks_client,
+            payer,
+            recent_blockhash,
+            program_id,
+            authority,
+            usdt_mint: Keypair::new(),
+            pda,
+            bump_seed,
+            pool_state,
+            pool_usdt_account,
+            ht_mint,
+            insurance_fund_usdt_account,
+            divvy_foundation_proceeds_usdt_account,
+            next_event_id: 0,
+            next_nonce: 0,
+        };
+
+        let payer = divvy.payer.pubkey();
+        let usdt_mint = divvy.usdt_mint.pubkey();
+        divvy
+            .create_mint(&clone_keypair(&divvy.usdt_mint), &payer)
+            .await;
+        divvy
+            .create_mint(&clone_keypair(&divvy.ht_mint), &pda)
+            .await;
+        for (account, owner) in [
+            (clone_keypair(&divvy.pool_usdt_account), pda),
+            (clone_keypair(&divvy.insurance_fund_usdt_account), payer),
+            (
+                clone_keypair(&divvy.divvy_foundation_proceeds_usdt_account),
+                payer,
+            ),
+        ]
+        .iter()
+        {
+            divvy.create_token_account(account, &usdt_mint, owner).await;
+        }
+        divvy
+    }
+
+    pub async fn process(
+        &mut self,
+        instructions: &[Instruction],
+        signers: &[&Keypair],
+    ) -> Result<(), TransactionError> {
+        let mut all_signers = vec![&self.payer];
+        all_signers.extend_from_slice(signers);
+        let transaction = Transaction::new_signed_with_payer(
+            instructions,
+            Some(&self.payer.pubkey()),
+            &all_signers,
+            self.recent_blockhash,
+        );
+        self.banks_client
+            .process_transaction(transaction)
+            .await
+            .map_err(|e| e.unwrap())
+    }
+
+    pub async fn create_account(&mut self, account: &Keypair, space: usize, owner: &Pubkey) {
+        let rent = self.banks_client.get_rent().await.unwrap();
+        let instruction = system_instruction::create_account(
+            &self.payer.pubkey(),
+            &account.pubkey(),
+            rent.minimum_balance(space),
+            space as u64,
+            owner,
+        );
+        self.process(&[instruction], &[account]).await.unwrap();
+    }
+
+    pub async fn create_mint(&mut self, mint: &Keypair, authority: &Pubkey) {
+        self.create_account(mint, Mint::LEN, &spl_token::id()).await;
+        let instruction = spl_token::instruction::initialize_mint(
+            &spl_token::id(),
+            &mint.pubkey(),
+            authority,
+            None,
+            6,
+        )
+        .unwrap();
+        self.process(&[instruction], &[]).await.unwrap();
+    }
+
+    pub async fn create_token_account(&mut self, account: &Keypair, mint: &Pubkey, owner: &Pubkey) {
+        self.create_account(account, TokenAccount::LEN, &spl_token::id())
+            .await;
+        let instruction = spl_token::instruction::initialize_account(
+            &spl_token::id(),
+            &account.pubkey(),
+            mint,
+            owner,
+        )
+        .unwrap();
+        self.process(&[instruction], &[]).await.unwrap();
+    }
+
+    /// Creates a user holding `usdt_amount` USDT and enough SOL for bet rent.
+    pub async fn create_user(&mut self, usdt_amount: u64) -> User {
+        let user = User {
+            keypair: Keypair::new(),
+            usdt_account: Keypair::new(),
+            ht_account: Keypair::new(),
+        };
+        let usdt_mint = self.usdt_mint.pubkey();
+        let ht_mint = self.ht_mint.pubkey();
+        self.create_token_account(&user.usdt_account, &usdt_mint, &user.keypair.pubkey())
+            .await;
+        self.create_token_account(&user.ht_account, &ht_mint, &user.keypair.pubkey())
+            .await;
+        let instruction = spl_token::instruction::mint_to(
+            &spl_token::id(),
+            &usdt_mint,
+            &user.usdt_account.pubkey(),
+            &self.payer.pubkey(),
+            &[],
+            usdt_amount,
+        )
+        .unwrap();
+        let fund = system_instruction::transfer(
+            &self.payer.pubkey(),
+            &user.keypair.pubkey(),
+            LAMPORTS_PER_SOL,
+        );
+        self.process(&[instruction, fund], &[]).await.unwrap();
+        user
+    }
+
+    pub async fn token_balance(&mut self, account: &Pubkey) -> u64 {
+        self.unpack::<TokenAccount>(account).await.amount
+    }
+
+    pub async fn unpack<T: Pack + IsInitialized>(&mut self, account: &Pubkey) -> T {
+        let account = self
+            .banks_client
+            .get_account(*account)
+            .await
+            .unwrap()
+            .unwrap();
+        T::unpack(&account.data).unwrap()
+    }
+
+    pub async fn account_exists(&mut self, account: &Pubkey) -> bool {
+        self.banks_client
+            .get_account(*account)
+            .await
+            .unwrap()
+            .map_or(false, |account| account.lamports > 0)
+    }
+
+    pub async fn deposit(&mut self, user: &User, usdt_amount: u64) -> Result<(), TransactionError> {
+        let instruction = instruction::deposit(
+            &self.program_id,
+            &user.keypair.pubkey(),
+            &self.ht_mint.pubkey(),
+            &user.ht_account.pubkey(),
+            &self.pda,
+            &user.usdt_account.pubkey(),
+            &self.pool_usdt_account.pubkey(),
+            &self.pool_state.pubkey(),
+            usdt_amount,
+            self.bump_seed,
+        )
+        .unwrap();
+        self.process(&[instruction], &[&user.keypair]).await
+    }
+
+    pub async fn withdraw(&mut self, user: &User, ht_amount: u64) -> Result<(), TransactionError> {
+        let instruction = instruction::withdraw(
+            &self.program_id,
+            &user.keypair.pubkey(),
+            &self.ht_mint.pubkey(),
+            &user.ht_account.pubkey(),
+            &self.pda,
+            &user.usdt_account.pubkey(),
+            &self.pool_usdt_account.pubkey(),
+            &self.pool_state.pubkey(),
+            ht_amount,
+            self.bump_seed,
+        )
+        .unwrap();
+        self.process(&[instruction], &[&user.keypair]).await
+    }
+
+    pub async fn init_market(
+        &mut self,
+        result_feed: &Pubkey,
+        odds_feeds: &[&Pubkey],
+        points_feeds: &[&Pubkey],
+        bet_type: BetType,
+    ) -> Result<Pubkey, TransactionError> {
+        let event_id = self.next_event_id;
+        self.next_event_id += 1;
+        let (market, _) = find_market_address(&self.program_id, event_id, bet_type);
+        let authority = clone_keypair(&self.authority);
+        let instruction = instruction::init_moneyline_market(
+            &self.program_id,
+            &authority.pubkey(),
+            &self.payer.pubkey(),
+            result_feed,
+            &self.pool_state.pubkey(),
+            odds_feeds,
+            points_feeds,
+            event_id,
+            bet_type,
+            OracleSource::Manual,
+            0,
+            0,
+            "TEST",
+            now() + 86_400,
+            &["Home", "Away", "Draw"][..odds_feeds.len()],
+            &[],
+        )
+        .unwrap();
+        self.process(&[instruction], &[&authority]).await?;
+        Ok(market)
+    }
+
+    pub async fn create_manual_feed(
+        &mut self,
+        value: i64,
+        expo: i32,
+    ) -> Result<Keypair, TransactionError> {
+        let feed = Keypair::new();
+        self.create_account(&feed, ManualFeed::LEN, &self.program_id.clone())
+            .await;
+        self.update_manual_feed(&feed.pubkey(), value, expo).await?;
+        Ok(feed)
+    }
+
+    pub async fn update_manual_feed(
+        &mut self,
+        feed: &Pubkey,
+        value: i64,
+        expo: i32,
+    ) -> Result<(), TransactionError> {
+        let authority = clone_keypair(&self.authority);
+        let instruction = instruction::update_manual_feed(
+            &self.program_id,
+            &authority.pubkey(),
+            feed,
+            &self.pool_state.pubkey(),
+            value,
+            expo,
+            &[],
+        )
+        .unwrap();
+        self.process(&[instruction], &[&authority]).await
+    }
+
+    pub async fn init_bet(
+        &mut self,
+        user: &User,
+        market: &Pubkey,
+        feed: &Pubkey,
+        risk: u64,
+        odds: i64,
+        market_side: u8,
+    ) -> Result<Pubkey, TransactionError> {
+        self.init_bet_with_points_feed(user, market, feed, None, risk, odds, market_side)
+            .await
+    }
+
+    pub async fn init_bet_with_points_feed(
+        &mut self,
+        user: &User,
+        market: &Pubkey,
+        feed: &Pubkey,
+        points_feed: Option<&Pubkey>,
+        risk: u64,
+        odds: i64,
+        market_side: u8,
+    ) -> Result<Pubkey, TransactionError> {
+        let nonce = self.next_nonce;
+        self.next_nonce += 1;
+        let (bet, _) = find_bet_address(&self.program_id, market, &user.keypair.pubkey(), nonce);
+        let instruction = instruction::init_bet(
+            &self.program_id,
+            &user.keypair.pubkey(),
+            feed,
+            points_feed,
+            market,
+            &self.pool_state.pubkey(),
+            &self.pool_usdt_account.pubkey(),
+            &user.usdt_account.pubkey(),
+            risk,
+            odds,
+            market_side,
+            0,
+            nonce,
+        )
+        .unwrap();
+        self.process(&[instruction], &[&user.keypair]).await?;
+        Ok(bet)
+    }
+
+    pub async fn commence_market(&mut self, market: &Pubkey) -> Result<(), TransactionError> {
+        let authority = clone_keypair(&self.authority);
+        let instruction = instruction::commence_market(
+            &self.program_id,
+            &authority.pubkey(),
+            market,
+            &self.pool_state.pubkey(),
+            &[],
+        )
+        .unwrap();
+        self.process(&[instruction], &[&authority]).await
+    }
+
+    pub async fn settle_market(
+        &mut self,
+        market: &Pubkey,
+        result_feed: &Pubkey,
+    ) -> Result<(), TransactionError> {
+        let authority = clone_keypair(&self.authority);
+        let instruction = instruction::settle_moneyline_market(
+            &self.program_id,
+            &authority.pubkey(),
+            market,
+            &self.pool_state.pubkey(),
+            result_feed,
+            &self.pda,
+            &self.pool_usdt_account.pubkey(),
+            &self.insurance_fund_usdt_account.pubkey(),
+            &self.divvy_foundation_proceeds_usdt_account.pubkey(),
+            self.bump_seed,
+        )
+        .unwrap();
+        self.process(&[instruction], &[&authority]).await
+    }
+
+    pub async fn void_market(&mut self, market: &Pubkey) -> Result<(), TransactionError> {
+        let authority = clone_keypair(&self.authority);
+        let instruction = instruction::void_market(
+            &self.program_id,
+            &authority.pubkey(),
+            market,
+            &self.pool_state.pubkey(),
+            &[],
+        )
+        .unwrap();
+        self.process(&[instruction], &[&authority]).await
+    }
+
+    pub async fn settle_bet(
+        &mut self,
+        user: &User,
+        market: &Pubkey,
+        bet: &Pubkey,
+    ) -> Result<(), TransactionError> {
+        let instruction = instruction::settle_bet(
+            &self.program_id,
+            &self.payer.pubkey(),
+            market,
+            bet,
+            &self.pda,
+            &self.pool_usdt_account.pubkey(),
+            &user.usdt_account.pubkey(),
+            &user.keypair.pubkey(),
+            &self.pool_state.pubkey(),
+            self.bump_seed,
+        )
+        .unwrap();
+        self.process(&[instruction], &[]).await
+    }
+}
+
+/// Boots the program with 1,000 USDT deposited in the house pool.
+pub async fn funded_divvy(program_test: ProgramTest, program_id: Pubkey) -> Divvy {
+    let mut divvy = Divvy::start(program_test, program_id).await;
+    let lp = divvy.create_user(1_000 * USDT).await;
+    divvy.deposit(&lp, 1_000 * USDT).await.unwrap();
+    divvy
+}
+
+fn clone_keypair(keypair: &Keypair) -> Keypair {
+    Keypair::from_bytes(&keypair.to_bytes()).unwrap()
+}
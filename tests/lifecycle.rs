@@ -0,0 +1,144 @@
+mod common;
+
+use common::{program_test, Divvy, USDT};
+use divvycommon::fees::{fee, DEFAULT_DIVVY_FOUNDATION_FEE_BPS, DEFAULT_INSURANCE_FUND_FEE_BPS};
+use divvyexchange::state::{BetType, HpLiquidity, Market, MoneylineMarketOutcome};
+use fixed::types::U64F64;
+use solana_program::pubkey::Pubkey;
+use solana_sdk::signature::Signer;
+use spl_token::state::Mint;
+
+#[tokio::test]
+async fn test_market_lifecycle() {
+    let program_id = Pubkey::new_unique();
+    let mut divvy = Divvy::start(program_test(&program_id), program_id).await;
+    let side_0_feed = divvy.create_manual_feed(-110, 0).await.unwrap().pubkey();
+    let side_1_feed = divvy.create_manual_feed(150, 0).await.unwrap().pubkey();
+    let side_2_feed = divvy.create_manual_feed(300, 0).await.unwrap().pubkey();
+    let result_feed = divvy.create_manual_feed(0, 0).await.unwrap().pubkey();
+
+    // Deposit
+    let lp = divvy.create_user(1_000 * USDT).await;
+    divvy.deposit(&lp, 1_000 * USDT).await.unwrap();
+    assert_eq!(divvy.token_balance(&lp.usdt_account.pubkey()).await, 0);
+    assert_eq!(
+        divvy.token_balance(&lp.ht_account.pubkey()).await,
+        1_000 * USDT
+    );
+    let pool_usdt = divvy.pool_usdt_account.pubkey();
+    assert_eq!(divvy.token_balance(&pool_usdt).await, 1_000 * USDT);
+
+    // InitMoneylineMarket
+    let market = divvy
+        .init_market(
+            &result_feed,
+            &[&side_0_feed, &side_1_feed, &side_2_feed],
+            &[],
+            BetType::MoneyLine,
+        )
+        .await
+        .unwrap();
+    let market_state: Market = divvy.unpack(&market).await;
+    assert!(market_state.result == MoneylineMarketOutcome::NotYetCommenced);
+    assert_eq!(market_state.result_feed, result_feed);
+
+    // Initbet
+    let winner = divvy.create_user(100 * USDT).await;
+    let loser = divvy.create_user(150 * USDT).await;
+    let winning_bet = divvy
+        .init_bet(&winner, &market, &side_0_feed, 100 * USDT, -110, 0)
+        .await
+        .unwrap();
+    let losing_bet = divvy
+        .init_bet(&loser, &market, &side_1_feed, 150 * USDT, 150, 1)
+        .await
+        .unwrap();
+
+    let market_state: Market = divvy.unpack(&market).await;
+    assert_eq!(market_state.market_sides[0].payout, 90_909_090);
+    assert_eq!(market_state.market_sides[1].payout, 225 * USDT);
+    assert_eq!(market_state.locked_liquidity, 125 * USDT);
+    assert_eq!(market_state.bettor_balance, 250 * USDT);
+    let pool_state: HpLiquidity = divvy.unpack(&divvy.pool_state.pubkey()).await;
+    assert_eq!(pool_state.locked_liquidity, 125 * USDT);
+    assert_eq!(pool_state.bettor_balance, 250 * USDT);
+    assert_eq!(pool_state.pending_bets, 2);
+    assert_eq!(divvy.token_balance(&pool_usdt).await, 1_250 * USDT);
+
+    // CommenceMarket
+    divvy.commence_market(&market).await.unwrap();
+    let market_state: Market = divvy.unpack(&market).await;
+    assert!(market_state.result == MoneylineMarketOutcome::Commenced);
+    let pool_state: HpLiquidity = divvy.unpack(&divvy.pool_state.pubkey()).await;
+    assert_eq!(pool_state.locked_liquidity, 0);
+    assert_eq!(pool_state.live_liquidity, 125 * USDT);
+
+    // SettleMoneylineMarket
+    divvy.settle_market(&market, &result_feed).await.unwrap();
+    let winner_balance = 100 * USDT + 90_909_090;
+    let house_profit = 250 * USDT - winner_balance;
+    let insurance_fund_fee = fee(house_profit, DEFAULT_INSURANCE_FUND_FEE_BPS);
+    let divvy_foundation_fee = fee(house_profit, DEFAULT_DIVVY_FOUNDATION_FEE_BPS);
+    let market_state: Market = divvy.unpack(&market).await;
+    assert!(market_state.result == MoneylineMarketOutcome::MarketSide0Won);
+    assert_eq!(market_state.locked_liquidity, 0);
+    assert_eq!(market_state.bettor_balance, winner_balance);
+    let pool_state: HpLiquidity = divvy.unpack(&divvy.pool_state.pubkey()).await;
+    assert_eq!(pool_state.live_liquidity, 0);
+    assert_eq!(pool_state.bettor_balance, winner_balance);
+    assert_eq!(
+        divvy
+            .token_balance(&divvy.insurance_fund_usdt_account.pubkey())
+            .await,
+        insurance_fund_fee
+    );
+    assert_eq!(
+        divvy
+            .token_balance(&divvy.divvy_foundation_proceeds_usdt_account.pubkey())
+            .await,
+        divvy_foundation_fee
+    );
+
+    // SettleBet
+    divvy
+        .settle_bet(&winner, &market, &winning_bet)
+        .await
+        .unwrap();
+    assert_eq!(
+        divvy.token_balance(&winner.usdt_account.pubkey()).await,
+        winner_balance
+    );
+    assert!(!divvy.account_exists(&winning_bet).await);
+    divvy
+        .settle_bet(&loser, &market, &losing_bet)
+        .await
+        .unwrap();
+    assert_eq!(divvy.token_balance(&loser.usdt_account.pubkey()).await, 0);
+    assert!(!divvy.account_exists(&losing_bet).await);
+
+    let market_state: Market = divvy.unpack(&market).await;
+    assert_eq!(market_state.pending_bets, 0);
+    assert_eq!(market_state.bettor_balance, 0);
+    let pool_state: HpLiquidity = divvy.unpack(&divvy.pool_state.pubkey()).await;
+    assert_eq!(pool_state.pending_bets, 0);
+    assert_eq!(pool_state.bettor_balance, 0);
+    let house_balance = 1_000 * USDT + house_profit - insurance_fund_fee - divvy_foundation_fee;
+    assert_eq!(divvy.token_balance(&pool_usdt).await, house_balance);
+
+    // Withdraw
+    divvy.withdraw(&lp, 1_000 * USDT).await.unwrap();
+    let withdrawn: u64 = (U64F64::from_num(house_balance) / U64F64::from_num(1_000 * USDT)
+        * U64F64::from_num(1_000 * USDT))
+    .to_num();
+    assert_eq!(
+        divvy.token_balance(&lp.usdt_account.pubkey()).await,
+        withdrawn
+    );
+    assert_eq!(divvy.token_balance(&lp.ht_account.pubkey()).await, 0);
+    assert_eq!(
+        divvy.token_balance(&pool_usdt).await,
+        house_balance - withdrawn
+    );
+    let ht_mint: Mint = divvy.unpack(&divvy.ht_mint.pubkey()).await;
+    assert_eq!(ht_mint.supply, 0);
+}
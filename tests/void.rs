@@ -0,0 +1,192 @@
+mod common;
+
+use common::{exchange_error, funded_divvy, program_test, USDT};
+use divvyexchange::{
+    error::ExchangeError,
+    state::{BetType, HpLiquidity, Market, MoneylineMarketOutcome, VOID_RESULT},
+};
+use solana_program::pubkey::Pubkey;
+use solana_sdk::signature::Signer;
+
+#[tokio::test]
+async fn test_void_moneyline_market() {
+    let program_id = Pubkey::new_unique();
+    let mut divvy = funded_divvy(program_test(&program_id), program_id).await;
+    let side_0_feed = divvy.create_manual_feed(-110, 0).await.unwrap().pubkey();
+    let side_1_feed = divvy.create_manual_feed(150, 0).await.unwrap().pubkey();
+    let side_2_feed = divvy.create_manual_feed(300, 0).await.unwrap().pubkey();
+    let result_feed = divvy.create_manual_feed(0, 0).await.unwrap().pubkey();
+    let market = divvy
+        .init_market(
+            &result_feed,
+            &[&side_0_feed, &side_1_feed, &side_2_feed],
+            &[],
+            BetType::MoneyLine,
+        )
+        .await
+        .unwrap();
+
+    let home = divvy.create_user(110 * USDT).await;
+    let away = divvy.create_user(100 * USDT).await;
+    let home_bet = divvy
+        .init_bet(&home, &market, &side_0_feed, 110 * USDT, -110, 0)
+        .await
+        .unwrap();
+    let away_bet = divvy
+        .init_bet(&away, &market, &side_1_feed, 100 * USDT, 150, 1)
+        .await
+        .unwrap();
+    divvy.commence_market(&market).await.unwrap();
+
+    // The game is cancelled
+    divvy
+        .update_manual_feed(&result_feed, VOID_RESULT as i64, 0)
+        .await
+        .unwrap();
+    divvy.settle_market(&market, &result_feed).await.unwrap();
+    let market_state: Market = divvy.unpack(&market).await;
+    assert!(market_state.result == MoneylineMarketOutcome::Void);
+    assert_eq!(market_state.bettor_balance, 210 * USDT);
+    assert_eq!(market_state.locked_liquidity, 0);
+    let pool_state: HpLiquidity = divvy.unpack(&divvy.pool_state.pubkey()).await;
+    assert_eq!(pool_state.live_liquidity, 0);
+    // No fees are taken
+    assert_eq!(
+        divvy
+            .token_balance(&divvy.insurance_fund_usdt_account.pubkey())
+            .await,
+        0
+    );
+    assert_eq!(
+        divvy
+            .token_balance(&divvy.divvy_foundation_proceeds_usdt_account.pubkey())
+            .await,
+        0
+    );
+
+    divvy.settle_bet(&home, &market, &home_bet).await.unwrap();
+    divvy.settle_bet(&away, &market, &away_bet).await.unwrap();
+    assert_eq!(
+        divvy.token_balance(&home.usdt_account.pubkey()).await,
+        110 * USDT
+    );
+    assert_eq!(
+        divvy.token_balance(&away.usdt_account.pubkey()).await,
+        100 * USDT
+    );
+    let market_state: Market = divvy.unpack(&market).await;
+    assert_eq!(market_state.bettor_balance, 0);
+    for market_side in market_state.market_sides.iter() {
+        assert_eq!(market_side.risk, 0);
+        assert_eq!(market_side.payout, 0);
+    }
+    assert_eq!(
+        divvy.token_balance(&divvy.pool_usdt_account.pubkey()).await,
+        1_000 * USDT
+    );
+}
+
+#[tokio::test]
+async fn test_void_spread_market() {
+    let program_id = Pubkey::new_unique();
+    let mut divvy = funded_divvy(program_test(&program_id), program_id).await;
+    let home_odds_feed = divvy.create_manual_feed(-110, 0).await.unwrap().pubkey();
+    let away_odds_feed = divvy.create_manual_feed(-110, 0).await.unwrap().pubkey();
+    let home_points_feed = divvy.create_manual_feed(-35, -1).await.unwrap().pubkey();
+    let away_points_feed = divvy.create_manual_feed(35, -1).await.unwrap().pubkey();
+    let result_feed = divvy
+        .create_manual_feed(VOID_RESULT as i64, 0)
+        .await
+        .unwrap()
+        .pubkey();
+    let market = divvy
+        .init_market(
+            &result_feed,
+            &[&home_odds_feed, &away_odds_feed],
+            &[&home_points_feed, &away_points_feed],
+            BetType::Spread,
+        )
+        .await
+        .unwrap();
+    let home = divvy.create_user(110 * USDT).await;
+    let home_bet = divvy
+        .init_bet_with_points_feed(
+            &home,
+            &market,
+            &home_odds_feed,
+            Some(&home_points_feed),
+            110 * USDT,
+            -110,
+            0,
+        )
+        .await
+        .unwrap();
+
+    divvy.commence_market(&market).await.unwrap();
+    divvy.settle_market(&market, &result_feed).await.unwrap();
+    let market_state: Market = divvy.unpack(&market).await;
+    assert!(market_state.result == MoneylineMarketOutcome::Void);
+    assert_eq!(market_state.bettor_balance, 110 * USDT);
+
+    divvy.settle_bet(&home, &market, &home_bet).await.unwrap();
+    assert_eq!(
+        divvy.token_balance(&home.usdt_account.pubkey()).await,
+        110 * USDT
+    );
+    assert!(!divvy.account_exists(&home_bet).await);
+    let market_state: Market = divvy.unpack(&market).await;
+    assert_eq!(market_state.bettor_balance, 0);
+    assert_eq!(market_state.market_sides[0].risk, 0);
+}
+
+#[tokio::test]
+async fn test_void_market_after_commence() {
+    let program_id = Pubkey::new_unique();
+    let mut divvy = funded_divvy(program_test(&program_id), program_id).await;
+    let side_0_feed = divvy.create_manual_feed(-110, 0).await.unwrap().pubkey();
+    let side_1_feed = divvy.create_manual_feed(150, 0).await.unwrap().pubkey();
+    let side_2_feed = divvy.create_manual_feed(300, 0).await.unwrap().pubkey();
+    let result_feed = divvy.create_manual_feed(0, 0).await.unwrap().pubkey();
+    let market = divvy
+        .init_market(
+            &result_feed,
+            &[&side_0_feed, &side_1_feed, &side_2_feed],
+            &[],
+            BetType::MoneyLine,
+        )
+        .await
+        .unwrap();
+    let away = divvy.create_user(100 * USDT).await;
+    let away_bet = divvy
+        .init_bet(&away, &market, &side_1_feed, 100 * USDT, 150, 1)
+        .await
+        .unwrap();
+    divvy.commence_market(&market).await.unwrap();
+
+    divvy.void_market(&market).await.unwrap();
+    let market_state: Market = divvy.unpack(&market).await;
+    assert!(market_state.result == MoneylineMarketOutcome::Void);
+    assert_eq!(market_state.locked_liquidity, 0);
+    let pool_state: HpLiquidity = divvy.unpack(&divvy.pool_state.pubkey()).await;
+    assert_eq!(pool_state.live_liquidity, 0);
+    // A voided market can not be settled from its result feed
+    assert_eq!(
+        divvy
+            .settle_market(&market, &result_feed)
+            .await
+            .unwrap_err(),
+        exchange_error(ExchangeError::MarketAlreadySettled)
+    );
+
+    divvy.settle_bet(&away, &market, &away_bet).await.unwrap();
+    assert_eq!(
+        divvy.token_balance(&away.usdt_account.pubkey()).await,
+        100 * USDT
+    );
+    let pool_state: HpLiquidity = divvy.unpack(&divvy.pool_state.pubkey()).await;
+    assert_eq!(pool_state.bettor_balance, 0);
+    assert_eq!(
+        divvy.token_balance(&divvy.pool_usdt_account.pubkey()).await,
+        1_000 * USDT
+    );
+}